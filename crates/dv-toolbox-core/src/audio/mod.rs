@@ -0,0 +1,46 @@
+//! Native decoding of the audio stored in DV frames, as defined in
+//! [IEC 61834-2](https://webstore.iec.ch/en/publication/5984) and other related standards.
+//!
+//! Audio samples are spread across the audio DIF blocks of a frame in a shuffled order, so that a
+//! dropout on the tape damages samples scattered throughout the frame instead of a contiguous run
+//! of them.  The [`read_frame_audio`] function reverses the shuffling and returns interleaved PCM
+//! samples for each stereo channel pair in the frame.  FFmpeg is not involved, so the exact
+//! number of samples in each frame is preserved, as are the error codes that a tape deck uses to
+//! mark lost samples.
+//!
+//! How the audio block channels (see [`crate::dif::AAUXPack::audio_block_channel`]) map to stereo
+//! channel pairs depends on the audio quantization.  With 16-bit audio, each DIF channel holds a
+//! single stereo channel pair: the left channel is stored in the first audio block channel, and
+//! the right channel is stored in the second audio block channel.  With 12-bit audio, which is
+//! commonly used by consumer camcorders at 32 kHz, each audio block channel holds a complete stereo
+//! channel pair: CH1/CH2 in the first one, and CH3/CH4 in the second one.  The 12-bit nonlinear
+//! samples are expanded to 16-bit linear samples.
+//!
+//! The [`AudioErrorReport`] structure builds on this to locate the samples that a tape deck
+//! marked as lost, and the frames whose audio could not be decoded at all, for the purpose of
+//! quality control.
+
+pub use pcm::*;
+pub use report::*;
+use snafu::prelude::*;
+
+mod pcm;
+mod report;
+mod shuffle;
+
+/// Result type for calls related to decoding audio.
+pub type AudioResult<T, E = AudioError> = std::result::Result<T, E>;
+
+/// Error type for when the audio in a DV frame could not be decoded.
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum AudioError {
+    #[snafu(whatever, display("{message}"))]
+    Whatever {
+        message: String,
+        #[snafu(source(from(Box<dyn std::error::Error>, Some)))]
+        source: Option<Box<dyn std::error::Error>>,
+        // There is intentionally not a backtrace here, since they are slow and we could encounter
+        // a lot of these errors when reading bad videotapes.
+    },
+}
@@ -0,0 +1,225 @@
+use snafu::prelude::*;
+
+use super::{
+    shuffle::{
+        locate_sample, sample_capacity, AUDIO_SAMPLES_OFFSET, AUDIO_SAMPLES_SIZE,
+        SAMPLE_SIZE_12_BIT, SAMPLE_SIZE_16_BIT,
+    },
+    AudioResult,
+};
+use crate::{dif, pack};
+
+#[cfg(test)]
+mod tests;
+
+/// Error code that a tape deck records in place of a lost 12-bit sample.
+const ERROR_CODE_12_BIT: u16 = 0x800;
+
+/// Audio samples of one stereo channel pair within a single frame.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct StereoPairAudio {
+    /// Index of the stereo channel pair within the frame: 0 for the first pair (CH1/CH2), 1 for
+    /// the second pair (CH3/CH4), and so on.
+    pub channel_pair: usize,
+
+    /// The consensus AAUX source pack that describes the audio.
+    pub source: pack::ValidPack<pack::AAUXSource>,
+
+    /// Interleaved PCM samples: left, right, left, right, and so on.
+    ///
+    /// There are exactly [`pack::AAUXSource::audio_frame_size`] samples for each of the two
+    /// channels.  Samples that the tape deck marked as lost are left as the error code that was
+    /// recorded in their place, which is `0x8000` ([`i16::MIN`]) for 16-bit audio.  The 12-bit
+    /// error code `0x800` is likewise converted to `0x8000`, and all other 12-bit samples are
+    /// expanded to 16 bits.
+    pub samples: Vec<i16>,
+}
+
+/// Read the audio samples of every stereo channel pair in the frame.
+///
+/// The samples are read directly out of the audio DIF blocks, according to the consensus AAUX
+/// source pack of each audio block channel.  The quantization of the first audio block channel of
+/// each DIF channel determines how the DIF channel is decoded:
+///
+/// - [`pack::AudioQuantization::Linear16Bit`]: both audio block channels together hold one stereo
+///   channel pair.
+/// - [`pack::AudioQuantization::NonLinear12Bit`]: each audio block channel holds its own stereo
+///   channel pair, so the DIF channel holds two of them.
+///
+/// An error is returned if a needed AAUX source pack is missing, or if it describes a
+/// quantization that is not supported, or more samples than the audio DIF blocks can hold.  The
+/// latter can happen when the consensus AAUX source pack was damaged by a dropout.
+pub fn read_frame_audio(frame: &dif::Frame) -> AudioResult<Vec<StereoPairAudio>> {
+    let metadata = dif::FrameMetadata::from_frame(frame);
+    let mut pairs = Vec::new();
+    for (channel_index, channel) in frame.channels.iter().enumerate() {
+        let first_audio_block_channel = channel_index * 2;
+        let source = consensus_source(&metadata, first_audio_block_channel)?;
+        match source.quantization {
+            pack::AudioQuantization::Linear16Bit => {
+                check_audio_frame_size(channel, &source, first_audio_block_channel)?;
+                pairs.push(StereoPairAudio {
+                    channel_pair: pairs.len(),
+                    source,
+                    samples: read_16_bit_samples(channel, source.audio_frame_size),
+                });
+            }
+            pack::AudioQuantization::NonLinear12Bit => {
+                for half in 0..2 {
+                    let audio_block_channel = first_audio_block_channel + half;
+                    let source = consensus_source(&metadata, audio_block_channel)?;
+                    check_audio_frame_size(channel, &source, audio_block_channel)?;
+                    pairs.push(StereoPairAudio {
+                        channel_pair: pairs.len(),
+                        source,
+                        samples: read_12_bit_samples(channel, half, source.audio_frame_size),
+                    });
+                }
+            }
+            quantization => {
+                whatever!("Audio quantization {quantization:?} is not supported")
+            }
+        }
+    }
+    Ok(pairs)
+}
+
+/// Returns the consensus AAUX source pack of the given audio block channel.
+fn consensus_source(
+    metadata: &dif::FrameMetadata,
+    audio_block_channel: usize,
+) -> AudioResult<pack::ValidPack<pack::AAUXSource>> {
+    match metadata.aaux_pack(audio_block_channel, pack::Type::AAUXSource) {
+        Some(pack::Pack::AAUXSource(source)) => Ok(*source),
+        _ => whatever!(
+            "No valid AAUX source pack was found for audio block channel CH{}",
+            audio_block_channel + 1
+        ),
+    }
+}
+
+/// Returns an error if the AAUX source pack of the audio block channel describes more samples than
+/// the audio DIF blocks of the DIF channel can hold with its quantization.
+fn check_audio_frame_size(
+    channel: &dif::Channel,
+    source: &pack::AAUXSource,
+    audio_block_channel: usize,
+) -> AudioResult<()> {
+    let sample_size = match source.quantization {
+        pack::AudioQuantization::NonLinear12Bit => SAMPLE_SIZE_12_BIT,
+        _ => SAMPLE_SIZE_16_BIT,
+    };
+    let capacity = sample_capacity(channel.dif_sequences.len() / 2, sample_size);
+    ensure_whatever!(
+        usize::from(source.audio_frame_size) <= capacity,
+        "AAUX source pack for audio block channel CH{} describes {} samples with {:?} \
+        quantization, but the audio DIF blocks can only hold {capacity}",
+        audio_block_channel + 1,
+        source.audio_frame_size,
+        source.quantization
+    );
+    Ok(())
+}
+
+/// Read 16-bit samples from a DIF channel, which holds the left channel in its first audio block
+/// channel and the right channel in its second audio block channel.
+fn read_16_bit_samples(channel: &dif::Channel, audio_frame_size: u16) -> Vec<i16> {
+    let sequences_per_channel = channel.dif_sequences.len() / 2;
+    let audio_frame_size = usize::from(audio_frame_size);
+    let mut samples = Vec::with_capacity(audio_frame_size * 2);
+    for sample in 0..audio_frame_size {
+        let location = locate_sample(sequences_per_channel, SAMPLE_SIZE_16_BIT, sample);
+        for half in 0..2 {
+            let block = &channel.dif_sequences
+                [half * sequences_per_channel + location.dif_sequence]
+                .audio[location.block];
+            // Samples are stored in big-endian order
+            samples.push(i16::from_be_bytes([
+                block.data[location.offset],
+                block.data[location.offset + 1],
+            ]));
+        }
+    }
+    samples
+}
+
+/// Read 12-bit samples from one audio block channel of a DIF channel, which holds both the left
+/// and right channels of a stereo channel pair.  `half` is 0 for the first audio block channel and
+/// 1 for the second one.
+fn read_12_bit_samples(channel: &dif::Channel, half: usize, audio_frame_size: u16) -> Vec<i16> {
+    let sequences_per_channel = channel.dif_sequences.len() / 2;
+    let audio_frame_size = usize::from(audio_frame_size);
+    let mut samples = Vec::with_capacity(audio_frame_size * 2);
+    for sample in 0..audio_frame_size {
+        let location = locate_sample(sequences_per_channel, SAMPLE_SIZE_12_BIT, sample);
+        let block = &channel.dif_sequences[half * sequences_per_channel + location.dif_sequence]
+            .audio[location.block];
+        let (left, right) = split_12_bit_group(
+            block.data[location.offset..location.offset + SAMPLE_SIZE_12_BIT].try_into().unwrap(),
+        );
+        samples.push(expand_12_bit_sample(left));
+        samples.push(expand_12_bit_sample(right));
+    }
+    samples
+}
+
+/// Split a group of two 12-bit samples into the left and right samples.
+///
+/// The most significant bits of each sample are stored in the first two bytes, and the least
+/// significant bits of both samples share the third byte.
+fn split_12_bit_group(group: &[u8; SAMPLE_SIZE_12_BIT]) -> (u16, u16) {
+    let [left_high, right_high, low] = group.map(u16::from);
+    (left_high << 4 | low >> 4, right_high << 4 | low & 0x0F)
+}
+
+/// Count the samples stored in an audio DIF block that hold the error code.
+///
+/// The samples are interpreted according to the quantization: 12-bit samples are checked for
+/// `0x800`, and samples of any other quantization are checked for the 16-bit `0x8000`.  Every
+/// sample position of the block is checked, so the count does not depend on the shuffling
+/// pattern.
+pub(crate) fn block_error_sample_count(
+    block: &dif::DIFBlock,
+    quantization: pack::AudioQuantization,
+) -> usize {
+    let samples = &block.data[AUDIO_SAMPLES_OFFSET..AUDIO_SAMPLES_OFFSET + AUDIO_SAMPLES_SIZE];
+    match quantization {
+        pack::AudioQuantization::NonLinear12Bit => samples
+            .chunks_exact(SAMPLE_SIZE_12_BIT)
+            .map(|group| {
+                let (left, right) = split_12_bit_group(group.try_into().unwrap());
+                usize::from(left == ERROR_CODE_12_BIT) + usize::from(right == ERROR_CODE_12_BIT)
+            })
+            .sum(),
+        _ => samples
+            .chunks_exact(SAMPLE_SIZE_16_BIT)
+            .filter(|sample| i16::from_be_bytes([sample[0], sample[1]]) == i16::MIN)
+            .count(),
+    }
+}
+
+/// Expand a 12-bit nonlinear sample to a 16-bit linear sample.
+///
+/// The 12-bit code is divided into segments, and each step within a segment further away from
+/// zero is worth twice as much as a step in the previous segment.  The segments closest to zero
+/// are not compressed at all.  The conversion is defined in IEC 61834-2.
+fn expand_12_bit_sample(sample: u16) -> i16 {
+    if sample == ERROR_CODE_12_BIT {
+        return i16::MIN;
+    }
+
+    // Sign-extend the 12-bit two's complement value
+    let sample = i32::from(sample) - if sample & 0x800 != 0 { 0x1000 } else { 0 };
+    let expanded = match sample >> 8 {
+        segment @ 2..=7 => {
+            let shift = segment - 1;
+            (sample - 256 * shift) << shift
+        }
+        segment @ -8..=-3 => {
+            let shift = -2 - segment;
+            ((sample + 256 * shift + 1) << shift) - 1
+        }
+        _ => sample,
+    };
+    i16::try_from(expanded).unwrap()
+}
@@ -0,0 +1,229 @@
+use arbitrary_int::{u2, u4};
+use display_error_chain::ErrorChainExt;
+use garde::Unvalidated;
+use googletest::prelude::*;
+use rstest::rstest;
+
+use super::*;
+use crate::{
+    audio::shuffle::{locate_sample, SAMPLE_SIZE_12_BIT, SAMPLE_SIZE_16_BIT},
+    file::testutil::{read_test_frames, SONY_GOOD_QUALITY},
+};
+
+/// AAUX source pack describing 48 kHz 16-bit audio in a 525-60 system.
+fn source_16_bit(audio_frame_size: u16) -> pack::AAUXSource {
+    pack::AAUXSource {
+        audio_sample_rate: 48_000,
+        quantization: pack::AudioQuantization::Linear16Bit,
+        audio_frame_size,
+        locked_mode: pack::LockedMode::Unlocked,
+        stereo_mode: pack::StereoMode::MultiStereoAudio,
+        audio_block_channel_count: 1,
+        audio_mode: u4::new(0x0),
+        audio_block_pairing: pack::AudioBlockPairing::Paired,
+        multi_language: false,
+        source_type: pack::SourceType::StandardDefinitionCompressedChroma,
+        field_count: 60,
+        emphasis_on: false,
+        emphasis_time_constant: pack::EmphasisTimeConstant::Emphasis50_15,
+        reserved: u2::new(0x3),
+    }
+}
+
+/// Replace the AAUX source packs and audio samples of the test file's first frame with the given
+/// 16-bit audio.
+fn frame_with_16_bit_audio(source: pack::AAUXSource, samples: &[i16]) -> dif::Frame {
+    let mut frame =
+        read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY).remove(0);
+    let ctx = frame.pack_context();
+    let source_pack =
+        pack::Pack::AAUXSource(Unvalidated::new(source).validate_with(&ctx).unwrap().into());
+    for (aaux_pack, _) in frame.aaux_packs() {
+        if aaux_pack.pack.pack_type() == pack::Type::AAUXSource {
+            frame.set_aaux_pack(&aaux_pack.position, &source_pack);
+        }
+    }
+
+    let channel = &mut frame.channels[0];
+    for (index, sample) in samples.iter().enumerate() {
+        let location = locate_sample(5, SAMPLE_SIZE_16_BIT, index / 2);
+        let block = &mut channel.dif_sequences[(index % 2) * 5 + location.dif_sequence].audio
+            [location.block];
+        block.data[location.offset..location.offset + 2].copy_from_slice(&sample.to_be_bytes());
+    }
+    frame
+}
+
+#[googletest::test]
+fn test_read_frame_audio_16_bit() {
+    // A distinct value for every sample, so that any misplaced sample would be noticed
+    let source = source_16_bit(1_601);
+    let samples: Vec<i16> =
+        (0..1_601 * 2).map(|i| i16::try_from(i * 7 - 11_000).unwrap()).collect();
+    let frame = frame_with_16_bit_audio(source, &samples);
+
+    let pairs = read_frame_audio(&frame).unwrap();
+    expect_that!(pairs.len(), eq(1));
+    expect_that!(pairs[0].channel_pair, eq(0));
+    expect_that!(**pairs[0].source, eq(source));
+    expect_that!(pairs[0].samples, eq(&samples));
+}
+
+#[googletest::test]
+fn test_read_frame_audio_16_bit_error_codes() {
+    // Error codes must be passed through without being altered
+    let source = source_16_bit(1_580);
+    let mut samples = vec![0x1234; 1_580 * 2];
+    samples[1] = i16::MIN;
+    samples[3_000] = i16::MIN;
+    let frame = frame_with_16_bit_audio(source, &samples);
+
+    let pairs = read_frame_audio(&frame).unwrap();
+    expect_that!(pairs[0].samples, eq(&samples));
+}
+
+#[googletest::test]
+fn test_read_frame_audio_unsupported_quantization() {
+    let mut source = source_16_bit(1_601);
+    source.quantization = pack::AudioQuantization::Linear20Bit;
+    let frame = frame_with_16_bit_audio(source, &[]);
+
+    expect_that!(
+        read_frame_audio(&frame).map_err(|e| e.chain().to_string()),
+        err(eq("Audio quantization Linear20Bit is not supported"))
+    );
+}
+
+#[googletest::test]
+fn test_read_frame_audio_missing_source() {
+    let mut frame =
+        read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY).remove(0);
+    let ctx = frame.pack_context();
+    let no_info = pack::Pack::from_raw(&[0xFF; 5], &ctx).0;
+    for (aaux_pack, _) in frame.aaux_packs() {
+        frame.set_aaux_pack(&aaux_pack.position, &no_info);
+    }
+
+    expect_that!(
+        read_frame_audio(&frame).map_err(|e| e.chain().to_string()),
+        err(eq("No valid AAUX source pack was found for audio block channel CH1"))
+    );
+}
+
+#[googletest::test]
+#[rstest]
+#[case::first_pair(0)]
+#[case::second_pair(1)]
+fn test_read_frame_audio_12_bit_too_many_samples(#[case] audio_block_channel: usize) {
+    // A damaged source pack can describe more 12-bit samples than the audio DIF blocks hold.
+    let mut frame =
+        read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY).remove(0);
+    let ctx = frame.pack_context();
+    let mut source = source_16_bit(1_601);
+    source.quantization = pack::AudioQuantization::NonLinear12Bit;
+    let source_pack =
+        pack::Pack::AAUXSource(Unvalidated::new(source).validate_with(&ctx).unwrap().into());
+    for (aaux_pack, _) in frame.aaux_packs() {
+        if aaux_pack.audio_block_channel == audio_block_channel
+            && aaux_pack.pack.pack_type() == pack::Type::AAUXSource
+        {
+            frame.set_aaux_pack(&aaux_pack.position, &source_pack);
+        }
+    }
+
+    expect_that!(
+        read_frame_audio(&frame).map_err(|e| e.chain().to_string()),
+        err(eq(&format!(
+            "AAUX source pack for audio block channel CH{} describes 1601 samples with \
+            NonLinear12Bit quantization, but the audio DIF blocks can only hold 1080",
+            audio_block_channel + 1
+        )))
+    );
+}
+
+#[googletest::test]
+fn test_read_frame_audio_12_bit() {
+    let frame =
+        read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY).remove(0);
+
+    // Both channel pairs are returned, even though CH3/CH4 were not recorded by the camcorder
+    let pairs = read_frame_audio(&frame).unwrap();
+    expect_that!(pairs.len(), eq(2));
+    for (index, pair) in pairs.iter().enumerate() {
+        expect_that!(pair.channel_pair, eq(index));
+        expect_that!(pair.source.quantization, eq(pack::AudioQuantization::NonLinear12Bit));
+        expect_that!(pair.samples.len(), eq(1_068 * 2));
+    }
+    expect_that!(pairs[0].source.audio_mode, eq(u4::new(0x0)));
+    expect_that!(
+        pairs[0].samples[..8],
+        eq(&[-1_029, -531, -1_073, -589, -1_201, -631, -1_301, -697])
+    );
+    expect_that!(pairs[1].source.audio_mode, eq(u4::new(0xF)));
+    expect_that!(pairs[1].samples, each(eq(&0)));
+}
+
+#[googletest::test]
+fn test_read_frame_audio_12_bit_layout() {
+    // Fill both audio block channels with distinct 12-bit codes, so that any misplaced sample
+    // would be noticed
+    let mut frame =
+        read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY).remove(0);
+    let codes: Vec<Vec<u16>> =
+        (0..2).map(|half| (0..1_068 * 2).map(|i| (i * 3 + half * 7) % 0x1000).collect()).collect();
+    let channel = &mut frame.channels[0];
+    for (half, codes) in codes.iter().enumerate() {
+        for (sample, code) in codes.chunks(2).enumerate() {
+            let location = locate_sample(5, SAMPLE_SIZE_12_BIT, sample);
+            let block =
+                &mut channel.dif_sequences[half * 5 + location.dif_sequence].audio[location.block];
+            block.data[location.offset..location.offset + 3].copy_from_slice(&[
+                u8::try_from(code[0] >> 4).unwrap(),
+                u8::try_from(code[1] >> 4).unwrap(),
+                u8::try_from((code[0] & 0x0F) << 4 | code[1] & 0x0F).unwrap(),
+            ]);
+        }
+    }
+
+    let pairs = read_frame_audio(&frame).unwrap();
+    for (pair, codes) in pairs.iter().zip(codes.iter()) {
+        let expected: Vec<_> = codes.iter().map(|c| expand_12_bit_sample(*c)).collect();
+        expect_that!(pair.samples, eq(&expected));
+    }
+}
+
+#[googletest::test]
+#[rstest]
+#[case::zero(0x000, 0)]
+#[case::positive_linear(0x1FF, 511)]
+#[case::positive_segment_2_start(0x200, 512)]
+#[case::positive_segment_2_end(0x2FF, 1_022)]
+#[case::positive_segment_3_start(0x300, 1_024)]
+#[case::positive_max(0x7FF, 32_704)]
+#[case::negative_linear(0xE00, -512)]
+#[case::minus_one(0xFFF, -1)]
+#[case::negative_segment_2_start(0xDFF, -513)]
+#[case::negative_segment_2_end(0xD00, -1_023)]
+#[case::negative_segment_3_start(0xCFF, -1_025)]
+#[case::negative_min(0x801, -32_641)]
+#[case::error_code(0x800, i16::MIN)]
+fn test_expand_12_bit_sample(#[case] sample: u16, #[case] expected: i16) {
+    expect_that!(expand_12_bit_sample(sample), eq(expected));
+}
+
+#[googletest::test]
+fn test_block_error_sample_count() {
+    let frame =
+        read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY).remove(0);
+    let mut block = frame.channels[0].dif_sequences[0].audio[0];
+    expect_that!(block_error_sample_count(&block, pack::AudioQuantization::NonLinear12Bit), eq(0));
+
+    // Left sample of the first group, and both samples of the last group.
+    block.data[5..8].copy_from_slice(&[0x80, 0x12, 0x03]);
+    block.data[74..77].copy_from_slice(&[0x80, 0x80, 0x00]);
+    expect_that!(block_error_sample_count(&block, pack::AudioQuantization::NonLinear12Bit), eq(3));
+
+    // As 16-bit samples, the last group holds one error sample, and another one is added.
+    block.data[9..11].copy_from_slice(&[0x80, 0x00]);
+    expect_that!(block_error_sample_count(&block, pack::AudioQuantization::Linear16Bit), eq(2));
+}
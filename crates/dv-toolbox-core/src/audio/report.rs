@@ -0,0 +1,145 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::{read_frame_audio, StereoPairAudio};
+use crate::dif;
+
+#[cfg(test)]
+mod tests;
+
+/// Locations of the samples in one stereo channel pair of a frame that hold the error code.
+///
+/// Tape decks record the error code in place of samples that could not be recovered from the
+/// tape: `0x8000` for 16-bit audio, and `0x800` for 12-bit audio.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct StereoPairErrors {
+    /// Index of the stereo channel pair within the frame; see [`StereoPairAudio::channel_pair`].
+    pub channel_pair: usize,
+
+    /// Indices of the error samples within the left channel, in ascending order.
+    pub left: Vec<usize>,
+
+    /// Indices of the error samples within the right channel, in ascending order.
+    pub right: Vec<usize>,
+}
+
+impl StereoPairErrors {
+    /// Locate the error samples in the decoded audio of a stereo channel pair.
+    pub fn from_audio(audio: &StereoPairAudio) -> Self {
+        let mut errors =
+            Self { channel_pair: audio.channel_pair, left: Vec::new(), right: Vec::new() };
+        for (index, sample) in audio.samples.iter().enumerate() {
+            if *sample == i16::MIN {
+                match index % 2 {
+                    0 => errors.left.push(index / 2),
+                    _ => errors.right.push(index / 2),
+                }
+            }
+        }
+        errors
+    }
+
+    /// Total number of error samples across both channels of the pair.
+    pub fn error_sample_count(&self) -> usize {
+        self.left.len() + self.right.len()
+    }
+}
+
+/// Error samples found in the audio of a single frame.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct FrameAudioErrors {
+    /// Zero-based index of the frame within the file.
+    pub frame_index: u64,
+
+    /// Error samples of each stereo channel pair in the frame, in channel pair order.
+    pub pairs: Vec<StereoPairErrors>,
+
+    /// Why the audio of the frame could not be decoded, if it could not be.  This usually happens
+    /// when a dropout destroyed every copy of the AAUX source pack.  There are no
+    /// [`FrameAudioErrors::pairs`] in that case.
+    pub unreadable: Option<String>,
+}
+
+impl FrameAudioErrors {
+    /// Decode the audio of the frame, and locate the error samples in every stereo channel pair.
+    ///
+    /// If the audio could not be decoded (see [`read_frame_audio`]), the reason is recorded in
+    /// [`FrameAudioErrors::unreadable`] instead.
+    pub fn from_frame(frame_index: u64, frame: &dif::Frame) -> Self {
+        match read_frame_audio(frame) {
+            Ok(audio) => Self {
+                frame_index,
+                pairs: audio.iter().map(StereoPairErrors::from_audio).collect(),
+                unreadable: None,
+            },
+            Err(err) => Self { frame_index, pairs: Vec::new(), unreadable: Some(err.to_string()) },
+        }
+    }
+
+    /// Total number of error samples across all stereo channel pairs of the frame.
+    pub fn error_sample_count(&self) -> usize {
+        self.pairs.iter().map(StereoPairErrors::error_sample_count).sum()
+    }
+}
+
+/// Report of the audio error samples across many frames, such as all the frames in a file.
+///
+/// Only frames that have at least one error sample, or whose audio could not be decoded at all,
+/// are kept in the report.  The [`fmt::Display`] implementation prints one line for each stereo
+/// channel pair that has error samples, such as `frame 1234, CH1/CH2, 412 error samples`, and one
+/// line for each frame whose audio is unreadable, such as `frame 1235, audio unreadable`.
+#[derive(Debug, PartialEq, Eq, Clone, Default, Serialize, Deserialize)]
+pub struct AudioErrorReport {
+    /// Frames that have at least one error sample or unreadable audio, in the order that they
+    /// were added.
+    pub frames: Vec<FrameAudioErrors>,
+}
+
+impl AudioErrorReport {
+    /// Creates an empty report.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Scan the audio of a frame for error samples, and add the frame to the report if any were
+    /// found, or if its audio could not be decoded; see [`FrameAudioErrors::from_frame`].
+    pub fn add_frame(&mut self, frame_index: u64, frame: &dif::Frame) {
+        let errors = FrameAudioErrors::from_frame(frame_index, frame);
+        if errors.error_sample_count() > 0 || errors.unreadable.is_some() {
+            self.frames.push(errors);
+        }
+    }
+
+    /// Total number of error samples across all frames in the report.
+    pub fn error_sample_count(&self) -> usize {
+        self.frames.iter().map(FrameAudioErrors::error_sample_count).sum()
+    }
+
+    /// Number of frames in the report whose audio could not be decoded.
+    pub fn unreadable_frame_count(&self) -> usize {
+        self.frames.iter().filter(|f| f.unreadable.is_some()).count()
+    }
+}
+
+impl fmt::Display for AudioErrorReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for frame in &self.frames {
+            if frame.unreadable.is_some() {
+                writeln!(f, "frame {}, audio unreadable", frame.frame_index)?;
+            }
+            for pair in frame.pairs.iter().filter(|p| p.error_sample_count() > 0) {
+                let count = pair.error_sample_count();
+                writeln!(
+                    f,
+                    "frame {}, CH{}/CH{}, {count} error {}",
+                    frame.frame_index,
+                    pair.channel_pair * 2 + 1,
+                    pair.channel_pair * 2 + 2,
+                    if count == 1 { "sample" } else { "samples" }
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,109 @@
+use googletest::prelude::*;
+
+use super::*;
+use crate::{
+    audio::shuffle::{locate_sample, SAMPLE_SIZE_12_BIT},
+    file::testutil::{read_test_frames, SONY_GOOD_QUALITY},
+    pack,
+};
+
+/// Overwrite one 12-bit sample of the test file with the error code.  `right` selects the right
+/// channel of the stereo channel pair instead of the left one.
+fn set_12_bit_error(frame: &mut dif::Frame, channel_pair: usize, sample: usize, right: bool) {
+    let location = locate_sample(5, SAMPLE_SIZE_12_BIT, sample);
+    let block = &mut frame.channels[0].dif_sequences[channel_pair * 5 + location.dif_sequence]
+        .audio[location.block];
+    let data = &mut block.data[location.offset..location.offset + 3];
+    if right {
+        data[1] = 0x80;
+        data[2] &= 0xF0;
+    } else {
+        data[0] = 0x80;
+        data[2] &= 0x0F;
+    }
+}
+
+#[googletest::test]
+fn test_audio_error_report_undamaged() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let mut report = AudioErrorReport::new();
+    for (index, frame) in frames.iter().enumerate() {
+        report.add_frame(u64::try_from(index).unwrap(), frame);
+    }
+
+    expect_that!(report.frames, empty());
+    expect_that!(report.error_sample_count(), eq(0));
+    expect_that!(report.to_string(), eq(""));
+}
+
+#[googletest::test]
+fn test_audio_error_report_damaged() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let mut damaged = frames[0].clone();
+    set_12_bit_error(&mut damaged, 0, 10, false);
+    set_12_bit_error(&mut damaged, 0, 10, true);
+    set_12_bit_error(&mut damaged, 0, 500, false);
+    set_12_bit_error(&mut damaged, 1, 3, true);
+
+    let mut report = AudioErrorReport::new();
+    report.add_frame(1_233, &frames[1]);
+    report.add_frame(1_234, &damaged);
+    report.add_frame(1_235, &frames[2]);
+
+    expect_that!(
+        report.frames,
+        eq(&vec![FrameAudioErrors {
+            frame_index: 1_234,
+            pairs: vec![
+                StereoPairErrors { channel_pair: 0, left: vec![10, 500], right: vec![10] },
+                StereoPairErrors { channel_pair: 1, left: vec![], right: vec![3] },
+            ],
+            unreadable: None,
+        }])
+    );
+    expect_that!(report.error_sample_count(), eq(4));
+    expect_that!(
+        report.to_string(),
+        eq("frame 1234, CH1/CH2, 3 error samples\nframe 1234, CH3/CH4, 1 error sample\n")
+    );
+}
+
+#[googletest::test]
+fn test_audio_error_report_unreadable() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let mut damaged = frames[0].clone();
+    let no_info = pack::Pack::from_raw(&[0xFF; 5], &damaged.pack_context()).0;
+    for (aaux_pack, _) in frames[0].aaux_packs() {
+        if aaux_pack.pack.pack_type() == pack::Type::AAUXSource {
+            damaged.set_pack(&dif::PackPosition::AAUX(aaux_pack.position), &no_info);
+        }
+    }
+
+    // The scan carries on past the frame that can't be decoded.
+    let mut report = AudioErrorReport::new();
+    report.add_frame(1_234, &damaged);
+    report.add_frame(1_235, &frames[1]);
+
+    expect_that!(
+        report.frames,
+        elements_are![matches_pattern!(FrameAudioErrors {
+            frame_index: eq(&1_234),
+            pairs: empty(),
+            unreadable: some(eq("No valid AAUX source pack was found for audio block channel CH1")),
+        })]
+    );
+    expect_that!(report.unreadable_frame_count(), eq(1));
+    expect_that!(report.to_string(), eq("frame 1234, audio unreadable\n"));
+}
+
+#[googletest::test]
+fn test_stereo_pair_errors_16_bit() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let mut audio = read_frame_audio(&frames[0]).unwrap().remove(0);
+    audio.samples = vec![0, i16::MIN, i16::MIN + 1, 0, i16::MIN, i16::MIN];
+
+    let errors = StereoPairErrors::from_audio(&audio);
+    expect_that!(errors.left, eq(&vec![2]));
+    expect_that!(errors.right, eq(&vec![0, 2]));
+    expect_that!(errors.error_sample_count(), eq(3));
+}
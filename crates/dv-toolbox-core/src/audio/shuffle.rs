@@ -0,0 +1,62 @@
+//! Implements the audio shuffling pattern that spreads audio samples across the audio DIF blocks
+//! of a frame.
+
+use crate::dif::AUDIO_BLOCK_COUNT;
+
+#[cfg(test)]
+mod tests;
+
+/// Offset of the first audio sample within the payload of an audio DIF block.  The AAUX pack
+/// comes before it.
+pub(crate) const AUDIO_SAMPLES_OFFSET: usize = 5;
+
+/// Number of bytes of audio samples within the payload of an audio DIF block.
+pub(crate) const AUDIO_SAMPLES_SIZE: usize = 72;
+
+/// Size in bytes of one 16-bit audio sample.
+pub(crate) const SAMPLE_SIZE_16_BIT: usize = 2;
+
+/// Size in bytes of one group of two 12-bit audio samples, one for each channel of a stereo pair.
+pub(crate) const SAMPLE_SIZE_12_BIT: usize = 3;
+
+/// Location of an audio sample within the audio DIF blocks of one audio block channel.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(crate) struct SampleLocation {
+    /// Index of the DIF sequence, relative to the first DIF sequence of the audio block channel.
+    pub(crate) dif_sequence: usize,
+
+    /// Index of the audio DIF block within the DIF sequence.
+    pub(crate) block: usize,
+
+    /// Offset of the sample within the payload of the audio DIF block.
+    pub(crate) offset: usize,
+}
+
+/// Maximum number of samples that fit in one audio block channel.
+///
+/// `sequences_per_channel` is the number of DIF sequences in the audio block channel: 5 for
+/// 525-60 systems and 6 for 625-50 systems.  `sample_size` is the number of bytes that each
+/// sample (or group of samples, for 12-bit audio) occupies.
+pub(crate) fn sample_capacity(sequences_per_channel: usize, sample_size: usize) -> usize {
+    AUDIO_SAMPLES_SIZE / sample_size * AUDIO_BLOCK_COUNT * sequences_per_channel
+}
+
+/// Locate the given sample number of an audio block channel within the audio DIF blocks.
+///
+/// The shuffling pattern is defined by equations in IEC 61834-2, which are equivalent to the
+/// shuffling tables given there.  Consecutive samples are spread across different DIF sequences
+/// and audio DIF blocks, and only samples that are far apart share the same audio DIF block.
+///
+/// `sequences_per_channel` and `sample_size` have the same meaning as in [`sample_capacity`].
+pub(crate) fn locate_sample(
+    sequences_per_channel: usize,
+    sample_size: usize,
+    sample: usize,
+) -> SampleLocation {
+    let samples_per_row = AUDIO_BLOCK_COUNT * sequences_per_channel;
+    SampleLocation {
+        dif_sequence: (sample / 3 + 2 * (sample % 3)) % sequences_per_channel,
+        block: 3 * (sample % 3) + (sample % samples_per_row) / (3 * sequences_per_channel),
+        offset: AUDIO_SAMPLES_OFFSET + sample_size * (sample / samples_per_row),
+    }
+}
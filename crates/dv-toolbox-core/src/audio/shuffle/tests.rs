@@ -0,0 +1,79 @@
+use std::collections::HashSet;
+
+use googletest::prelude::*;
+use rstest::rstest;
+
+use super::*;
+use crate::dif::DIF_BLOCK_DATA_SIZE;
+
+/// Number of the first sample stored in each audio DIF block of each DIF sequence of an audio
+/// block channel, for 525-60 systems.
+const SHUFFLE_TABLE_525_60: [[usize; AUDIO_BLOCK_COUNT]; 5] = [
+    [0, 15, 30, 10, 25, 40, 5, 20, 35],
+    [3, 18, 33, 13, 28, 43, 8, 23, 38],
+    [6, 21, 36, 1, 16, 31, 11, 26, 41],
+    [9, 24, 39, 4, 19, 34, 14, 29, 44],
+    [12, 27, 42, 7, 22, 37, 2, 17, 32],
+];
+
+/// Number of the first sample stored in each audio DIF block of each DIF sequence of an audio
+/// block channel, for 625-50 systems.
+const SHUFFLE_TABLE_625_50: [[usize; AUDIO_BLOCK_COUNT]; 6] = [
+    [0, 18, 36, 13, 31, 49, 8, 26, 44],
+    [3, 21, 39, 16, 34, 52, 11, 29, 47],
+    [6, 24, 42, 1, 19, 37, 14, 32, 50],
+    [9, 27, 45, 4, 22, 40, 17, 35, 53],
+    [12, 30, 48, 7, 25, 43, 2, 20, 38],
+    [15, 33, 51, 10, 28, 46, 5, 23, 41],
+];
+
+#[googletest::test]
+fn test_locate_sample_matches_shuffle_table() {
+    for (table, sequences) in [(&SHUFFLE_TABLE_525_60[..], 5), (&SHUFFLE_TABLE_625_50[..], 6)] {
+        for (dif_sequence, row) in table.iter().enumerate() {
+            for (block, first_sample) in row.iter().enumerate() {
+                expect_that!(
+                    locate_sample(sequences, SAMPLE_SIZE_16_BIT, *first_sample),
+                    eq(SampleLocation { dif_sequence, block, offset: AUDIO_SAMPLES_OFFSET })
+                );
+
+                // The following samples in the same audio DIF block are each one row later
+                expect_that!(
+                    locate_sample(sequences, SAMPLE_SIZE_16_BIT, first_sample + 9 * sequences),
+                    eq(SampleLocation {
+                        dif_sequence,
+                        block,
+                        offset: AUDIO_SAMPLES_OFFSET + SAMPLE_SIZE_16_BIT
+                    })
+                );
+            }
+        }
+    }
+}
+
+#[googletest::test]
+#[rstest]
+#[case::sys_525_60_16_bit(5, 2, 1620)]
+#[case::sys_625_50_16_bit(6, 2, 1944)]
+#[case::sys_525_60_12_bit(5, 3, 1080)]
+#[case::sys_625_50_12_bit(6, 3, 1296)]
+fn test_locate_sample_is_unique(
+    #[case] sequences: usize,
+    #[case] sample_size: usize,
+    #[case] expected_capacity: usize,
+) {
+    let capacity = sample_capacity(sequences, sample_size);
+    expect_that!(capacity, eq(expected_capacity));
+
+    // Every sample must occupy its own space within the audio DIF blocks
+    let locations: HashSet<_> = (0..capacity)
+        .map(|sample| {
+            let location = locate_sample(sequences, sample_size, sample);
+            expect_that!(location.dif_sequence, lt(sequences));
+            expect_that!(location.block, lt(AUDIO_BLOCK_COUNT));
+            expect_that!(location.offset + sample_size, le(DIF_BLOCK_DATA_SIZE));
+            (location.dif_sequence, location.block, location.offset)
+        })
+        .collect();
+    expect_that!(locations.len(), eq(capacity));
+}
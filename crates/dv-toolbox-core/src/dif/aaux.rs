@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Frame, PackArea};
+use crate::pack;
+
+#[cfg(test)]
+mod tests;
+
+/// Offset of the AAUX pack within the payload of an audio DIF block.
+const AAUX_PACK_OFFSET: usize = 0;
+
+/// Number of audio DIF blocks in the AAUX main area of each DIF sequence.
+const MAIN_AREA_PACK_COUNT: usize = 6;
+
+/// First audio DIF block of the AAUX main area in even-numbered DIF sequences.
+const EVEN_SEQUENCE_MAIN_AREA_START: usize = 3;
+
+/// First audio DIF block of the AAUX main area in odd-numbered DIF sequences.
+const ODD_SEQUENCE_MAIN_AREA_START: usize = 0;
+
+/// Location of an AAUX pack within a DV frame.
+///
+/// Every audio DIF block begins with a single AAUX pack, so the location is identified by the
+/// audio DIF block that holds it.
+///
+/// - IEC 61834-2:1998 - SD format for 525-60 and 625-50 systems
+/// - SMPTE 306M-2002 - 6.35-mm Type D-7 Component Format
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub struct AAUXPackPosition {
+    /// Index of the channel within the frame.
+    pub channel: usize,
+
+    /// Index of the DIF sequence within the channel.
+    pub dif_sequence: usize,
+
+    /// Index of the audio DIF block within the DIF sequence, in the range `[0, 8]`.
+    pub block: usize,
+}
+
+impl AAUXPackPosition {
+    /// Area of the AAUX packs that the audio DIF block belongs to.
+    ///
+    /// The main area consists of audio DIF blocks 3 through 8 in even-numbered DIF sequences, and
+    /// audio DIF blocks 0 through 5 in odd-numbered DIF sequences.  All other audio DIF blocks are
+    /// in the optional area.
+    pub fn area(&self) -> PackArea {
+        self.main_area_index().map_or(PackArea::Optional, |_| PackArea::Main)
+    }
+
+    /// Index of the pack within the main area, or None if it is in the optional area.
+    pub(super) fn main_area_index(&self) -> Option<usize> {
+        let main_area_start = if self.dif_sequence % 2 == 0 {
+            EVEN_SEQUENCE_MAIN_AREA_START
+        } else {
+            ODD_SEQUENCE_MAIN_AREA_START
+        };
+        self.block.checked_sub(main_area_start).filter(|index| *index < MAIN_AREA_PACK_COUNT)
+    }
+}
+
+/// A pack that was read from an audio DIF block, along with its location.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct AAUXPack {
+    /// Location of the pack within the frame.
+    pub position: AAUXPackPosition,
+
+    /// Index of the audio block channel that the audio DIF block belongs to: 0 for CH1, 1 for
+    /// CH2, and so on.
+    ///
+    /// The first half of the DIF sequences in a channel hold the first audio block channel, and
+    /// the second half hold the second one.  The audio block channels of the second channel of a
+    /// 50 mbps frame are numbered after those of the first channel.  How the audio block channels
+    /// map to stereo channel pairs depends on the audio quantization; see [`crate::audio`].
+    pub audio_block_channel: usize,
+
+    /// The pack stored in the audio DIF block.
+    pub pack: pack::Pack,
+}
+
+impl Frame {
+    /// Deserialize the AAUX pack at the start of every audio DIF block of the frame.
+    ///
+    /// There are 9 audio DIF blocks in each DIF sequence, and the packs are returned in the order
+    /// that they are stored in the file.  Each pack has the same meaning as the return value of
+    /// [`pack::Pack::from_raw`], and any pack parsing error is returned alongside it.
+    pub fn aaux_packs(&self) -> Vec<(AAUXPack, Option<pack::RawError>)> {
+        let ctx = self.pack_context();
+        let mut packs = Vec::new();
+        for (channel_index, channel) in self.channels.iter().enumerate() {
+            let sequences_per_half = channel.dif_sequences.len() / 2;
+            for (sequence_index, sequence) in channel.dif_sequences.iter().enumerate() {
+                for (block_index, block) in sequence.audio.iter().enumerate() {
+                    let (pack, err) = block.pack_at(AAUX_PACK_OFFSET, &ctx);
+                    packs.push((
+                        AAUXPack {
+                            position: AAUXPackPosition {
+                                channel: channel_index,
+                                dif_sequence: sequence_index,
+                                block: block_index,
+                            },
+                            audio_block_channel: channel_index * 2
+                                + sequence_index / sequences_per_half,
+                            pack,
+                        },
+                        err,
+                    ));
+                }
+            }
+        }
+        packs
+    }
+
+    /// Serialize the pack into the AAUX pack of the given audio DIF block of the frame.
+    ///
+    /// Only the bytes occupied by the pack are modified.  The function will panic if the position
+    /// does not exist in the frame.
+    pub fn set_aaux_pack(&mut self, position: &AAUXPackPosition, pack: &pack::Pack) {
+        let ctx = self.pack_context();
+        self.channels[position.channel].dif_sequences[position.dif_sequence].audio[position.block]
+            .set_pack_at(AAUX_PACK_OFFSET, pack, &ctx);
+    }
+}
@@ -0,0 +1,78 @@
+use googletest::prelude::*;
+use rstest::rstest;
+
+use super::*;
+use crate::{
+    file::testutil::{read_test_frames, SONY_GOOD_QUALITY},
+    testutil::*,
+};
+
+#[googletest::test]
+#[rstest]
+#[case::even_sequence_before_main(0, 2, PackArea::Optional)]
+#[case::even_sequence_main_start(0, 3, PackArea::Main)]
+#[case::even_sequence_main_end(8, 8, PackArea::Main)]
+#[case::odd_sequence_main_start(1, 0, PackArea::Main)]
+#[case::odd_sequence_main_end(9, 5, PackArea::Main)]
+#[case::odd_sequence_after_main(9, 6, PackArea::Optional)]
+fn test_aaux_pack_position_area(
+    #[case] dif_sequence: usize,
+    #[case] block: usize,
+    #[case] expected: PackArea,
+) {
+    let position = AAUXPackPosition { channel: 0, dif_sequence, block };
+    expect_that!(position.area(), eq(expected));
+}
+
+#[googletest::test]
+fn test_frame_aaux_packs() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let ctx = frames[0].pack_context();
+    let packs = frames[0].aaux_packs();
+    expect_that!(packs.len(), eq(10 * 9));
+    expect_that!(packs.iter().all(|(_, err)| err.is_none()), eq(true));
+
+    // The first half of the DIF sequences belong to the first audio block channel
+    for (pack, _) in &packs {
+        expect_that!(pack.audio_block_channel, eq(pack.position.dif_sequence / 5));
+    }
+
+    // Each audio block channel has its own copy of the source pack
+    let sources: Vec<_> =
+        packs.iter().filter(|(p, _)| p.pack.pack_type() == pack::Type::AAUXSource).collect();
+    expect_that!(sources.len(), eq(10));
+    expect_that!(
+        sources[0].0.position,
+        eq(AAUXPackPosition { channel: 0, dif_sequence: 0, block: 3 })
+    );
+    expect_that!(
+        sources[1].0.position,
+        eq(AAUXPackPosition { channel: 0, dif_sequence: 1, block: 0 })
+    );
+    expect_that!(sources[0].0.pack.to_raw(&ctx), eq(from_hex("50 CF 30 C0 D1")));
+    expect_that!(sources[9].0.audio_block_channel, eq(1));
+    expect_that!(sources[9].0.pack.to_raw(&ctx), eq(from_hex("50 CF 3F C0 D1")));
+}
+
+#[googletest::test]
+fn test_frame_set_aaux_pack() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let mut frame = frames[0].clone();
+    let position = AAUXPackPosition { channel: 0, dif_sequence: 1, block: 4 };
+    frame.set_aaux_pack(&position, &frames[0].aaux_packs()[9].0.pack);
+
+    // Only that one audio DIF block is affected
+    let updated = frame.aaux_packs();
+    for ((before, _), (after, _)) in frames[0].aaux_packs().iter().zip(updated.iter()) {
+        if before.position == position {
+            expect_that!(before.pack.pack_type(), eq(pack::Type::NoInfo));
+            expect_that!(after.pack.pack_type(), eq(pack::Type::AAUXSource));
+        } else {
+            expect_that!(after, eq(before));
+        }
+    }
+    expect_that!(
+        frame.channels[0].dif_sequences[1].audio[4].data[5..],
+        eq(&frames[0].channels[0].dif_sequences[1].audio[4].data[5..])
+    );
+}
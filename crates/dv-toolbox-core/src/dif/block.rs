@@ -0,0 +1,211 @@
+use arbitrary_int::{u1, u3, u4};
+use bitbybit::{bitenum, bitfield};
+use serde::{Deserialize, Serialize};
+
+use crate::pack;
+
+#[cfg(test)]
+mod tests;
+
+/// Size of a DIF block in bytes, including the ID.
+pub const DIF_BLOCK_SIZE: usize = 80;
+
+/// Size of the ID at the start of each DIF block, in bytes.
+pub const DIF_BLOCK_ID_SIZE: usize = 3;
+
+/// Size of the payload of a DIF block in bytes, excluding the ID.
+pub const DIF_BLOCK_DATA_SIZE: usize = DIF_BLOCK_SIZE - DIF_BLOCK_ID_SIZE;
+
+/// Raw bytes composing a DIF block, including the ID.
+pub type RawDIFBlock = [u8; DIF_BLOCK_SIZE];
+
+/// Raw bytes composing the ID of a DIF block.
+pub type RawDIFBlockID = [u8; DIF_BLOCK_ID_SIZE];
+
+/// Raw bytes composing the payload of a DIF block, excluding the ID.
+pub type DIFBlockData = [u8; DIF_BLOCK_DATA_SIZE];
+
+crate::pack::util::required_enum! {
+    /// Type of section that a DIF block belongs to.
+    #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+    #[allow(missing_docs)]
+    pub enum SectionType {
+        /// Header section: contains information about the entire DIF sequence.
+        Header = 0x0,
+
+        /// Subcode section: contains time codes and other information used for searching.
+        Subcode = 0x1,
+
+        /// Video auxiliary (VAUX) section: contains packs describing the video.
+        VAUX = 0x2,
+
+        /// Audio section: contains compressed audio samples, as well as audio auxiliary (AAUX)
+        /// packs describing the audio.
+        Audio = 0x3,
+
+        /// Video section: contains compressed video macroblocks.
+        Video = 0x4,
+
+        Reserved5 = 0x5,
+        Reserved6 = 0x6,
+        Reserved7 = 0x7,
+    }
+
+    #[bitenum(u3, exhaustive = true)]
+    enum RawSectionType;
+}
+
+/// Identifies the location of a DIF block within a DV frame.
+///
+/// Every DIF block begins with this ID.  Note that the ID is not protected by any error
+/// correction beyond what the tape deck does, so it's possible for dropouts to corrupt these
+/// values.
+///
+/// - IEC 61834-2:1998 - SD format for 525-60 and 625-50 systems
+/// - SMPTE 306M-2002 - 6.35-mm Type D-7 Component Format
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub struct DIFBlockID {
+    /// Type of section that this DIF block belongs to.
+    pub section_type: SectionType,
+
+    /// Sequence number, which is arbitrary for some section types.
+    ///
+    /// Consumer equipment typically records `0xF` in the header and subcode sections.
+    pub sequence_number: u4,
+
+    /// Number of the DIF sequence within the channel that this block belongs to.
+    ///
+    /// Valid values are in the range `[0, 9]` for 525-60 systems and `[0, 11]` for 625-50 systems.
+    pub dif_sequence_number: u4,
+
+    /// The FSC channel flag: 0 for the first channel, 1 for the second channel.
+    ///
+    /// Only 50 mbps formats have a second channel.
+    pub channel: u1,
+
+    /// Number of the DIF block within its section of the DIF sequence.
+    ///
+    /// For example, a video DIF block will have a number in the range `[0, 134]`.
+    pub dif_block_number: u8,
+
+    /// Reserved bit in the first ID byte; should normally be set to 0x1.
+    pub id0_reserved: u1,
+
+    /// Reserved bits in the second ID byte; should normally be set to 0x7.
+    pub id1_reserved: u3,
+}
+
+#[bitfield(u32)]
+struct RawDIFBlockIDFields {
+    // ID0
+    #[bits(0..=3, rw)]
+    seq: u4,
+    #[bit(4, rw)]
+    id0_reserved: u1,
+    #[bits(5..=7, rw)]
+    sct: RawSectionType,
+
+    // ID1
+    #[bits(8..=10, rw)]
+    id1_reserved: u3,
+    #[bit(11, rw)]
+    fsc: u1,
+    #[bits(12..=15, rw)]
+    dseq: u4,
+
+    // ID2
+    #[bits(16..=23, rw)]
+    dbn: u8,
+}
+
+impl DIFBlockID {
+    /// Deserialize the DIF block ID from the raw bytes at the start of a DIF block.
+    ///
+    /// Every possible combination of bits is representable, so this function cannot fail.
+    pub fn from_raw(raw: &RawDIFBlockID) -> Self {
+        let raw = RawDIFBlockIDFields::new_with_raw_value(u32::from_le_bytes([
+            raw[0], raw[1], raw[2], 0,
+        ]));
+        Self {
+            section_type: raw.sct().into(),
+            sequence_number: raw.seq(),
+            dif_sequence_number: raw.dseq(),
+            channel: raw.fsc(),
+            dif_block_number: raw.dbn(),
+            id0_reserved: raw.id0_reserved(),
+            id1_reserved: raw.id1_reserved(),
+        }
+    }
+
+    /// Serialize the DIF block ID to binary suitable for writing to a DV file.
+    pub fn to_raw(&self) -> RawDIFBlockID {
+        // The uppermost byte is not part of the ID, so the builder can't be used here.
+        let raw = RawDIFBlockIDFields::new_with_raw_value(0)
+            .with_seq(self.sequence_number)
+            .with_id0_reserved(self.id0_reserved)
+            .with_sct(self.section_type.into())
+            .with_id1_reserved(self.id1_reserved)
+            .with_fsc(self.channel)
+            .with_dseq(self.dif_sequence_number)
+            .with_dbn(self.dif_block_number)
+            .raw_value()
+            .to_le_bytes();
+        [raw[0], raw[1], raw[2]]
+    }
+}
+
+/// A single DIF block: the basic unit of a DV frame.
+///
+/// The block consists of an ID that identifies where the block belongs, followed by a payload
+/// whose interpretation depends on [`DIFBlockID::section_type`].
+///
+/// - IEC 61834-2:1998 - SD format for 525-60 and 625-50 systems
+/// - SMPTE 306M-2002 - 6.35-mm Type D-7 Component Format
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct DIFBlock {
+    /// Identifies the location of the DIF block within the frame.
+    pub id: DIFBlockID,
+
+    /// The 77-byte payload of the DIF block.
+    pub data: DIFBlockData,
+}
+
+impl DIFBlock {
+    /// Deserialize the DIF block from binary bytes obtained from a DV file.
+    ///
+    /// Every possible combination of bits is representable, so this function cannot fail.  The
+    /// original bytes are preserved exactly, so that [`DIFBlock::to_raw`] returns the same bytes.
+    pub fn from_raw(raw: &RawDIFBlock) -> Self {
+        Self {
+            id: DIFBlockID::from_raw(raw[..DIF_BLOCK_ID_SIZE].try_into().unwrap()),
+            data: raw[DIF_BLOCK_ID_SIZE..].try_into().unwrap(),
+        }
+    }
+
+    /// Serialize the DIF block to binary suitable for writing to a DV file.
+    pub fn to_raw(&self) -> RawDIFBlock {
+        let mut raw = [0; DIF_BLOCK_SIZE];
+        raw[..DIF_BLOCK_ID_SIZE].copy_from_slice(&self.id.to_raw());
+        raw[DIF_BLOCK_ID_SIZE..].copy_from_slice(&self.data);
+        raw
+    }
+
+    /// Deserialize the pack located at the given offset within [`DIFBlock::data`].
+    ///
+    /// The return value has the same meaning as the return value of [`pack::Pack::from_raw`].
+    pub fn pack_at(
+        &self,
+        offset: usize,
+        ctx: &pack::PackContext,
+    ) -> (pack::Pack, Option<pack::RawError>) {
+        pack::Pack::from_raw(self.data[offset..offset + 5].try_into().unwrap(), ctx)
+    }
+
+    /// Serialize the pack into the given offset within [`DIFBlock::data`].
+    ///
+    /// Only the bytes occupied by the pack are modified.  All other bytes in the block are left
+    /// untouched.
+    pub fn set_pack_at(&mut self, offset: usize, pack: &pack::Pack, ctx: &pack::PackContext) {
+        self.data[offset..offset + 5].copy_from_slice(&pack.to_raw(ctx));
+    }
+}
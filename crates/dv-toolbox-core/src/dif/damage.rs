@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+    DIFBlock, Frame, MacroblockHeader, MacroblockStatus, VideoBlockPosition, VIDEO_BLOCK_COUNT,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Condition of a single compressed video macroblock.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub enum MacroblockDamage {
+    /// The macroblock has no errors.
+    Good,
+
+    /// The tape deck concealed an error by substituting the macroblock from another frame; see
+    /// [`MacroblockStatus::is_concealed`].
+    Concealed,
+
+    /// The macroblock has an error that was not concealed, or its status is reserved.
+    Errored,
+
+    /// The video DIF block holding the macroblock is missing altogether.  Its payload is filled
+    /// with `0xFF` bytes, or it is a repeat of the previous video DIF block of the DIF sequence.
+    Missing,
+}
+
+impl MacroblockDamage {
+    /// Classify the video DIF block at the given index of a DIF sequence.
+    fn classify(video: &[DIFBlock; VIDEO_BLOCK_COUNT], block: usize) -> Self {
+        let current = &video[block];
+        let repeated = block > 0 && video[block - 1] == *current;
+        if repeated || current.data.iter().all(|b| *b == 0xFF) {
+            return Self::Missing;
+        }
+        let status = MacroblockHeader::from_raw(&current.data).status;
+        if status == MacroblockStatus::NoError {
+            Self::Good
+        } else if status.is_concealed() {
+            Self::Concealed
+        } else {
+            Self::Errored
+        }
+    }
+}
+
+/// Map of the condition of every compressed video macroblock in a single frame.
+///
+/// There are 1350 macroblocks per channel for 525-60 systems, and 1620 for 625-50 systems.  The
+/// map is laid out as a bitmap with one row for each DIF sequence of the frame, and one column
+/// for each video DIF block within the DIF sequence.  Rows of the second channel of a 50 mbps
+/// frame follow those of the first channel.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct DamageMap {
+    /// Number of DIF sequences in each channel of the frame.
+    pub dif_sequences_per_channel: usize,
+
+    /// Condition of each macroblock, in row-major order.  Each row has [`VIDEO_BLOCK_COUNT`]
+    /// entries.
+    pub macroblocks: Vec<MacroblockDamage>,
+}
+
+impl DamageMap {
+    /// Build the damage map of the frame from its video DIF blocks.
+    pub fn from_frame(frame: &Frame) -> Self {
+        let mut macroblocks = Vec::new();
+        for channel in &frame.channels {
+            for sequence in &channel.dif_sequences {
+                macroblocks.extend(
+                    (0..VIDEO_BLOCK_COUNT).map(|b| MacroblockDamage::classify(&sequence.video, b)),
+                );
+            }
+        }
+        Self {
+            dif_sequences_per_channel: frame.channels.first().map_or(0, |c| c.dif_sequences.len()),
+            macroblocks,
+        }
+    }
+
+    /// Returns the condition of the macroblock at the given position.
+    ///
+    /// The function will panic if the position does not exist in the frame.
+    pub fn get(&self, position: &VideoBlockPosition) -> MacroblockDamage {
+        assert!(position.dif_sequence < self.dif_sequences_per_channel);
+        assert!(position.block < VIDEO_BLOCK_COUNT);
+        let row = position.channel * self.dif_sequences_per_channel + position.dif_sequence;
+        self.macroblocks[row * VIDEO_BLOCK_COUNT + position.block]
+    }
+
+    /// Iterate over the rows of the bitmap.  Each row holds the macroblocks of one DIF sequence.
+    pub fn rows(&self) -> impl Iterator<Item = &[MacroblockDamage]> {
+        self.macroblocks.chunks_exact(VIDEO_BLOCK_COUNT)
+    }
+
+    /// Count the macroblocks of each condition.
+    pub fn summary(&self) -> DamageSummary {
+        let mut summary = DamageSummary::default();
+        for macroblock in &self.macroblocks {
+            match macroblock {
+                MacroblockDamage::Good => summary.good += 1,
+                MacroblockDamage::Concealed => summary.concealed += 1,
+                MacroblockDamage::Errored => summary.errored += 1,
+                MacroblockDamage::Missing => summary.missing += 1,
+            }
+        }
+        summary
+    }
+}
+
+/// Number of macroblocks of each condition in a [`DamageMap`].
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct DamageSummary {
+    /// Number of [`MacroblockDamage::Good`] macroblocks.
+    pub good: usize,
+
+    /// Number of [`MacroblockDamage::Concealed`] macroblocks.
+    pub concealed: usize,
+
+    /// Number of [`MacroblockDamage::Errored`] macroblocks.
+    pub errored: usize,
+
+    /// Number of [`MacroblockDamage::Missing`] macroblocks.
+    pub missing: usize,
+}
+
+impl DamageSummary {
+    /// Total number of macroblocks.
+    pub fn total(&self) -> usize {
+        self.good + self.concealed + self.errored + self.missing
+    }
+
+    /// Percentage of macroblocks that are not [`MacroblockDamage::Good`], in the range
+    /// `[0, 100]`.  Zero is returned if there are no macroblocks at all.
+    pub fn damaged_percentage(&self) -> f64 {
+        match self.total() {
+            0 => 0.0,
+            total => (total - self.good) as f64 * 100.0 / total as f64,
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use googletest::prelude::*;
+use serde_test::Token;
+
+use super::*;
+use crate::file::testutil::{read_test_frames, SONY_GOOD_QUALITY};
+
+#[googletest::test]
+fn test_damage_map_undamaged() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let map = DamageMap::from_frame(&frames[0]);
+
+    expect_that!(map.dif_sequences_per_channel, eq(10));
+    expect_that!(map.macroblocks.len(), eq(1_350));
+    expect_that!(map.rows().count(), eq(10));
+    expect_that!(map.macroblocks, each(eq(&MacroblockDamage::Good)));
+    expect_that!(
+        map.summary(),
+        eq(DamageSummary { good: 1_350, concealed: 0, errored: 0, missing: 0 })
+    );
+    expect_that!(map.summary().damaged_percentage(), eq(0.0));
+}
+
+#[googletest::test]
+fn test_damage_map_damaged() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let mut frame = frames[0].clone();
+    let sequence = &mut frame.channels[0].dif_sequences[3];
+
+    // Concealed and errored macroblocks, according to their status
+    sequence.video[0].data[0] = sequence.video[0].data[0] & 0x0F | 0x40;
+    sequence.video[1].data[0] = sequence.video[1].data[0] & 0x0F | 0x70;
+    sequence.video[2].data[0] = sequence.video[2].data[0] & 0x0F | 0x10;
+
+    // Missing macroblocks: filled with 0xFF, or repeated from the previous block
+    sequence.video[50].data.fill(0xFF);
+    sequence.video[81] = sequence.video[80];
+    sequence.video[82] = sequence.video[80];
+
+    let map = DamageMap::from_frame(&frame);
+    let position = |block| VideoBlockPosition { channel: 0, dif_sequence: 3, block };
+    expect_that!(map.get(&position(0)), eq(MacroblockDamage::Concealed));
+    expect_that!(map.get(&position(1)), eq(MacroblockDamage::Errored));
+    expect_that!(map.get(&position(2)), eq(MacroblockDamage::Errored));
+    expect_that!(map.get(&position(3)), eq(MacroblockDamage::Good));
+    expect_that!(map.get(&position(50)), eq(MacroblockDamage::Missing));
+    expect_that!(map.get(&position(80)), eq(MacroblockDamage::Good));
+    expect_that!(map.get(&position(81)), eq(MacroblockDamage::Missing));
+    expect_that!(map.get(&position(82)), eq(MacroblockDamage::Missing));
+    expect_that!(
+        map.get(&VideoBlockPosition { channel: 0, dif_sequence: 4, block: 0 }),
+        eq(MacroblockDamage::Good)
+    );
+    expect_that!(map.rows().nth(3).unwrap()[50], eq(MacroblockDamage::Missing));
+
+    let summary = map.summary();
+    expect_that!(summary, eq(DamageSummary { good: 1_344, concealed: 1, errored: 2, missing: 3 }));
+    expect_that!(summary.total(), eq(1_350));
+    expect_that!(summary.damaged_percentage(), near(6.0 * 100.0 / 1_350.0, 1e-9));
+}
+
+#[googletest::test]
+fn test_damage_summary_empty() {
+    expect_that!(DamageSummary::default().damaged_percentage(), eq(0.0));
+}
+
+#[googletest::test]
+fn test_damage_map_serde() {
+    let map = DamageMap {
+        dif_sequences_per_channel: 10,
+        macroblocks: vec![MacroblockDamage::Good, MacroblockDamage::Missing],
+    };
+    serde_test::assert_tokens(
+        &map,
+        &[
+            Token::Struct { name: "DamageMap", len: 2 },
+            Token::Str("dif_sequences_per_channel"),
+            Token::U64(10),
+            Token::Str("macroblocks"),
+            Token::Seq { len: Some(2) },
+            Token::UnitVariant { name: "MacroblockDamage", variant: "Good" },
+            Token::UnitVariant { name: "MacroblockDamage", variant: "Missing" },
+            Token::SeqEnd,
+            Token::StructEnd,
+        ],
+    );
+}
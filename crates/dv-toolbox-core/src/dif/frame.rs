@@ -0,0 +1,259 @@
+use serde::{Deserialize, Serialize};
+
+use super::{DIFBlock, RawDIFBlock, SectionType, DIF_BLOCK_SIZE};
+use crate::{
+    file::{self, ValidInfoMethods},
+    pack,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Number of header DIF blocks in each DIF sequence.
+pub const HEADER_BLOCK_COUNT: usize = 1;
+
+/// Number of subcode DIF blocks in each DIF sequence.
+pub const SUBCODE_BLOCK_COUNT: usize = 2;
+
+/// Number of VAUX DIF blocks in each DIF sequence.
+pub const VAUX_BLOCK_COUNT: usize = 3;
+
+/// Number of audio DIF blocks in each DIF sequence.
+pub const AUDIO_BLOCK_COUNT: usize = 9;
+
+/// Number of video DIF blocks in each DIF sequence.
+pub const VIDEO_BLOCK_COUNT: usize = 135;
+
+/// Total number of DIF blocks in each DIF sequence.
+pub const DIF_SEQUENCE_BLOCK_COUNT: usize = HEADER_BLOCK_COUNT
+    + SUBCODE_BLOCK_COUNT
+    + VAUX_BLOCK_COUNT
+    + AUDIO_BLOCK_COUNT
+    + VIDEO_BLOCK_COUNT;
+
+/// Size of a DIF sequence in bytes.
+pub const DIF_SEQUENCE_SIZE: usize = DIF_SEQUENCE_BLOCK_COUNT * DIF_BLOCK_SIZE;
+
+/// Raw bytes composing a DIF sequence.
+pub type RawDIFSequence = [u8; DIF_SEQUENCE_SIZE];
+
+/// Identifies which section of a DIF sequence a DIF block position belongs to, along with the
+/// index of the block within that section.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[allow(clippy::upper_case_acronyms)]
+enum BlockPosition {
+    Header,
+    Subcode(usize),
+    VAUX(usize),
+    Audio(usize),
+    Video(usize),
+}
+
+impl BlockPosition {
+    /// Determine which section a DIF block belongs to, given the index of the block within the
+    /// DIF sequence as it is stored in the file.
+    ///
+    /// The sections are interleaved: after the header, subcode, and VAUX sections, each audio
+    /// block is followed by 15 video blocks.
+    fn from_sequence_index(index: usize) -> Self {
+        match index {
+            0 => Self::Header,
+            1..=2 => Self::Subcode(index - 1),
+            3..=5 => Self::VAUX(index - 3),
+            _ => {
+                let index = index - 6;
+                match index % 16 {
+                    0 => Self::Audio(index / 16),
+                    v => Self::Video(index / 16 * 15 + v - 1),
+                }
+            }
+        }
+    }
+}
+
+/// Location of a DIF block of any section within a DV frame.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub struct DIFBlockPosition {
+    /// Index of the channel within the frame.
+    pub channel: usize,
+
+    /// Index of the DIF sequence within the channel.
+    pub dif_sequence: usize,
+
+    /// Section of the DIF sequence that the DIF block belongs to.
+    pub section: SectionType,
+
+    /// Index of the DIF block within its section; see [`DIFSequence::section`].
+    pub block: usize,
+}
+
+/// A single DIF sequence: the set of DIF blocks that are recorded in a single track on the tape.
+///
+/// The DIF blocks are split up by the section they belong to.  Every section has a fixed number
+/// of DIF blocks, and the blocks are always placed according to their position in the file.  The
+/// IDs within each block are not used to determine the placement, since a dropout could have
+/// damaged them.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct DIFSequence {
+    /// Header section, with information about the entire DIF sequence.
+    pub header: DIFBlock,
+
+    /// Subcode section, with time codes and other information used for searching.
+    pub subcode: [DIFBlock; SUBCODE_BLOCK_COUNT],
+
+    /// Video auxiliary (VAUX) section, with packs describing the video.
+    pub vaux: [DIFBlock; VAUX_BLOCK_COUNT],
+
+    /// Audio section, with audio samples and audio auxiliary (AAUX) packs.
+    pub audio: [DIFBlock; AUDIO_BLOCK_COUNT],
+
+    /// Video section, with compressed video macroblocks.
+    pub video: [DIFBlock; VIDEO_BLOCK_COUNT],
+}
+
+impl DIFSequence {
+    /// Deserialize the DIF sequence from binary bytes obtained from a DV file.
+    pub fn from_raw(raw: &RawDIFSequence) -> Self {
+        let block = |index: usize| {
+            let start = index * DIF_BLOCK_SIZE;
+            DIFBlock::from_raw(
+                <&RawDIFBlock>::try_from(&raw[start..start + DIF_BLOCK_SIZE]).unwrap(),
+            )
+        };
+        Self {
+            header: block(0),
+            subcode: std::array::from_fn(|i| block(1 + i)),
+            vaux: std::array::from_fn(|i| block(3 + i)),
+            audio: std::array::from_fn(|i| block(6 + i * 16)),
+            video: std::array::from_fn(|i| block(6 + (i / 15) * 16 + i % 15 + 1)),
+        }
+    }
+
+    /// The DIF blocks of the given section, in the order that they are stored in the file.
+    ///
+    /// Reserved section types have no DIF blocks, so an empty slice is returned for them.
+    pub fn section(&self, section: SectionType) -> &[DIFBlock] {
+        match section {
+            SectionType::Header => std::slice::from_ref(&self.header),
+            SectionType::Subcode => &self.subcode,
+            SectionType::VAUX => &self.vaux,
+            SectionType::Audio => &self.audio,
+            SectionType::Video => &self.video,
+            SectionType::Reserved5 | SectionType::Reserved6 | SectionType::Reserved7 => &[],
+        }
+    }
+
+    /// Mutable access to the DIF blocks of the given section; see [`DIFSequence::section`].
+    pub fn section_mut(&mut self, section: SectionType) -> &mut [DIFBlock] {
+        match section {
+            SectionType::Header => std::slice::from_mut(&mut self.header),
+            SectionType::Subcode => &mut self.subcode,
+            SectionType::VAUX => &mut self.vaux,
+            SectionType::Audio => &mut self.audio,
+            SectionType::Video => &mut self.video,
+            SectionType::Reserved5 | SectionType::Reserved6 | SectionType::Reserved7 => &mut [],
+        }
+    }
+
+    /// Serialize the DIF sequence to binary suitable for writing to a DV file.
+    pub fn to_raw(&self) -> RawDIFSequence {
+        let mut raw = [0; DIF_SEQUENCE_SIZE];
+        for (index, chunk) in raw.chunks_exact_mut(DIF_BLOCK_SIZE).enumerate() {
+            let block = match BlockPosition::from_sequence_index(index) {
+                BlockPosition::Header => &self.header,
+                BlockPosition::Subcode(i) => &self.subcode[i],
+                BlockPosition::VAUX(i) => &self.vaux[i],
+                BlockPosition::Audio(i) => &self.audio[i],
+                BlockPosition::Video(i) => &self.video[i],
+            };
+            chunk.copy_from_slice(&block.to_raw());
+        }
+        raw
+    }
+}
+
+/// A single channel of a DV frame.
+///
+/// 25 mbps formats have one channel per frame, while 50 mbps formats have two.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Channel {
+    /// The DIF sequences in the channel.  There are 10 sequences for 525-60 systems, and 12
+    /// sequences for 625-50 systems.
+    pub dif_sequences: Vec<DIFSequence>,
+}
+
+/// A single DV frame, split up into its channels and DIF sequences.
+#[derive(Debug, Clone)]
+pub struct Frame {
+    /// Information about the file that the frame was obtained from.
+    pub file_info: file::ValidInfo,
+
+    /// The channels in the frame.
+    pub channels: Vec<Channel>,
+}
+
+impl PartialEq for Frame {
+    fn eq(&self, other: &Self) -> bool {
+        *self.file_info == *other.file_info && self.channels == other.channels
+    }
+}
+
+impl Eq for Frame {}
+
+impl Frame {
+    /// Deserialize the frame from binary bytes obtained from a DV file.
+    ///
+    /// The length of `raw` must be exactly [`file::ValidInfoMethods::video_frame_size`] bytes.
+    /// The function will panic otherwise.
+    pub fn from_raw(raw: &[u8], file_info: &file::ValidInfo) -> Self {
+        assert_eq!(
+            raw.len(),
+            usize::try_from(file_info.video_frame_size()).unwrap(),
+            "raw frame data has the wrong length"
+        );
+        let sequence_count = usize::from(file_info.video_frame_dif_sequence_count());
+        Self {
+            file_info: *file_info,
+            channels: raw
+                .chunks_exact(sequence_count * DIF_SEQUENCE_SIZE)
+                .map(|channel| Channel {
+                    dif_sequences: channel
+                        .chunks_exact(DIF_SEQUENCE_SIZE)
+                        .map(|sequence| DIFSequence::from_raw(sequence.try_into().unwrap()))
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Serialize the frame to binary suitable for writing to a DV file.
+    pub fn to_raw(&self) -> Vec<u8> {
+        let mut raw =
+            Vec::with_capacity(usize::try_from(self.file_info.video_frame_size()).unwrap());
+        for channel in &self.channels {
+            for sequence in &channel.dif_sequences {
+                raw.extend_from_slice(&sequence.to_raw());
+            }
+        }
+        raw
+    }
+
+    /// Returns the DIF block at the given position.
+    ///
+    /// The function will panic if the position does not exist in the frame.
+    pub fn block(&self, position: &DIFBlockPosition) -> &DIFBlock {
+        &self.channels[position.channel].dif_sequences[position.dif_sequence]
+            .section(position.section)[position.block]
+    }
+
+    /// Mutable access to the DIF block at the given position; see [`Frame::block`].
+    pub fn block_mut(&mut self, position: &DIFBlockPosition) -> &mut DIFBlock {
+        &mut self.channels[position.channel].dif_sequences[position.dif_sequence]
+            .section_mut(position.section)[position.block]
+    }
+
+    /// Context to use when serializing and deserializing packs contained within this frame.
+    pub fn pack_context(&self) -> pack::PackContext {
+        pack::PackContext { file_info: self.file_info }
+    }
+}
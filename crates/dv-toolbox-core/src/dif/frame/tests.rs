@@ -0,0 +1,77 @@
+use googletest::prelude::*;
+
+use super::*;
+use crate::{file::testutil::SONY_GOOD_QUALITY, testutil::*};
+
+#[googletest::test]
+fn test_block_position() {
+    let positions: Vec<_> =
+        (0..DIF_SEQUENCE_BLOCK_COUNT).map(BlockPosition::from_sequence_index).collect();
+
+    expect_that!(positions[0], eq(BlockPosition::Header));
+    expect_that!(positions[1], eq(BlockPosition::Subcode(0)));
+    expect_that!(positions[2], eq(BlockPosition::Subcode(1)));
+    expect_that!(positions[3], eq(BlockPosition::VAUX(0)));
+    expect_that!(positions[5], eq(BlockPosition::VAUX(2)));
+    expect_that!(positions[6], eq(BlockPosition::Audio(0)));
+    expect_that!(positions[7], eq(BlockPosition::Video(0)));
+    expect_that!(positions[21], eq(BlockPosition::Video(14)));
+    expect_that!(positions[22], eq(BlockPosition::Audio(1)));
+    expect_that!(positions[23], eq(BlockPosition::Video(15)));
+    expect_that!(positions[134], eq(BlockPosition::Audio(8)));
+    expect_that!(positions[149], eq(BlockPosition::Video(134)));
+}
+
+#[googletest::test]
+fn test_dif_sequence_section() {
+    let data = std::fs::read(test_resource("dv_multiframe/sony_good_quality.dv")).unwrap();
+    let frame_size = usize::try_from(SONY_GOOD_QUALITY.video_frame_size()).unwrap();
+    let mut frame = Frame::from_raw(&data[..frame_size], &SONY_GOOD_QUALITY);
+
+    let sequence = &frame.channels[0].dif_sequences[3];
+    for (section, count) in [
+        (SectionType::Header, HEADER_BLOCK_COUNT),
+        (SectionType::Subcode, SUBCODE_BLOCK_COUNT),
+        (SectionType::VAUX, VAUX_BLOCK_COUNT),
+        (SectionType::Audio, AUDIO_BLOCK_COUNT),
+        (SectionType::Video, VIDEO_BLOCK_COUNT),
+    ] {
+        let blocks = sequence.section(section);
+        expect_that!(blocks.len(), eq(count));
+        for (index, block) in blocks.iter().enumerate() {
+            expect_that!(block.id.section_type, eq(section));
+            expect_that!(usize::from(block.id.dif_block_number), eq(index));
+        }
+    }
+    expect_that!(sequence.section(SectionType::Reserved5), empty());
+
+    let position =
+        DIFBlockPosition { channel: 0, dif_sequence: 3, section: SectionType::Audio, block: 7 };
+    let original = frame.block(&position).data[10];
+    expect_that!(original, eq(frame.channels[0].dif_sequences[3].audio[7].data[10]));
+    frame.block_mut(&position).data[10] ^= 0xFF;
+    expect_that!(frame.channels[0].dif_sequences[3].audio[7].data[10], eq(original ^ 0xFF));
+}
+
+#[googletest::test]
+fn test_frame_from_raw() {
+    let data = std::fs::read(test_resource("dv_multiframe/sony_good_quality.dv")).unwrap();
+    let frame_size = usize::try_from(SONY_GOOD_QUALITY.video_frame_size()).unwrap();
+
+    let frame = Frame::from_raw(&data[..frame_size], &SONY_GOOD_QUALITY);
+    expect_that!(frame.channels.len(), eq(1));
+    expect_that!(frame.channels[0].dif_sequences.len(), eq(10));
+
+    // The sequences must serialize back to the same bytes that they were read from
+    for (seq, raw) in
+        frame.channels[0].dif_sequences.iter().zip(data.chunks_exact(DIF_SEQUENCE_SIZE))
+    {
+        expect_that!(seq.to_raw()[..], eq(raw));
+    }
+}
+
+#[googletest::test]
+#[should_panic(expected = "raw frame data has the wrong length")]
+fn test_frame_from_raw_wrong_length() {
+    Frame::from_raw(&[0; 1000], &SONY_GOOD_QUALITY);
+}
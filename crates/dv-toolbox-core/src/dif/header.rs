@@ -0,0 +1,233 @@
+use arbitrary_int::{u1, u4, u5, u6};
+use bitbybit::{bitenum, bitfield};
+use garde::{Unvalidated, Validate};
+use serde::{Deserialize, Serialize};
+use snafu::prelude::*;
+
+use super::{BlockError, BlockValidationSnafu, DIFBlockData};
+use crate::{
+    file::{self, ValidInfoMethods},
+    pack,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Number of bytes at the start of the header DIF block payload that hold meaningful fields.
+///
+/// The remaining bytes of the payload are reserved.
+const HEADER_FIELDS_SIZE: usize = 5;
+
+crate::pack::util::optional_enum! {
+    /// Identifies the application (i.e. tape format) that recorded an area of the track.
+    ///
+    /// The track application ID (APT) in the header block is what distinguishes DVCPRO
+    /// recordings from consumer DV and DVCAM recordings.  DVCAM uses the same value as consumer
+    /// DV, so telling those two apart requires looking at other information, such as whether
+    /// the audio is locked.
+    #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+    #[allow(missing_docs)]
+    pub enum ApplicationID {
+        /// IEC 61834 consumer DV format.  DVCAM recordings also use this value.
+        IEC61834 = 0x0,
+
+        /// SMPTE 306M Type D-7 format, commonly known as DVCPRO.
+        D7 = 0x1,
+
+        Reserved2 = 0x2,
+        Reserved3 = 0x3,
+        Reserved4 = 0x4,
+        Reserved5 = 0x5,
+        Reserved6 = 0x6,
+    }
+
+    #[bitenum(u3, exhaustive = true)]
+    pub(super) enum RawApplicationID {
+        NoInfo = 0x7,
+    }
+}
+
+/// Decoded contents of the header DIF block at the start of every DIF sequence.
+///
+/// The header block identifies the video system and the application (tape format) used to
+/// record each area of the track.  It also carries transmitting flags, which a tape deck uses
+/// to signal that the audio, video, or subcode data in the DIF sequence is not valid.
+///
+/// - IEC 61834-2:1998 - SD format for 525-60 and 625-50 systems
+/// - SMPTE 306M-2002 - 6.35-mm Type D-7 Component Format
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Validate, Serialize, Deserialize)]
+#[garde(context(pack::PackContext))]
+pub struct HeaderBlock {
+    /// Video system indicated by the DIF sequence flag (DSF).
+    ///
+    /// It must match the system of the file that the block was read from.
+    #[garde(custom(check_system))]
+    pub system: file::System,
+
+    /// Track application ID (APT), which identifies the tape format of the track.
+    #[garde(skip)]
+    pub track_application_id: Option<ApplicationID>,
+
+    /// Application ID of the audio area (AP1).
+    #[garde(skip)]
+    pub audio_application_id: Option<ApplicationID>,
+
+    /// Application ID of the video area (AP2).
+    #[garde(skip)]
+    pub video_application_id: Option<ApplicationID>,
+
+    /// Application ID of the subcode area (AP3).
+    #[garde(skip)]
+    pub subcode_application_id: Option<ApplicationID>,
+
+    /// Whether the audio data of the DIF sequence is valid.
+    ///
+    /// This is the inverse of the TF1 transmitting flag.  A tape deck sets TF1 when it could
+    /// not provide valid audio data for the DIF sequence.
+    #[garde(skip)]
+    pub audio_valid: bool,
+
+    /// Whether the video data of the DIF sequence is valid.
+    ///
+    /// This is the inverse of the TF2 transmitting flag.  A tape deck sets TF2 when it could
+    /// not provide valid video data for the DIF sequence.
+    #[garde(skip)]
+    pub video_valid: bool,
+
+    /// Whether the subcode data of the DIF sequence is valid.
+    ///
+    /// This is the inverse of the TF3 transmitting flag.  A tape deck sets TF3 when it could
+    /// not provide valid subcode data for the DIF sequence.
+    #[garde(skip)]
+    pub subcode_valid: bool,
+
+    /// Reserved bits following the DSF; should normally be set to 0x3F.
+    #[garde(skip)]
+    pub dsf_reserved: u6,
+
+    /// Reserved bits preceding the APT.
+    ///
+    /// Unlike most reserved bits, the values vary in practice: for example, both 0xF and 0xD
+    /// have been observed in recordings from the same consumer camcorder.
+    #[garde(skip)]
+    pub apt_reserved: u5,
+
+    /// Reserved bits following AP1, AP2, and AP3, respectively; should normally be set to 0xF.
+    #[garde(skip)]
+    pub ap_reserved: [u4; 3],
+}
+
+fn check_system(system: &file::System, ctx: &pack::PackContext) -> garde::Result {
+    let expected = ctx.file_info.system();
+    if *system != expected {
+        Err(garde::Error::new(format!(
+            "header block indicates system {system}, but the file has system {expected}"
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+#[bitfield(u64)]
+struct RawHeaderBlock {
+    // Byte 0
+    #[bits(0..=5, rw)]
+    dsf_reserved: u6,
+    #[bit(6, rw)]
+    zero: u1,
+    #[bit(7, rw)]
+    dsf: u1,
+
+    // Byte 1
+    #[bits(8..=10, rw)]
+    apt: RawApplicationID,
+    #[bits(11..=15, rw)]
+    apt_reserved: u5,
+
+    // Byte 2
+    #[bits(16..=18, rw)]
+    ap1: RawApplicationID,
+    #[bits(19..=22, rw)]
+    ap1_reserved: u4,
+    #[bit(23, rw)]
+    tf1: bool,
+
+    // Byte 3
+    #[bits(24..=26, rw)]
+    ap2: RawApplicationID,
+    #[bits(27..=30, rw)]
+    ap2_reserved: u4,
+    #[bit(31, rw)]
+    tf2: bool,
+
+    // Byte 4
+    #[bits(32..=34, rw)]
+    ap3: RawApplicationID,
+    #[bits(35..=38, rw)]
+    ap3_reserved: u4,
+    #[bit(39, rw)]
+    tf3: bool,
+}
+
+impl HeaderBlock {
+    /// Read from a DV file by decoding the payload of a header DIF block, and then validating
+    /// that it is correct.
+    pub fn try_from_raw(raw: &DIFBlockData, ctx: &pack::PackContext) -> Result<Self, BlockError> {
+        let mut bytes = [0; 8];
+        bytes[..HEADER_FIELDS_SIZE].copy_from_slice(&raw[..HEADER_FIELDS_SIZE]);
+        let raw = RawHeaderBlock::new_with_raw_value(u64::from_le_bytes(bytes));
+
+        ensure_whatever!(raw.zero().value() == 0, "header block bit that must be zero was set");
+
+        let unvalidated = Self {
+            system: match raw.dsf().value() {
+                0x0 => file::System::Sys525_60,
+                0x1 => file::System::Sys625_50,
+                _ => panic!("code was supposed to be unreachable"),
+            },
+            track_application_id: raw.apt().into(),
+            audio_application_id: raw.ap1().into(),
+            video_application_id: raw.ap2().into(),
+            subcode_application_id: raw.ap3().into(),
+            audio_valid: !raw.tf1(),
+            video_valid: !raw.tf2(),
+            subcode_valid: !raw.tf3(),
+            dsf_reserved: raw.dsf_reserved(),
+            apt_reserved: raw.apt_reserved(),
+            ap_reserved: [raw.ap1_reserved(), raw.ap2_reserved(), raw.ap3_reserved()],
+        };
+        Ok(Unvalidated::new(unvalidated)
+            .validate_with(ctx)
+            .context(BlockValidationSnafu)?
+            .into_inner())
+    }
+
+    /// Serialize the header fields into the payload of a header DIF block.
+    ///
+    /// Only the leading bytes that hold the header fields are modified.  The remaining reserved
+    /// bytes in the payload are left untouched.
+    pub fn write_raw(&self, raw: &mut DIFBlockData) {
+        // The uppermost bytes are not part of the header, so the builder can't be used here.
+        let bytes = RawHeaderBlock::new_with_raw_value(0)
+            .with_dsf_reserved(self.dsf_reserved)
+            .with_zero(u1::new(0))
+            .with_dsf(match self.system {
+                file::System::Sys525_60 => u1::new(0x0),
+                file::System::Sys625_50 => u1::new(0x1),
+            })
+            .with_apt(self.track_application_id.into())
+            .with_apt_reserved(self.apt_reserved)
+            .with_ap1(self.audio_application_id.into())
+            .with_ap1_reserved(self.ap_reserved[0])
+            .with_tf1(!self.audio_valid)
+            .with_ap2(self.video_application_id.into())
+            .with_ap2_reserved(self.ap_reserved[1])
+            .with_tf2(!self.video_valid)
+            .with_ap3(self.subcode_application_id.into())
+            .with_ap3_reserved(self.ap_reserved[2])
+            .with_tf3(!self.subcode_valid)
+            .raw_value()
+            .to_le_bytes();
+        raw[..HEADER_FIELDS_SIZE].copy_from_slice(&bytes[..HEADER_FIELDS_SIZE]);
+    }
+}
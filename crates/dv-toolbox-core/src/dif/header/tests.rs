@@ -0,0 +1,164 @@
+use arbitrary_int::{u4, u5, u6};
+use display_error_chain::ErrorChainExt;
+use googletest::prelude::*;
+use rstest::rstest;
+use stdext::function_name;
+
+use super::*;
+use crate::{
+    dif::DIF_BLOCK_DATA_SIZE,
+    pack::testutil::{NTSC, PAL},
+    testutil::*,
+};
+
+test_all_test_cases_ran!(("test_header_block_binary", &HEADER_BLOCK_BINARY_TEST_CASES));
+
+#[derive(Debug)]
+struct HeaderBlockBinaryTestCase<'a> {
+    input: &'a str,
+    parsed: Option<HeaderBlock>,
+    err: Option<&'a str>,
+    ctx: pack::PackContext,
+}
+
+impl<'a> Default for HeaderBlockBinaryTestCase<'a> {
+    fn default() -> Self {
+        Self { input: "", parsed: None, err: None, ctx: *NTSC }
+    }
+}
+
+/// Header fields of a valid consumer DV recording, which individual test cases can tweak.
+const CONSUMER_DV: HeaderBlock = HeaderBlock {
+    system: file::System::Sys525_60,
+    track_application_id: Some(ApplicationID::IEC61834),
+    audio_application_id: Some(ApplicationID::IEC61834),
+    video_application_id: Some(ApplicationID::IEC61834),
+    subcode_application_id: Some(ApplicationID::IEC61834),
+    audio_valid: true,
+    video_valid: true,
+    subcode_valid: true,
+    dsf_reserved: u6::new(0x3F),
+    apt_reserved: u5::new(0xF),
+    ap_reserved: [u4::new(0xF), u4::new(0xF), u4::new(0xF)],
+};
+
+static HEADER_BLOCK_BINARY_TEST_CASES: LazyTestCases<HeaderBlockBinaryTestCase> = test_case_map!(
+    //
+    // These are from real tape transfers: my Sony DCR-TRV460
+    //
+    "sony",
+    HeaderBlockBinaryTestCase {
+        input: "3F 78 78 78 78",
+        parsed: Some(CONSUMER_DV),
+        ..Default::default()
+    },
+    //
+    // the reserved bits in front of the APT vary from frame to frame
+    "sony_apt_reserved",
+    HeaderBlockBinaryTestCase {
+        input: "3F 68 78 78 78",
+        parsed: Some(HeaderBlock { apt_reserved: u5::new(0xD), ..CONSUMER_DV }),
+        ..Default::default()
+    },
+    //
+    // ===== SYNTHETIC TEST CASES =====
+    //
+    // DVCPRO recording in a 625-50 system
+    "dvcpro_pal",
+    HeaderBlockBinaryTestCase {
+        input: "BF 79 79 79 79",
+        parsed: Some(HeaderBlock {
+            system: file::System::Sys625_50,
+            track_application_id: Some(ApplicationID::D7),
+            audio_application_id: Some(ApplicationID::D7),
+            video_application_id: Some(ApplicationID::D7),
+            subcode_application_id: Some(ApplicationID::D7),
+            ..CONSUMER_DV
+        }),
+        ctx: *PAL,
+        ..Default::default()
+    },
+    //
+    // audio and video flagged as invalid by the tape deck
+    "transmitting_flags",
+    HeaderBlockBinaryTestCase {
+        input: "3F 78 F8 F8 78",
+        parsed: Some(HeaderBlock { audio_valid: false, video_valid: false, ..CONSUMER_DV }),
+        ..Default::default()
+    },
+    //
+    // application IDs with no information
+    "no_info",
+    HeaderBlockBinaryTestCase {
+        input: "3F 7F 7F 7F 7F",
+        parsed: Some(HeaderBlock {
+            track_application_id: None,
+            audio_application_id: None,
+            video_application_id: None,
+            subcode_application_id: None,
+            ..CONSUMER_DV
+        }),
+        ..Default::default()
+    },
+    //
+    // ===== ERROR CASES =====
+    //
+    // system does not match the file
+    "wrong_system",
+    HeaderBlockBinaryTestCase {
+        input: "BF 78 78 78 78",
+        err: Some(
+            "DIF block failed validation during deserialization of raw bytes\n\
+            Caused by:\n  \
+            -> system: header block indicates system 625-50, but the file has system 525-60\n"
+        ),
+        ..Default::default()
+    },
+    //
+    // bit that must always be zero was set
+    "zero_bit_set",
+    HeaderBlockBinaryTestCase {
+        input: "7F 78 78 78 78",
+        err: Some(
+            "DIF block failed deserialization of raw bytes: header block bit that must be zero \
+            was set"
+        ),
+        ..Default::default()
+    }
+);
+
+#[googletest::test]
+#[rstest]
+#[case::sony(function_name!())]
+#[case::sony_apt_reserved(function_name!())]
+#[case::dvcpro_pal(function_name!())]
+#[case::transmitting_flags(function_name!())]
+#[case::no_info(function_name!())]
+#[case::wrong_system(function_name!())]
+#[case::zero_bit_set(function_name!())]
+fn test_header_block_binary(#[case] test_function_name: &str) {
+    let tc = HEADER_BLOCK_BINARY_TEST_CASES.get_test_case(test_function_name);
+
+    // The reserved bytes after the header fields are always 0xFF
+    let mut input = [0xFF; DIF_BLOCK_DATA_SIZE];
+    input[..HEADER_FIELDS_SIZE].copy_from_slice(&from_hex::<HEADER_FIELDS_SIZE>(tc.input));
+
+    // Decode the header and check for the expected error or contents
+    let decoded = HeaderBlock::try_from_raw(&input, &tc.ctx);
+    match tc.err {
+        None => expect_that!(decoded.as_ref().err().map(|e| e.chain().to_string()), none()),
+        Some(msg) => {
+            expect_that!(decoded.as_ref().err().map(|e| e.chain().to_string()), some(eq(msg)))
+        }
+    };
+    expect_that!(decoded.as_ref().ok(), eq(tc.parsed.as_ref()));
+
+    // Serialize the header back on top of a block of a different value, and check that only
+    // the header fields changed
+    if let Ok(header) = decoded {
+        let mut output = [0x00; DIF_BLOCK_DATA_SIZE];
+        header.write_raw(&mut output);
+        expect_that!(output[..HEADER_FIELDS_SIZE], eq(&input[..HEADER_FIELDS_SIZE]));
+        expect_that!(output[HEADER_FIELDS_SIZE..], each(eq(&0x00)));
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+
+use arbitrary_int::u4;
+use bitbybit::{bitenum, bitfield};
+use serde::{Deserialize, Serialize};
+
+use super::{DIFBlockData, Frame};
+
+#[cfg(test)]
+mod tests;
+
+/// Number of video DIF blocks in each video segment.  Each video DIF block holds one compressed
+/// macroblock.
+pub const VIDEO_SEGMENT_BLOCK_COUNT: usize = 5;
+
+crate::pack::util::required_enum! {
+    /// Error status of a compressed macroblock (STA), as recorded by the tape deck.
+    ///
+    /// When a tape deck can't recover a macroblock from the tape, it may conceal the error by
+    /// substituting the macroblock at the same position in a nearby frame.  The most significant
+    /// bit is set when the substituted macroblock came from a frame whose sequence number is not
+    /// continuous with the current frame.
+    ///
+    /// - IEC 61834-2:1998 - SD format for 525-60 and 625-50 systems
+    /// - SMPTE 306M-2002 - 6.35-mm Type D-7 Component Format
+    #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+    #[allow(missing_docs)]
+    pub enum MacroblockStatus {
+        /// The macroblock has no errors.
+        NoError = 0x0,
+
+        Reserved1 = 0x1,
+
+        /// The macroblock was concealed with the one from the previous frame.
+        ConcealedWithPreviousFrame = 0x2,
+
+        Reserved3 = 0x3,
+
+        /// The macroblock was concealed with the one from the next frame.
+        ConcealedWithNextFrame = 0x4,
+
+        Reserved5 = 0x5,
+
+        /// The macroblock was concealed with the one from a frame that is not specified.
+        ConcealedWithOtherFrame = 0x6,
+
+        /// The macroblock has an error that was not concealed.
+        ErrorNotConcealed = 0x7,
+
+        Reserved8 = 0x8,
+        Reserved9 = 0x9,
+
+        /// Same as [`MacroblockStatus::ConcealedWithPreviousFrame`], but the frame sequence is
+        /// not continuous.
+        ConcealedWithPreviousFrameDiscontinuous = 0xA,
+
+        ReservedB = 0xB,
+
+        /// Same as [`MacroblockStatus::ConcealedWithNextFrame`], but the frame sequence is not
+        /// continuous.
+        ConcealedWithNextFrameDiscontinuous = 0xC,
+
+        ReservedD = 0xD,
+
+        /// Same as [`MacroblockStatus::ConcealedWithOtherFrame`], but the frame sequence is not
+        /// continuous.
+        ConcealedWithOtherFrameDiscontinuous = 0xE,
+
+        /// The macroblock has an error, and whether it was concealed is unknown.
+        ErrorUnknownConcealment = 0xF,
+    }
+
+    #[bitenum(u4, exhaustive = true)]
+    enum RawMacroblockStatus;
+}
+
+impl MacroblockStatus {
+    /// Whether the tape deck concealed an error by substituting a macroblock from another frame.
+    pub fn is_concealed(&self) -> bool {
+        matches!(
+            self,
+            Self::ConcealedWithPreviousFrame
+                | Self::ConcealedWithNextFrame
+                | Self::ConcealedWithOtherFrame
+                | Self::ConcealedWithPreviousFrameDiscontinuous
+                | Self::ConcealedWithNextFrameDiscontinuous
+                | Self::ConcealedWithOtherFrameDiscontinuous
+        )
+    }
+
+    /// Whether the macroblock has an error that was not known to be concealed.
+    pub fn is_unconcealed_error(&self) -> bool {
+        matches!(self, Self::ErrorNotConcealed | Self::ErrorUnknownConcealment)
+    }
+}
+
+/// Decoded header at the start of the payload of every video DIF block.
+///
+/// - IEC 61834-2:1998 - SD format for 525-60 and 625-50 systems
+/// - SMPTE 306M-2002 - 6.35-mm Type D-7 Component Format
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub struct MacroblockHeader {
+    /// Error status of the compressed macroblock (STA).
+    pub status: MacroblockStatus,
+
+    /// Quantization number (QNO) that was used to compress the macroblock.
+    pub quantization_number: u4,
+}
+
+#[bitfield(u8)]
+struct RawMacroblockHeader {
+    #[bits(0..=3, rw)]
+    qno: u4,
+    #[bits(4..=7, rw)]
+    sta: RawMacroblockStatus,
+}
+
+impl MacroblockHeader {
+    /// Decode the header from the payload of a video DIF block.
+    ///
+    /// All possible values are valid, so this can't fail.
+    pub fn from_raw(raw: &DIFBlockData) -> Self {
+        let raw = RawMacroblockHeader::new_with_raw_value(raw[0]);
+        Self { status: raw.sta().into(), quantization_number: raw.qno() }
+    }
+
+    /// Serialize the header into the payload of a video DIF block.
+    ///
+    /// Only the first byte of the payload is modified.
+    pub fn write_raw(&self, raw: &mut DIFBlockData) {
+        raw[0] = RawMacroblockHeader::builder()
+            .with_qno(self.quantization_number)
+            .with_sta(self.status.into())
+            .build()
+            .raw_value();
+    }
+}
+
+/// Location of a video DIF block within a DV frame.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub struct VideoBlockPosition {
+    /// Index of the channel within the frame.
+    pub channel: usize,
+
+    /// Index of the DIF sequence within the channel.
+    pub dif_sequence: usize,
+
+    /// Index of the video DIF block within the DIF sequence, in the range `[0, 134]`.
+    pub block: usize,
+}
+
+impl VideoBlockPosition {
+    /// Index of the video segment within the DIF sequence, in the range `[0, 26]`.
+    pub fn video_segment(&self) -> usize {
+        self.block / VIDEO_SEGMENT_BLOCK_COUNT
+    }
+
+    /// Index of the macroblock within the video segment, in the range `[0, 4]`.
+    pub fn macroblock(&self) -> usize {
+        self.block % VIDEO_SEGMENT_BLOCK_COUNT
+    }
+}
+
+impl Frame {
+    /// Decode the macroblock header of every video DIF block of the frame.
+    ///
+    /// The headers are returned in the order that they are stored in the file.
+    pub fn macroblock_headers(&self) -> Vec<(VideoBlockPosition, MacroblockHeader)> {
+        let mut headers = Vec::new();
+        for (channel_index, channel) in self.channels.iter().enumerate() {
+            for (sequence_index, sequence) in channel.dif_sequences.iter().enumerate() {
+                for (block_index, block) in sequence.video.iter().enumerate() {
+                    headers.push((
+                        VideoBlockPosition {
+                            channel: channel_index,
+                            dif_sequence: sequence_index,
+                            block: block_index,
+                        },
+                        MacroblockHeader::from_raw(&block.data),
+                    ));
+                }
+            }
+        }
+        headers
+    }
+}
+
+/// Summary of the macroblock error status of a single frame.
+///
+/// This reveals how much of the picture the tape deck had to conceal, which isn't visible from
+/// the metadata packs alone.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct FrameVideoStatus {
+    /// Total number of macroblocks in the frame.
+    pub macroblock_count: usize,
+
+    /// Number of macroblocks with each error status.  Statuses that don't appear in the frame are
+    /// absent.
+    pub status_counts: HashMap<MacroblockStatus, usize>,
+
+    /// Every macroblock whose status is not [`MacroblockStatus::NoError`], in the order that
+    /// they are stored in the file.
+    pub damaged_macroblocks: Vec<(VideoBlockPosition, MacroblockStatus)>,
+}
+
+impl FrameVideoStatus {
+    /// Summarize the macroblock headers of the frame.
+    pub fn from_frame(frame: &Frame) -> Self {
+        let headers = frame.macroblock_headers();
+        let mut status_counts = HashMap::new();
+        let mut damaged_macroblocks = Vec::new();
+        for (position, header) in &headers {
+            *status_counts.entry(header.status).or_default() += 1;
+            if header.status != MacroblockStatus::NoError {
+                damaged_macroblocks.push((*position, header.status));
+            }
+        }
+        Self { macroblock_count: headers.len(), status_counts, damaged_macroblocks }
+    }
+
+    /// Number of macroblocks that the tape deck concealed.
+    pub fn concealed_count(&self) -> usize {
+        self.damaged_macroblocks.iter().filter(|(_, status)| status.is_concealed()).count()
+    }
+
+    /// Number of macroblocks that have an error that was not known to be concealed.
+    pub fn unconcealed_error_count(&self) -> usize {
+        self.damaged_macroblocks.iter().filter(|(_, status)| status.is_unconcealed_error()).count()
+    }
+}
@@ -0,0 +1,109 @@
+use googletest::prelude::*;
+use rstest::rstest;
+
+use super::*;
+use crate::{
+    dif::DIF_BLOCK_DATA_SIZE,
+    file::testutil::{read_test_frames, SONY_GOOD_QUALITY},
+};
+
+#[googletest::test]
+#[rstest]
+#[case::no_error(0x09, MacroblockStatus::NoError, 0x9)]
+#[case::concealed_previous(0x2B, MacroblockStatus::ConcealedWithPreviousFrame, 0xB)]
+#[case::concealed_next_discontinuous(
+    0xC0,
+    MacroblockStatus::ConcealedWithNextFrameDiscontinuous,
+    0x0
+)]
+#[case::not_concealed(0x7F, MacroblockStatus::ErrorNotConcealed, 0xF)]
+#[case::unknown_concealment(0xF5, MacroblockStatus::ErrorUnknownConcealment, 0x5)]
+#[case::reserved(0x13, MacroblockStatus::Reserved1, 0x3)]
+fn test_macroblock_header_binary(
+    #[case] raw: u8,
+    #[case] status: MacroblockStatus,
+    #[case] quantization_number: u8,
+) {
+    let mut data = [0xAA; DIF_BLOCK_DATA_SIZE];
+    data[0] = raw;
+    let header = MacroblockHeader::from_raw(&data);
+    expect_that!(
+        header,
+        eq(MacroblockHeader { status, quantization_number: u4::new(quantization_number) })
+    );
+
+    // Writing back must only touch the first byte
+    let mut written = [0xAA; DIF_BLOCK_DATA_SIZE];
+    header.write_raw(&mut written);
+    expect_that!(written, eq(data));
+}
+
+#[googletest::test]
+fn test_frame_video_status_undamaged() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let status = FrameVideoStatus::from_frame(&frames[0]);
+
+    expect_that!(status.macroblock_count, eq(1_350));
+    expect_that!(status.status_counts, eq(&HashMap::from([(MacroblockStatus::NoError, 1_350)])));
+    expect_that!(status.damaged_macroblocks, empty());
+    expect_that!(status.concealed_count(), eq(0));
+    expect_that!(status.unconcealed_error_count(), eq(0));
+
+    // The camcorder that recorded this file happens to use the same quantization number for every
+    // macroblock of a video segment
+    let headers = frames[0].macroblock_headers();
+    expect_that!(headers[0].1.quantization_number, eq(u4::new(0x9)));
+    for segment in headers.chunks(VIDEO_SEGMENT_BLOCK_COUNT) {
+        let first = segment[0].1.quantization_number;
+        expect_that!(
+            segment.iter().map(|(_, h)| h.quantization_number).collect::<Vec<_>>(),
+            each(eq(&first))
+        );
+    }
+}
+
+#[googletest::test]
+fn test_frame_video_status_damaged() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let mut frame = frames[0].clone();
+    let damage = [
+        (VideoBlockPosition { channel: 0, dif_sequence: 2, block: 17 }, 0x2),
+        (VideoBlockPosition { channel: 0, dif_sequence: 2, block: 18 }, 0xA),
+        (VideoBlockPosition { channel: 0, dif_sequence: 7, block: 134 }, 0x7),
+        (VideoBlockPosition { channel: 0, dif_sequence: 9, block: 0 }, 0xF),
+    ];
+    for (position, status) in damage {
+        let data =
+            &mut frame.channels[0].dif_sequences[position.dif_sequence].video[position.block].data;
+        data[0] = data[0] & 0x0F | status << 4;
+    }
+
+    let status = FrameVideoStatus::from_frame(&frame);
+    expect_that!(
+        status.status_counts,
+        eq(&HashMap::from([
+            (MacroblockStatus::NoError, 1_346),
+            (MacroblockStatus::ConcealedWithPreviousFrame, 1),
+            (MacroblockStatus::ConcealedWithPreviousFrameDiscontinuous, 1),
+            (MacroblockStatus::ErrorNotConcealed, 1),
+            (MacroblockStatus::ErrorUnknownConcealment, 1),
+        ]))
+    );
+    expect_that!(
+        status.damaged_macroblocks,
+        eq(&vec![
+            (damage[0].0, MacroblockStatus::ConcealedWithPreviousFrame),
+            (damage[1].0, MacroblockStatus::ConcealedWithPreviousFrameDiscontinuous),
+            (damage[2].0, MacroblockStatus::ErrorNotConcealed),
+            (damage[3].0, MacroblockStatus::ErrorUnknownConcealment),
+        ])
+    );
+    expect_that!(status.concealed_count(), eq(2));
+    expect_that!(status.unconcealed_error_count(), eq(2));
+
+    // Positions can be broken down into video segments
+    expect_that!(damage[0].0.video_segment(), eq(3));
+    expect_that!(damage[0].0.macroblock(), eq(2));
+    expect_that!(damage[2].0.video_segment(), eq(26));
+    expect_that!(damage[2].0.macroblock(), eq(4));
+}
@@ -0,0 +1,314 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    AAUXPackPosition, Frame, SubcodeSection, VAUXPackPosition, SUBCODE_SYNC_BLOCKS_PER_BLOCK,
+    SUBCODE_SYNC_BLOCK_SIZE,
+};
+use crate::pack;
+
+#[cfg(test)]
+mod tests;
+
+/// Location of a single copy of a pack within a DV frame.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum PackPosition {
+    /// The pack was read from a subcode sync block.
+    Subcode {
+        /// Index of the channel within the frame.
+        channel: usize,
+
+        /// Index of the DIF sequence within the channel.
+        dif_sequence: usize,
+
+        /// Index of the sync block within the subcode section, in the range `[0, 11]`.
+        sync_block: usize,
+    },
+
+    /// The pack was read from a VAUX pack slot.
+    VAUX(VAUXPackPosition),
+
+    /// The pack was read from the start of an audio DIF block.
+    AAUX(AAUXPackPosition),
+}
+
+/// Offset of the pack within a subcode sync block, following the ID and parity bytes.
+const SUBCODE_PACK_OFFSET: usize = 3;
+
+impl Frame {
+    /// Serialize the pack into the given position of the frame.
+    ///
+    /// Only the bytes occupied by the pack are modified.  In particular, the ID and parity bytes
+    /// of a subcode sync block are left untouched.  The function will panic if the position does
+    /// not exist in the frame.
+    pub fn set_pack(&mut self, position: &PackPosition, pack: &pack::Pack) {
+        match position {
+            PackPosition::Subcode { channel, dif_sequence, sync_block } => {
+                let ctx = self.pack_context();
+                let offset = (sync_block % SUBCODE_SYNC_BLOCKS_PER_BLOCK) * SUBCODE_SYNC_BLOCK_SIZE
+                    + SUBCODE_PACK_OFFSET;
+                self.channels[*channel].dif_sequences[*dif_sequence].subcode
+                    [sync_block / SUBCODE_SYNC_BLOCKS_PER_BLOCK]
+                    .set_pack_at(offset, pack, &ctx);
+            }
+            PackPosition::VAUX(position) => self.set_vaux_pack(position, pack),
+            PackPosition::AAUX(position) => self.set_aaux_pack(position, pack),
+        }
+    }
+
+    /// Returns every pack of the subcode and VAUX sections of the frame, with their positions.
+    ///
+    /// Any of the positions can be passed to [`Frame::set_pack`] to replace the pack.
+    pub fn subcode_and_vaux_packs(&self) -> Vec<(PackPosition, pack::Pack)> {
+        let ctx = self.pack_context();
+        let mut packs = Vec::new();
+        for (channel, dif_channel) in self.channels.iter().enumerate() {
+            for (dif_sequence, sequence) in dif_channel.dif_sequences.iter().enumerate() {
+                let (subcode, _) = SubcodeSection::from_blocks(&sequence.subcode, &ctx);
+                for (sync_block, sync_block_data) in subcode.sync_blocks.iter().enumerate() {
+                    packs.push((
+                        PackPosition::Subcode { channel, dif_sequence, sync_block },
+                        sync_block_data.pack,
+                    ));
+                }
+            }
+        }
+        packs.extend(
+            self.vaux_packs()
+                .into_iter()
+                .map(|(vaux_pack, _)| (PackPosition::VAUX(vaux_pack.position), vaux_pack.pack)),
+        );
+        packs
+    }
+}
+
+/// Reason that a copy of a pack was not counted towards the consensus.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub enum DissentReason {
+    /// The pack is valid, but has different contents than the consensus pack of its type.
+    Disagrees,
+
+    /// The pack is a [`pack::Pack::Invalid`] pack.
+    Invalid,
+
+    /// The pack is a [`pack::Pack::NoInfo`] pack, in a position where other DIF sequences have a
+    /// pack.  This usually indicates a dropout.
+    NoInfo,
+}
+
+/// A copy of a pack that was not counted towards the consensus.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct DissentingPack {
+    /// Location of the copy within the frame.
+    pub position: PackPosition,
+
+    /// Contents of the copy.
+    pub pack: pack::Pack,
+
+    /// Why the copy was not counted towards the consensus.
+    pub reason: DissentReason,
+}
+
+/// Consensus view of the metadata packs in a DV frame.
+///
+/// DV repeats every important pack many times per frame, and dropouts can damage some of the
+/// copies.  This structure holds the result of voting across all copies of each pack type: the
+/// most common valid value wins.  In case of a tie, the copy that appears first in the frame wins.
+///
+/// Packs from the subcode and VAUX sections are voted on together.  AAUX packs are voted on
+/// separately for each audio block channel, since each audio block channel has its own AAUX packs
+/// that legitimately differ from the others.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FrameMetadata {
+    /// Consensus pack of each pack type found in the subcode and VAUX sections.
+    ///
+    /// Pack types that only had [`pack::Pack::Invalid`] copies have no consensus, and are
+    /// therefore absent.  The same is true of [`pack::Type::NoInfo`].
+    pub packs: HashMap<pack::Type, pack::Pack>,
+
+    /// Consensus pack of each AAUX pack type, for each audio block channel.
+    ///
+    /// The vector is indexed by [`super::AAUXPack::audio_block_channel`].
+    pub aaux_packs: Vec<HashMap<pack::Type, pack::Pack>>,
+
+    /// Copies of packs that were not counted towards the consensus, in the order that they appear
+    /// in the frame.
+    pub dissenting_packs: Vec<DissentingPack>,
+}
+
+/// Identifies copies of a pack that are expected to hold the same type of pack in every DIF
+/// sequence, for the purpose of detecting dropouts.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+enum SlotClass {
+    Subcode(usize),
+    VAUXMain(usize),
+    AAUXMain(usize, usize),
+    Optional,
+}
+
+/// A single copy of a pack that takes part in the voting.
+struct Candidate {
+    position: PackPosition,
+    pack: pack::Pack,
+    slot_class: SlotClass,
+}
+
+impl FrameMetadata {
+    /// Vote across all copies of each pack type in the frame.
+    pub fn from_frame(frame: &Frame) -> Self {
+        let ctx = frame.pack_context();
+
+        // Gather every copy of every pack in the frame
+        let mut candidates = Vec::new();
+        for (channel_index, channel) in frame.channels.iter().enumerate() {
+            for (sequence_index, sequence) in channel.dif_sequences.iter().enumerate() {
+                let (subcode, _) = SubcodeSection::from_blocks(&sequence.subcode, &ctx);
+                for (sync_block_index, sync_block) in subcode.sync_blocks.iter().enumerate() {
+                    candidates.push(Candidate {
+                        position: PackPosition::Subcode {
+                            channel: channel_index,
+                            dif_sequence: sequence_index,
+                            sync_block: sync_block_index,
+                        },
+                        pack: sync_block.pack,
+                        slot_class: SlotClass::Subcode(sync_block_index),
+                    });
+                }
+            }
+        }
+        for (vaux_pack, _) in frame.vaux_packs() {
+            candidates.push(Candidate {
+                position: PackPosition::VAUX(vaux_pack.position),
+                pack: vaux_pack.pack,
+                slot_class: vaux_pack
+                    .position
+                    .main_area_index()
+                    .map_or(SlotClass::Optional, SlotClass::VAUXMain),
+            });
+        }
+        let aaux_packs = frame.aaux_packs();
+        let audio_block_channel_count =
+            aaux_packs.iter().map(|(p, _)| p.audio_block_channel + 1).max();
+        let mut aaux_candidates: Vec<_> =
+            (0..audio_block_channel_count.unwrap_or(0)).map(|_| Vec::new()).collect();
+        for (aaux_pack, _) in aaux_packs {
+            aaux_candidates[aaux_pack.audio_block_channel].push(Candidate {
+                position: PackPosition::AAUX(aaux_pack.position),
+                pack: aaux_pack.pack,
+                slot_class: aaux_pack.position.main_area_index().map_or(SlotClass::Optional, |i| {
+                    SlotClass::AAUXMain(aaux_pack.audio_block_channel, i)
+                }),
+            });
+        }
+
+        // Slots that hold a pack in at least one DIF sequence are expected to hold one in all of
+        // them, so an empty slot in that class is likely a dropout.
+        let occupied_slot_classes: HashSet<_> = candidates
+            .iter()
+            .chain(aaux_candidates.iter().flatten())
+            .filter(|c| c.pack.pack_type() != pack::Type::NoInfo)
+            .map(|c| c.slot_class)
+            .filter(|slot_class| *slot_class != SlotClass::Optional)
+            .collect();
+
+        let mut dissenting_packs = Vec::new();
+        let packs = vote(&candidates, &occupied_slot_classes, &mut dissenting_packs);
+        let aaux_packs = aaux_candidates
+            .iter()
+            .map(|c| vote(c, &occupied_slot_classes, &mut dissenting_packs))
+            .collect();
+        dissenting_packs.sort_by_key(|d| position_order(&d.position));
+
+        Self { packs, aaux_packs, dissenting_packs }
+    }
+
+    /// Returns the consensus pack of the given type from the subcode and VAUX sections, if any.
+    pub fn pack(&self, pack_type: pack::Type) -> Option<&pack::Pack> {
+        self.packs.get(&pack_type)
+    }
+
+    /// Returns the consensus AAUX pack of the given type for the given audio block channel, if
+    /// any.
+    pub fn aaux_pack(
+        &self,
+        audio_block_channel: usize,
+        pack_type: pack::Type,
+    ) -> Option<&pack::Pack> {
+        self.aaux_packs.get(audio_block_channel)?.get(&pack_type)
+    }
+}
+
+/// Vote across the given copies of packs, returning the consensus pack of each type.
+///
+/// Copies that did not agree with the consensus are appended to `dissenting_packs`.
+fn vote(
+    candidates: &[Candidate],
+    occupied_slot_classes: &HashSet<SlotClass>,
+    dissenting_packs: &mut Vec<DissentingPack>,
+) -> HashMap<pack::Type, pack::Pack> {
+    // Tally the distinct values of each valid pack type, in order of first appearance
+    let mut tallies = HashMap::<pack::Type, Vec<(pack::Pack, usize)>>::new();
+    for candidate in candidates {
+        match candidate.pack {
+            pack::Pack::NoInfo(_) => {
+                if occupied_slot_classes.contains(&candidate.slot_class) {
+                    dissenting_packs.push(DissentingPack {
+                        position: candidate.position,
+                        pack: candidate.pack,
+                        reason: DissentReason::NoInfo,
+                    });
+                }
+            }
+            pack::Pack::Invalid(..) => dissenting_packs.push(DissentingPack {
+                position: candidate.position,
+                pack: candidate.pack,
+                reason: DissentReason::Invalid,
+            }),
+            _ => {
+                let tally = tallies.entry(candidate.pack.pack_type()).or_default();
+                match tally.iter_mut().find(|(pack, _)| *pack == candidate.pack) {
+                    Some((_, count)) => *count += 1,
+                    None => tally.push((candidate.pack, 1)),
+                }
+            }
+        }
+    }
+
+    // Pick the most common value of each type; the earliest value wins in case of a tie
+    let consensus: HashMap<_, _> = tallies
+        .into_iter()
+        .map(|(pack_type, tally)| {
+            let winner =
+                tally.iter().rev().max_by_key(|(_, count)| *count).map(|(pack, _)| *pack).unwrap();
+            (pack_type, winner)
+        })
+        .collect();
+
+    // Everything else disagrees with the consensus
+    for candidate in candidates {
+        if let Some(winner) = consensus.get(&candidate.pack.pack_type()) {
+            if !matches!(candidate.pack, pack::Pack::Invalid(..)) && candidate.pack != *winner {
+                dissenting_packs.push(DissentingPack {
+                    position: candidate.position,
+                    pack: candidate.pack,
+                    reason: DissentReason::Disagrees,
+                });
+            }
+        }
+    }
+
+    consensus
+}
+
+/// Sort key that orders pack positions by where they appear in the frame.
+fn position_order(position: &PackPosition) -> (usize, usize, usize, usize) {
+    match *position {
+        PackPosition::Subcode { channel, dif_sequence, sync_block } => {
+            (channel, dif_sequence, 0, sync_block)
+        }
+        PackPosition::VAUX(p) => (p.channel, p.dif_sequence, 1, p.pack_number()),
+        PackPosition::AAUX(p) => (p.channel, p.dif_sequence, 2, p.block),
+    }
+}
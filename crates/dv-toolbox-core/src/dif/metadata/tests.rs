@@ -0,0 +1,129 @@
+use googletest::prelude::*;
+
+use super::*;
+use crate::{
+    file::testutil::{read_test_frames, SONY_GOOD_QUALITY},
+    testutil::*,
+};
+
+#[googletest::test]
+fn test_frame_metadata_undamaged() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let ctx = frames[0].pack_context();
+    let metadata = FrameMetadata::from_frame(&frames[0]);
+
+    expect_that!(metadata.dissenting_packs, empty());
+    expect_that!(metadata.packs.len(), eq(8));
+    expect_that!(
+        metadata.pack(pack::Type::TitleTimecode).map(|p| p.to_raw(&ctx)),
+        some(eq(from_hex("13 D7 82 80 C0")))
+    );
+    expect_that!(
+        metadata.pack(pack::Type::VAUXRecordingDate).map(|p| p.to_raw(&ctx)),
+        some(eq(from_hex("62 FF C8 E7 24")))
+    );
+    expect_that!(metadata.pack(pack::Type::CameraConsumer1), some(anything()));
+    expect_that!(metadata.pack(pack::Type::Unknown(0x7F)), some(anything()));
+    expect_that!(metadata.pack(pack::Type::NoInfo), none());
+
+    // Each audio block channel has its own AAUX packs
+    expect_that!(metadata.aaux_packs.len(), eq(2));
+    expect_that!(
+        metadata.aaux_pack(0, pack::Type::AAUXSource).map(|p| p.to_raw(&ctx)),
+        some(eq(from_hex("50 CF 30 C0 D1")))
+    );
+    expect_that!(
+        metadata.aaux_pack(1, pack::Type::AAUXSource).map(|p| p.to_raw(&ctx)),
+        some(eq(from_hex("50 CF 3F C0 D1")))
+    );
+    expect_that!(metadata.aaux_pack(2, pack::Type::AAUXSource), none());
+}
+
+#[googletest::test]
+fn test_frame_metadata_damaged() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let ctx = frames[0].pack_context();
+    let mut frame = frames[0].clone();
+    let original = FrameMetadata::from_frame(&frame);
+
+    // A copy of the recording date with a different value
+    let disagreeing = VAUXPackPosition { channel: 0, dif_sequence: 0, block: 2, slot: 11 };
+    frame.set_vaux_pack(&disagreeing, &pack::Pack::from_raw(&from_hex("62 FF C8 E7 25"), &ctx).0);
+
+    // A copy of the recording date that fails validation
+    let invalid = VAUXPackPosition { channel: 0, dif_sequence: 1, block: 0, slot: 2 };
+    frame.set_vaux_pack(&invalid, &pack::Pack::from_raw(&from_hex("62 23 31 53 74"), &ctx).0);
+
+    // A copy of the AAUX source that dropped out
+    let dropout = AAUXPackPosition { channel: 0, dif_sequence: 3, block: 0 };
+    frame.set_aaux_pack(&dropout, &pack::Pack::from_raw(&[0xFF; 5], &ctx).0);
+
+    // A title timecode that dropped out in the subcode
+    frame.channels[0].dif_sequences[4].subcode[1].data[3..8].fill(0xFF);
+
+    // The consensus is unaffected
+    let metadata = FrameMetadata::from_frame(&frame);
+    expect_that!(metadata.packs, eq(&original.packs));
+    expect_that!(metadata.aaux_packs, eq(&original.aaux_packs));
+
+    // The damaged copies are reported, in the order that they appear in the frame
+    let dissenting: Vec<_> =
+        metadata.dissenting_packs.iter().map(|d| (d.position, d.reason)).collect();
+    expect_that!(
+        dissenting,
+        eq(&vec![
+            (PackPosition::VAUX(disagreeing), DissentReason::Disagrees),
+            (PackPosition::VAUX(invalid), DissentReason::Invalid),
+            (PackPosition::AAUX(dropout), DissentReason::NoInfo),
+            (
+                PackPosition::Subcode { channel: 0, dif_sequence: 4, sync_block: 6 },
+                DissentReason::NoInfo
+            ),
+        ])
+    );
+}
+
+#[googletest::test]
+fn test_frame_set_pack() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let ctx = frames[0].pack_context();
+    let mut frame = frames[0].clone();
+    let no_info = pack::Pack::from_raw(&[0xFF; 5], &ctx).0;
+
+    let subcode = PackPosition::Subcode { channel: 0, dif_sequence: 4, sync_block: 7 };
+    frame.set_pack(&subcode, &no_info);
+    let vaux = VAUXPackPosition { channel: 0, dif_sequence: 1, block: 0, slot: 2 };
+    frame.set_pack(&PackPosition::VAUX(vaux), &no_info);
+    let aaux = AAUXPackPosition { channel: 0, dif_sequence: 3, block: 0 };
+    frame.set_pack(&PackPosition::AAUX(aaux), &no_info);
+
+    // Only the pack bytes of the subcode sync block are modified
+    let mut expected = frames[0].channels[0].dif_sequences[4].subcode[1].data;
+    expected[11..16].fill(0xFF);
+    expect_that!(frame.channels[0].dif_sequences[4].subcode[1].data, eq(expected));
+    let vaux_packs = frame.vaux_packs();
+    let vaux_pack = vaux_packs.iter().find(|(p, _)| p.position == vaux).unwrap();
+    expect_that!(vaux_pack.0.pack, eq(no_info));
+    let aaux_packs = frame.aaux_packs();
+    let aaux_pack = aaux_packs.iter().find(|(p, _)| p.position == aaux).unwrap();
+    expect_that!(aaux_pack.0.pack, eq(no_info));
+}
+
+#[googletest::test]
+fn test_frame_subcode_and_vaux_packs() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let ctx = frames[0].pack_context();
+    let mut frame = frames[0].clone();
+
+    // 10 DIF sequences with 12 subcode sync blocks, and 45 VAUX pack slots each.
+    let packs = frame.subcode_and_vaux_packs();
+    expect_that!(packs.len(), eq(10 * (12 + 45)));
+
+    // Every position can be written back with set_pack.
+    let no_info = pack::Pack::from_raw(&[0xFF; 5], &ctx).0;
+    for (position, _) in &packs {
+        frame.set_pack(position, &no_info);
+    }
+    let packs: Vec<_> = frame.subcode_and_vaux_packs().into_iter().map(|(_, p)| p).collect();
+    expect_that!(packs, each(eq(&no_info)));
+}
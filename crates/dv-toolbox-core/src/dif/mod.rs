@@ -0,0 +1,63 @@
+//! Model structures for working with DIF blocks, as defined in
+//! [IEC 61834-2](https://webstore.iec.ch/en/publication/5984) and other related standards.
+//!
+//! A DV frame is composed of many DIF (digital interface) blocks.  Each DIF block is 80 bytes
+//! long: a 3-byte ID that identifies where the block belongs within the frame, followed by a
+//! 77-byte payload.  To deserialize from binary to [`DIFBlock`], use the [`DIFBlock::from_raw`]
+//! function.  To serialize from a [`DIFBlock`] back to binary, use the [`DIFBlock::to_raw`]
+//! function.
+//!
+//! DIF blocks are grouped into DIF sequences, which are in turn grouped into channels and then
+//! frames.  The [`Frame`] structure holds an entire frame organized in this way.
+//!
+//! The payloads of some sections can be decoded further into typed structures, such as
+//! [`HeaderBlock`] for the header section and [`SubcodeSection`] for the subcode section.  The
+//! packs stored in the VAUX and audio sections can be extracted with [`Frame::vaux_packs`] and
+//! [`Frame::aaux_packs`], respectively.  Since most packs are repeated many times per frame,
+//! [`FrameMetadata`] provides a consensus view of all the copies, and [`RecordingTimestamp`]
+//! combines the consensus recording date and time into a single timestamp.  The status that the
+//! tape deck recorded for each compressed video macroblock is summarized by
+//! [`FrameVideoStatus`], and [`DamageMap`] combines it with other signs of damage into a picture
+//! of the entire frame.
+
+pub use aaux::*;
+pub use block::*;
+pub use damage::*;
+pub use frame::*;
+pub use header::*;
+pub use macroblock::*;
+pub use metadata::*;
+use snafu::prelude::*;
+pub use subcode::*;
+pub use timestamp::*;
+pub use vaux::*;
+
+mod aaux;
+mod block;
+mod damage;
+mod frame;
+mod header;
+mod macroblock;
+mod metadata;
+mod subcode;
+mod timestamp;
+mod vaux;
+
+/// Error type for when there is a problem decoding the payload of a DIF block.
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum BlockError {
+    /// The raw bytes were read into an unvalidated structure, but they then failed to be
+    /// validated.
+    #[snafu(display("DIF block failed validation during deserialization of raw bytes"))]
+    BlockValidation { source: garde::Report },
+
+    #[snafu(whatever, display("DIF block failed deserialization of raw bytes: {message}"))]
+    Whatever {
+        message: String,
+        #[snafu(source(from(Box<dyn std::error::Error>, Some)))]
+        source: Option<Box<dyn std::error::Error>>,
+        // There is intentionally not a backtrace here, since they are slow and we could encounter
+        // a lot of these errors when reading bad videotapes.
+    },
+}
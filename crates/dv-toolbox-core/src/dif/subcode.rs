@@ -0,0 +1,259 @@
+use arbitrary_int::{u3, u4};
+use bitbybit::bitfield;
+use serde::{Deserialize, Serialize};
+
+use super::{ApplicationID, DIFBlock, RawApplicationID, SUBCODE_BLOCK_COUNT};
+use crate::pack;
+
+#[cfg(test)]
+mod tests;
+
+/// Number of sync blocks in each subcode DIF block.
+pub const SUBCODE_SYNC_BLOCKS_PER_BLOCK: usize = 6;
+
+/// Number of sync blocks in the subcode section of each DIF sequence.
+pub const SUBCODE_SYNC_BLOCK_COUNT: usize = SUBCODE_SYNC_BLOCKS_PER_BLOCK * SUBCODE_BLOCK_COUNT;
+
+/// Size of a subcode sync block in bytes: a 2-byte ID, a parity byte, and a 5-byte pack.
+pub const SUBCODE_SYNC_BLOCK_SIZE: usize = 8;
+
+/// Raw bytes composing a subcode sync block.
+pub type RawSubcodeSyncBlock = [u8; SUBCODE_SYNC_BLOCK_SIZE];
+
+/// Number of consecutive sync blocks that together hold one copy of the absolute track number.
+const ABST_SYNC_BLOCK_GROUP_SIZE: usize = 3;
+
+/// ID part of a subcode sync block.
+///
+/// The meaning of some of the bits depends on the sync block number.  The absolute track number
+/// is split up across three consecutive sync blocks; use
+/// [`SubcodeSection::absolute_track_number`] to reassemble it.
+///
+/// - IEC 61834-2:1998 - SD format for 525-60 and 625-50 systems
+/// - SMPTE 306M-2002 - 6.35-mm Type D-7 Component Format
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub struct SubcodeSyncBlockID {
+    /// The FR flag: true if the sync block is in the first half of the channel, false if it is in
+    /// the second half.
+    pub first_half: bool,
+
+    /// Application ID of the subcode area (AP3) for sync block numbers 0 and 6; tag bits (index
+    /// ID, skip ID, and photo/picture ID) for all other sync block numbers.
+    pub ap3_or_tag: u3,
+
+    /// The part of the absolute track number (and the blank flag) carried by this sync block.
+    ///
+    /// - Sync block numbers 0, 3, 6, 9: bit 0 is the blank flag, and bits 7-1 are bits 6-0 of
+    ///   the absolute track number.
+    /// - Sync block numbers 1, 4, 7, 10: bits 14-7 of the absolute track number.
+    /// - Sync block numbers 2, 5, 8, 11: bits 22-15 of the absolute track number.
+    pub absolute_track_number_part: u8,
+
+    /// Number of the sync block within the subcode section of the DIF sequence.
+    ///
+    /// Valid values are in the range `[0, 11]`.
+    pub sync_block_number: u4,
+}
+
+#[bitfield(u16)]
+struct RawSubcodeSyncBlockID {
+    // ID0
+    #[bits(0..=3, rw)]
+    abst_high: u4,
+    #[bits(4..=6, rw)]
+    ap3_or_tag: u3,
+    #[bit(7, rw)]
+    fr: bool,
+
+    // ID1
+    #[bits(8..=11, rw)]
+    syb: u4,
+    #[bits(12..=15, rw)]
+    abst_low: u4,
+}
+
+impl SubcodeSyncBlockID {
+    /// Deserialize the sync block ID from the two raw ID bytes.
+    ///
+    /// Every possible combination of bits is representable, so this function cannot fail.
+    pub fn from_raw(raw: &[u8; 2]) -> Self {
+        let raw = RawSubcodeSyncBlockID::new_with_raw_value(u16::from_le_bytes(*raw));
+        Self {
+            first_half: raw.fr(),
+            ap3_or_tag: raw.ap3_or_tag(),
+            absolute_track_number_part: raw.abst_high().value() << 4 | raw.abst_low().value(),
+            sync_block_number: raw.syb(),
+        }
+    }
+
+    /// Serialize the sync block ID to binary suitable for writing to a DV file.
+    pub fn to_raw(&self) -> [u8; 2] {
+        RawSubcodeSyncBlockID::builder()
+            .with_abst_high(u4::new(self.absolute_track_number_part >> 4))
+            .with_ap3_or_tag(self.ap3_or_tag)
+            .with_fr(self.first_half)
+            .with_syb(self.sync_block_number)
+            .with_abst_low(u4::new(self.absolute_track_number_part & 0xF))
+            .build()
+            .raw_value()
+            .to_le_bytes()
+    }
+}
+
+/// A single sync block within the subcode section of a DIF sequence.
+///
+/// - IEC 61834-2:1998 - SD format for 525-60 and 625-50 systems
+/// - SMPTE 306M-2002 - 6.35-mm Type D-7 Component Format
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct SubcodeSyncBlock {
+    /// ID part of the sync block.
+    pub id: SubcodeSyncBlockID,
+
+    /// Parity byte protecting the ID; should normally be set to 0xFF.
+    pub parity: u8,
+
+    /// The pack carried by the sync block.
+    pub pack: pack::Pack,
+}
+
+impl SubcodeSyncBlock {
+    /// Deserialize the sync block from binary bytes obtained from a DV file.
+    ///
+    /// The return value has the same meaning as the return value of [`pack::Pack::from_raw`].
+    pub fn from_raw(
+        raw: &RawSubcodeSyncBlock,
+        ctx: &pack::PackContext,
+    ) -> (Self, Option<pack::RawError>) {
+        let (pack, err) = pack::Pack::from_raw(raw[3..].try_into().unwrap(), ctx);
+        (
+            Self {
+                id: SubcodeSyncBlockID::from_raw(raw[..2].try_into().unwrap()),
+                parity: raw[2],
+                pack,
+            },
+            err,
+        )
+    }
+
+    /// Serialize the sync block to binary suitable for writing to a DV file.
+    pub fn to_raw(&self, ctx: &pack::PackContext) -> RawSubcodeSyncBlock {
+        let mut raw = [0; SUBCODE_SYNC_BLOCK_SIZE];
+        raw[..2].copy_from_slice(&self.id.to_raw());
+        raw[2] = self.parity;
+        raw[3..].copy_from_slice(&self.pack.to_raw(ctx));
+        raw
+    }
+}
+
+/// Absolute track number decoded from the subcode section of a DIF sequence.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub struct AbsoluteTrackNumber {
+    /// The absolute track number (ABST), which counts tracks from the start of the tape.
+    pub number: u32,
+
+    /// The blank flag (BF) recorded alongside the absolute track number.
+    pub blank_flag: bool,
+}
+
+/// Decoded contents of the two subcode DIF blocks of a DIF sequence.
+///
+/// The subcode section holds 12 sync blocks, each of which carries a pack.  Consumer equipment
+/// typically records the title timecode, as well as the recording date and time, in these packs.
+///
+/// - IEC 61834-2:1998 - SD format for 525-60 and 625-50 systems
+/// - SMPTE 306M-2002 - 6.35-mm Type D-7 Component Format
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct SubcodeSection {
+    /// The sync blocks, in the order that they are stored in the file.
+    pub sync_blocks: [SubcodeSyncBlock; SUBCODE_SYNC_BLOCK_COUNT],
+}
+
+impl SubcodeSection {
+    /// Deserialize the subcode section from the two subcode DIF blocks of a DIF sequence.
+    ///
+    /// The function always returns a [`SubcodeSection`].  Each pack has the same meaning as the
+    /// return value of [`pack::Pack::from_raw`], and any pack parsing errors are returned in the
+    /// array at the same index as the corresponding sync block.
+    pub fn from_blocks(
+        blocks: &[DIFBlock; SUBCODE_BLOCK_COUNT],
+        ctx: &pack::PackContext,
+    ) -> (Self, [Option<pack::RawError>; SUBCODE_SYNC_BLOCK_COUNT]) {
+        let mut errors = std::array::from_fn(|_| None);
+        let sync_blocks = std::array::from_fn(|i| {
+            let (sync_block, err) = SubcodeSyncBlock::from_raw(sync_block_raw(blocks, i), ctx);
+            errors[i] = err;
+            sync_block
+        });
+        (Self { sync_blocks }, errors)
+    }
+
+    /// Serialize the subcode section into the two subcode DIF blocks of a DIF sequence.
+    ///
+    /// Only the bytes occupied by the sync blocks are modified.  The block IDs and the reserved
+    /// bytes that follow the sync blocks are left untouched.
+    pub fn write_blocks(
+        &self,
+        blocks: &mut [DIFBlock; SUBCODE_BLOCK_COUNT],
+        ctx: &pack::PackContext,
+    ) {
+        for (i, sync_block) in self.sync_blocks.iter().enumerate() {
+            let offset = (i % SUBCODE_SYNC_BLOCKS_PER_BLOCK) * SUBCODE_SYNC_BLOCK_SIZE;
+            blocks[i / SUBCODE_SYNC_BLOCKS_PER_BLOCK].data
+                [offset..offset + SUBCODE_SYNC_BLOCK_SIZE]
+                .copy_from_slice(&sync_block.to_raw(ctx));
+        }
+    }
+
+    /// Reassemble the absolute track number from the sync block IDs.
+    ///
+    /// The section holds four copies of the absolute track number, each split across three
+    /// consecutive sync blocks.  The first copy whose sync blocks all have the expected sync block
+    /// numbers is returned, so that a dropout in some of the IDs can be tolerated.  If no copy is
+    /// intact, then None is returned.
+    pub fn absolute_track_number(&self) -> Option<AbsoluteTrackNumber> {
+        self.sync_blocks
+            .chunks_exact(ABST_SYNC_BLOCK_GROUP_SIZE)
+            .enumerate()
+            .find(|(group, sync_blocks)| {
+                sync_blocks.iter().enumerate().all(|(i, s)| {
+                    usize::from(s.id.sync_block_number.value())
+                        == group * ABST_SYNC_BLOCK_GROUP_SIZE + i
+                })
+            })
+            .map(|(_, sync_blocks)| {
+                let parts: [u32; ABST_SYNC_BLOCK_GROUP_SIZE] = std::array::from_fn(|i| {
+                    u32::from(sync_blocks[i].id.absolute_track_number_part)
+                });
+                AbsoluteTrackNumber {
+                    number: parts[2] << 15 | parts[1] << 7 | parts[0] >> 1,
+                    blank_flag: parts[0] & 0x1 == 0x1,
+                }
+            })
+    }
+
+    /// Application ID of the subcode area (AP3), as recorded in the sync block IDs.
+    ///
+    /// The value is recorded in sync blocks 0 and 6; the first of those with the expected sync
+    /// block number is used.  If neither is intact, then None is returned.  Otherwise, the inner
+    /// value is None if the application ID itself indicates no information.
+    pub fn application_id(&self) -> Option<Option<ApplicationID>> {
+        [0, SUBCODE_SYNC_BLOCK_COUNT / 2]
+            .into_iter()
+            .map(|i| (i, self.sync_blocks[i].id))
+            .find(|(i, id)| usize::from(id.sync_block_number.value()) == *i)
+            .map(|(_, id)| RawApplicationID::new_with_raw_value(id.ap3_or_tag).into())
+    }
+
+    /// Returns the first pack of the given type, if the section has one.
+    pub fn find_pack(&self, pack_type: pack::Type) -> Option<&pack::Pack> {
+        self.sync_blocks.iter().map(|s| &s.pack).find(|p| p.pack_type() == pack_type)
+    }
+}
+
+/// Get the raw bytes of the sync block with the given index within the subcode section.
+fn sync_block_raw(blocks: &[DIFBlock; SUBCODE_BLOCK_COUNT], index: usize) -> &RawSubcodeSyncBlock {
+    let offset = (index % SUBCODE_SYNC_BLOCKS_PER_BLOCK) * SUBCODE_SYNC_BLOCK_SIZE;
+    blocks[index / SUBCODE_SYNC_BLOCKS_PER_BLOCK].data[offset..offset + SUBCODE_SYNC_BLOCK_SIZE]
+        .try_into()
+        .unwrap()
+}
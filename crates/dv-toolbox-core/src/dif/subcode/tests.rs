@@ -0,0 +1,172 @@
+use arbitrary_int::{u3, u4};
+use googletest::prelude::*;
+use rstest::rstest;
+use stdext::function_name;
+
+use super::*;
+use crate::{
+    file::testutil::{read_test_frames, SONY_GOOD_QUALITY},
+    testutil::*,
+};
+
+test_all_test_cases_ran!((
+    "test_subcode_sync_block_id_binary",
+    &SUBCODE_SYNC_BLOCK_ID_BINARY_TEST_CASES
+));
+
+#[derive(Debug)]
+struct SubcodeSyncBlockIDBinaryTestCase<'a> {
+    input: &'a str,
+    parsed: SubcodeSyncBlockID,
+}
+
+static SUBCODE_SYNC_BLOCK_ID_BINARY_TEST_CASES: LazyTestCases<SubcodeSyncBlockIDBinaryTestCase> = test_case_map!(
+    //
+    // These are from real tape transfers: my Sony DCR-TRV460
+    //
+    // first sync block in the first half of the channel, with ABST bits 6-0 and the blank flag
+    "first_half",
+    SubcodeSyncBlockIDBinaryTestCase {
+        input: "80 50",
+        parsed: SubcodeSyncBlockID {
+            first_half: true,
+            ap3_or_tag: u3::new(0x0),
+            absolute_track_number_part: 0x05,
+            sync_block_number: u4::new(0),
+        },
+    },
+    //
+    // second sync block, with tag bits and ABST bits 14-7
+    "tag",
+    SubcodeSyncBlockIDBinaryTestCase {
+        input: "F0 61",
+        parsed: SubcodeSyncBlockID {
+            first_half: true,
+            ap3_or_tag: u3::new(0x7),
+            absolute_track_number_part: 0x06,
+            sync_block_number: u4::new(1),
+        },
+    },
+    //
+    // first sync block in the second half of the channel
+    "second_half",
+    SubcodeSyncBlockIDBinaryTestCase {
+        input: "01 70",
+        parsed: SubcodeSyncBlockID {
+            first_half: false,
+            ap3_or_tag: u3::new(0x0),
+            absolute_track_number_part: 0x17,
+            sync_block_number: u4::new(0),
+        },
+    },
+    //
+    // ===== ADDITIONAL CONTRIVED/SYNTHETIC TEST CASES =====
+    //
+    // the ID dropped out
+    "dropout",
+    SubcodeSyncBlockIDBinaryTestCase {
+        input: "FF FF",
+        parsed: SubcodeSyncBlockID {
+            first_half: true,
+            ap3_or_tag: u3::new(0x7),
+            absolute_track_number_part: 0xFF,
+            sync_block_number: u4::new(0xF),
+        },
+    }
+);
+
+#[googletest::test]
+#[rstest]
+#[case::first_half(function_name!())]
+#[case::tag(function_name!())]
+#[case::second_half(function_name!())]
+#[case::dropout(function_name!())]
+fn test_subcode_sync_block_id_binary(#[case] test_function_name: &str) {
+    let tc = SUBCODE_SYNC_BLOCK_ID_BINARY_TEST_CASES.get_test_case(test_function_name);
+    let input = from_hex(tc.input);
+
+    let parsed = SubcodeSyncBlockID::from_raw(&input);
+    expect_that!(parsed, eq(tc.parsed));
+    expect_that!(parsed.to_raw(), eq(input));
+}
+
+#[googletest::test]
+fn test_subcode_section_from_blocks() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let ctx = frames[0].pack_context();
+    let sequences = &frames[0].channels[0].dif_sequences;
+
+    // First half of the channel: only title timecodes are recorded
+    let (first, errors) = SubcodeSection::from_blocks(&sequences[0].subcode, &ctx);
+    expect_that!(errors.iter().all(Option::is_none), eq(true));
+    expect_that!(
+        first.absolute_track_number(),
+        some(eq(AbsoluteTrackNumber { number: 770, blank_flag: true }))
+    );
+    expect_that!(first.application_id(), some(some(eq(ApplicationID::IEC61834))));
+    expect_that!(first.sync_blocks.iter().all(|s| s.id.first_half), eq(true));
+    expect_that!(
+        first.sync_blocks.iter().all(|s| s.pack.pack_type() == pack::Type::TitleTimecode),
+        eq(true)
+    );
+    for (i, sync_block) in first.sync_blocks.iter().enumerate() {
+        expect_that!(usize::from(sync_block.id.sync_block_number.value()), eq(i));
+    }
+
+    // Second half of the channel: the recording date and time are also recorded
+    let (last, errors) = SubcodeSection::from_blocks(&sequences[9].subcode, &ctx);
+    expect_that!(errors.iter().all(Option::is_none), eq(true));
+    expect_that!(
+        last.absolute_track_number(),
+        some(eq(AbsoluteTrackNumber { number: 779, blank_flag: true }))
+    );
+    expect_that!(last.sync_blocks.iter().any(|s| s.id.first_half), eq(false));
+    expect_that!(last.find_pack(pack::Type::TitleTimecode), some(anything()));
+    expect_that!(last.find_pack(pack::Type::VAUXRecordingDate), some(anything()));
+    expect_that!(last.find_pack(pack::Type::VAUXRecordingTime), some(anything()));
+    expect_that!(last.find_pack(pack::Type::AAUXSource), none());
+
+    // The sync blocks must serialize back to the same bytes that they were read from
+    let mut blocks = sequences[9].subcode;
+    blocks[0].data[..SUBCODE_SYNC_BLOCK_SIZE].fill(0x00);
+    blocks[1].data[SUBCODE_SYNC_BLOCK_SIZE..].fill(0x00);
+    last.write_blocks(&mut blocks, &ctx);
+    expect_that!(blocks[0], eq(sequences[9].subcode[0]));
+    expect_that!(
+        blocks[1].data[..SUBCODE_SYNC_BLOCK_SIZE * SUBCODE_SYNC_BLOCKS_PER_BLOCK],
+        eq(&sequences[9].subcode[1].data
+            [..SUBCODE_SYNC_BLOCK_SIZE * SUBCODE_SYNC_BLOCKS_PER_BLOCK])
+    );
+    expect_that!(
+        blocks[1].data[SUBCODE_SYNC_BLOCK_SIZE * SUBCODE_SYNC_BLOCKS_PER_BLOCK..],
+        each(eq(&0x00))
+    );
+}
+
+#[googletest::test]
+fn test_subcode_section_id_dropouts() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let ctx = frames[0].pack_context();
+    let (mut section, _) =
+        SubcodeSection::from_blocks(&frames[0].channels[0].dif_sequences[0].subcode, &ctx);
+    let dropout = SubcodeSyncBlockID::from_raw(&[0xFF, 0xFF]);
+
+    // Losing an ID in the first copy of the absolute track number falls back to the next copy
+    section.sync_blocks[1].id = dropout;
+    expect_that!(
+        section.absolute_track_number(),
+        some(eq(AbsoluteTrackNumber { number: 770, blank_flag: true }))
+    );
+
+    // Likewise for the application ID
+    section.sync_blocks[0].id = dropout;
+    expect_that!(section.application_id(), some(some(eq(ApplicationID::IEC61834))));
+
+    // Nothing can be returned once every copy is damaged
+    for i in [4, 7, 10] {
+        section.sync_blocks[i].id = dropout;
+    }
+    section.sync_blocks[6].id = dropout;
+    expect_that!(section.absolute_track_number(), none());
+    expect_that!(section.application_id(), none());
+}
@@ -0,0 +1,142 @@
+use std::fmt;
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+use super::{Frame, FrameMetadata};
+use crate::{
+    file::{System, ValidInfoMethods},
+    pack,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// When a frame was recorded, combined from its recording date and recording time packs.
+///
+/// Parts of the timestamp are often missing: some equipment only records the date, and dropouts
+/// can damage every copy of one of the packs.  Each variant holds as much of the timestamp as
+/// could be found.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub enum RecordingTimestamp {
+    /// The date, time, and time zone were all recorded.
+    Complete(DateTime<FixedOffset>),
+
+    /// The date and time were recorded, but the time zone was not.
+    NoTimeZone(NaiveDateTime),
+
+    /// Only the date was recorded.
+    DateOnly(NaiveDate),
+
+    /// Only the time of day was recorded.
+    TimeOnly(NaiveTime),
+}
+
+impl RecordingTimestamp {
+    /// Combine the recording date and recording time packs of a frame.
+    ///
+    /// The time zone of the recording date pack is stored as a positive offset from UTC, so that
+    /// offsets of more than 12 hours stand for negative offsets: for example, New York is stored
+    /// as +19 hours, and is returned as -5 hours.  If daylight saving time was in effect, one hour
+    /// is added to the offset, since the recorded time is the local clock time.
+    ///
+    /// If the recording time has a frame number, it is converted to a fraction of a second using
+    /// the nominal frame rate of the system.  Otherwise, the time is rounded down to the second.
+    /// [`None`] is returned if neither a date nor a time is available.
+    pub fn from_packs(
+        date: Option<&pack::RecordingDate>,
+        time: Option<&pack::RecordingTime>,
+        system: System,
+    ) -> Option<Self> {
+        let time = time.and_then(|t| t.time).and_then(|t| {
+            let nanoseconds = t.frame.map_or(0, |frame| {
+                let nanoseconds =
+                    u64::from(frame) * 1_000_000_000 / u64::from(pack::timecode_frame_rate(system));
+                u32::try_from(nanoseconds).unwrap()
+            });
+            NaiveTime::from_hms_nano_opt(
+                t.hour.into(),
+                t.minute.into(),
+                t.second.into(),
+                nanoseconds,
+            )
+        });
+        let offset = date.and_then(|d| d.timezone).map(|timezone| {
+            let mut offset = timezone.local_minus_utc();
+            if offset > 12 * 60 * 60 {
+                offset -= 24 * 60 * 60;
+            }
+            if date.and_then(|d| d.daylight_saving_time)
+                == Some(pack::DaylightSavingTime::DaylightSavingTime)
+            {
+                offset += 60 * 60;
+            }
+            FixedOffset::east_opt(offset).unwrap()
+        });
+        match (date.and_then(|d| d.date), time, offset) {
+            (Some(date), Some(time), Some(offset)) => {
+                Some(Self::Complete(date.and_time(time).and_local_timezone(offset).single()?))
+            }
+            (Some(date), Some(time), None) => Some(Self::NoTimeZone(date.and_time(time))),
+            (Some(date), None, _) => Some(Self::DateOnly(date)),
+            (None, Some(time), _) => Some(Self::TimeOnly(time)),
+            (None, None, _) => None,
+        }
+    }
+
+    /// Find the recording timestamp in the consensus metadata of a frame.
+    ///
+    /// The VAUX packs are preferred.  The AAUX packs of the first audio block channel are used
+    /// for any pack that is missing from the VAUX.  See [`RecordingTimestamp::from_packs`].
+    pub fn from_metadata(metadata: &FrameMetadata, system: System) -> Option<Self> {
+        let pack = |vaux_type, aaux_type| {
+            metadata.pack(vaux_type).or_else(|| metadata.aaux_pack(0, aaux_type))
+        };
+        let date = match pack(pack::Type::VAUXRecordingDate, pack::Type::AAUXRecordingDate) {
+            Some(pack::Pack::VAUXRecordingDate(date) | pack::Pack::AAUXRecordingDate(date)) => {
+                Some(*date.0)
+            }
+            _ => None,
+        };
+        let time = match pack(pack::Type::VAUXRecordingTime, pack::Type::AAUXRecordingTime) {
+            Some(pack::Pack::VAUXRecordingTime(time) | pack::Pack::AAUXRecordingTime(time)) => {
+                Some(*time.0)
+            }
+            _ => None,
+        };
+        Self::from_packs(date.as_ref(), time.as_ref(), system)
+    }
+
+    /// Find the recording timestamp of a frame; see [`RecordingTimestamp::from_metadata`].
+    pub fn from_frame(frame: &Frame) -> Option<Self> {
+        Self::from_metadata(&FrameMetadata::from_frame(frame), frame.file_info.system())
+    }
+
+    /// Returns the complete timestamp, if the date, time, and time zone are all known.
+    pub fn date_time(&self) -> Option<DateTime<FixedOffset>> {
+        match self {
+            Self::Complete(date_time) => Some(*date_time),
+            _ => None,
+        }
+    }
+
+    /// Returns the local date and time, if both are known, regardless of the time zone.
+    pub fn naive_date_time(&self) -> Option<NaiveDateTime> {
+        match self {
+            Self::Complete(date_time) => Some(date_time.naive_local()),
+            Self::NoTimeZone(date_time) => Some(*date_time),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RecordingTimestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Complete(date_time) => date_time.fmt(f),
+            Self::NoTimeZone(date_time) => date_time.fmt(f),
+            Self::DateOnly(date) => date.fmt(f),
+            Self::TimeOnly(time) => time.fmt(f),
+        }
+    }
+}
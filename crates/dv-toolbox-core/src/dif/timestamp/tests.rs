@@ -0,0 +1,140 @@
+use arbitrary_int::u2;
+use chrono::Weekday;
+use googletest::prelude::*;
+use rstest::rstest;
+
+use super::*;
+use crate::file::testutil::{read_test_frames, SONY_GOOD_QUALITY};
+
+fn date(timezone_hours: Option<i32>, dst: Option<pack::DaylightSavingTime>) -> pack::RecordingDate {
+    pack::RecordingDate {
+        date: NaiveDate::from_ymd_opt(1997, 8, 27),
+        weekday: Some(Weekday::Wed),
+        timezone: timezone_hours.map(|h| FixedOffset::east_opt(h * 60 * 60).unwrap()),
+        daylight_saving_time: dst,
+        reserved: u2::new(0x3),
+    }
+}
+
+fn time(frame: Option<u8>) -> pack::RecordingTime {
+    pack::RecordingTime {
+        time: Some(pack::TimeValueWithOptionalFrame {
+            hour: 13,
+            minute: 45,
+            second: 30,
+            drop_frame: true,
+            frame,
+        }),
+        color_frame: pack::ColorFrame::Synchronized,
+        polarity_correction: pack::PolarityCorrection::Odd,
+        binary_group_flag: pack::BinaryGroupFlag::TimeClockGroupPageLine,
+    }
+}
+
+fn timestamp(rfc3339: &str) -> Option<RecordingTimestamp> {
+    Some(RecordingTimestamp::Complete(DateTime::parse_from_rfc3339(rfc3339).unwrap()))
+}
+
+#[googletest::test]
+#[rstest]
+#[case::positive_offset(
+    Some(date(Some(9), Some(pack::DaylightSavingTime::Normal))),
+    Some(time(None)),
+    System::Sys525_60,
+    timestamp("1997-08-27T13:45:30+09:00")
+)]
+#[case::negative_offset(
+    Some(date(Some(19), Some(pack::DaylightSavingTime::Normal))),
+    Some(time(None)),
+    System::Sys525_60,
+    timestamp("1997-08-27T13:45:30-05:00")
+)]
+#[case::daylight_saving_time(
+    Some(date(Some(19), Some(pack::DaylightSavingTime::DaylightSavingTime))),
+    Some(time(None)),
+    System::Sys525_60,
+    timestamp("1997-08-27T13:45:30-04:00")
+)]
+#[case::utc(
+    Some(date(Some(0), Some(pack::DaylightSavingTime::Normal))),
+    Some(time(None)),
+    System::Sys525_60,
+    timestamp("1997-08-27T13:45:30+00:00")
+)]
+#[case::ntsc_frame(
+    Some(date(Some(9), Some(pack::DaylightSavingTime::Normal))),
+    Some(time(Some(15))),
+    System::Sys525_60,
+    timestamp("1997-08-27T13:45:30.5+09:00")
+)]
+#[case::pal_frame(
+    Some(date(Some(9), Some(pack::DaylightSavingTime::Normal))),
+    Some(time(Some(5))),
+    System::Sys625_50,
+    timestamp("1997-08-27T13:45:30.2+09:00")
+)]
+#[case::no_time_zone(
+    Some(date(None, None)),
+    Some(time(None)),
+    System::Sys525_60,
+    Some(RecordingTimestamp::NoTimeZone(
+        NaiveDate::from_ymd_opt(1997, 8, 27).unwrap().and_hms_opt(13, 45, 30).unwrap()
+    ))
+)]
+#[case::date_only(
+    Some(date(Some(9), Some(pack::DaylightSavingTime::Normal))),
+    None,
+    System::Sys525_60,
+    Some(RecordingTimestamp::DateOnly(NaiveDate::from_ymd_opt(1997, 8, 27).unwrap()))
+)]
+#[case::empty_time(
+    Some(date(None, None)),
+    Some(pack::RecordingTime { time: None, ..time(None) }),
+    System::Sys525_60,
+    Some(RecordingTimestamp::DateOnly(NaiveDate::from_ymd_opt(1997, 8, 27).unwrap()))
+)]
+#[case::time_only(
+    None,
+    Some(time(Some(3))),
+    System::Sys525_60,
+    Some(RecordingTimestamp::TimeOnly(NaiveTime::from_hms_milli_opt(13, 45, 30, 100).unwrap()))
+)]
+#[case::empty_date(
+    Some(pack::RecordingDate { date: None, weekday: None, ..date(None, None) }),
+    None,
+    System::Sys525_60,
+    None
+)]
+#[case::nothing(None, None, System::Sys525_60, None)]
+fn test_recording_timestamp_from_packs(
+    #[case] date: Option<pack::RecordingDate>,
+    #[case] time: Option<pack::RecordingTime>,
+    #[case] system: System,
+    #[case] expected: Option<RecordingTimestamp>,
+) {
+    expect_that!(
+        RecordingTimestamp::from_packs(date.as_ref(), time.as_ref(), system),
+        eq(expected)
+    );
+}
+
+#[googletest::test]
+fn test_recording_timestamp_from_frame() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let expected = NaiveDate::from_ymd_opt(2024, 7, 8).unwrap().and_hms_opt(19, 55, 58).unwrap();
+    let timestamp = RecordingTimestamp::from_frame(&frames[0]);
+    expect_that!(timestamp, some(eq(RecordingTimestamp::NoTimeZone(expected))));
+    expect_that!(timestamp.and_then(|t| t.naive_date_time()), some(eq(expected)));
+    expect_that!(timestamp.and_then(|t| t.date_time()), none());
+
+    // The AAUX packs are used when the VAUX packs are missing.
+    let mut frame = frames[0].clone();
+    let ctx = frame.pack_context();
+    for (vaux_pack, _) in frame.vaux_packs() {
+        frame.set_vaux_pack(&vaux_pack.position, &pack::Pack::from_raw(&[0xFF; 5], &ctx).0);
+    }
+    expect_that!(
+        RecordingTimestamp::from_frame(&frame),
+        some(eq(RecordingTimestamp::NoTimeZone(expected)))
+    );
+}
@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Frame, VAUX_BLOCK_COUNT};
+use crate::pack;
+
+#[cfg(test)]
+mod tests;
+
+/// Number of pack slots in each VAUX DIF block.
+pub const VAUX_PACKS_PER_BLOCK: usize = 15;
+
+/// Number of pack slots in the VAUX section of each DIF sequence.
+pub const VAUX_PACK_COUNT: usize = VAUX_PACKS_PER_BLOCK * VAUX_BLOCK_COUNT;
+
+/// Size of a pack in bytes, including the pack header byte.
+const PACK_SIZE: usize = 5;
+
+/// Number of pack slots in the main area of each DIF sequence.
+const MAIN_AREA_PACK_COUNT: usize = 6;
+
+/// First pack number of the main area in even-numbered DIF sequences.
+const EVEN_SEQUENCE_MAIN_AREA_START: usize = 39;
+
+/// First pack number of the main area in odd-numbered DIF sequences.
+const ODD_SEQUENCE_MAIN_AREA_START: usize = 0;
+
+/// Area of the VAUX or audio section that a pack slot belongs to.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub enum PackArea {
+    /// The main area, which starts with the source, source control, recording date, and recording
+    /// time packs, in that order.  The remaining slots are empty if the recording device had no
+    /// further information to record there.
+    Main,
+
+    /// The optional area, which holds any other packs the recording device chose to record.
+    Optional,
+}
+
+/// Location of a VAUX pack slot within a DV frame.
+///
+/// - IEC 61834-2:1998 - SD format for 525-60 and 625-50 systems
+/// - SMPTE 306M-2002 - 6.35-mm Type D-7 Component Format
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub struct VAUXPackPosition {
+    /// Index of the channel within the frame.
+    pub channel: usize,
+
+    /// Index of the DIF sequence within the channel.
+    pub dif_sequence: usize,
+
+    /// Index of the VAUX DIF block within the DIF sequence, in the range `[0, 2]`.
+    pub block: usize,
+
+    /// Index of the pack slot within the VAUX DIF block, in the range `[0, 14]`.
+    pub slot: usize,
+}
+
+impl VAUXPackPosition {
+    /// Number of the pack slot within the VAUX section of the DIF sequence, in the range
+    /// `[0, 44]`.
+    pub fn pack_number(&self) -> usize {
+        self.block * VAUX_PACKS_PER_BLOCK + self.slot
+    }
+
+    /// Area of the VAUX section that the pack slot belongs to.
+    ///
+    /// The main area consists of pack numbers 39 through 44 in even-numbered DIF sequences, and
+    /// pack numbers 0 through 5 in odd-numbered DIF sequences.  All other pack slots are in the
+    /// optional area.
+    pub fn area(&self) -> PackArea {
+        self.main_area_index().map_or(PackArea::Optional, |_| PackArea::Main)
+    }
+
+    /// Index of the pack slot within the main area, or None if it is in the optional area.
+    pub(super) fn main_area_index(&self) -> Option<usize> {
+        let main_area_start = if self.dif_sequence % 2 == 0 {
+            EVEN_SEQUENCE_MAIN_AREA_START
+        } else {
+            ODD_SEQUENCE_MAIN_AREA_START
+        };
+        self.pack_number()
+            .checked_sub(main_area_start)
+            .filter(|index| *index < MAIN_AREA_PACK_COUNT)
+    }
+
+    /// Offset of the pack slot within the payload of its VAUX DIF block.
+    fn data_offset(&self) -> usize {
+        self.slot * PACK_SIZE
+    }
+}
+
+/// A pack that was read from a VAUX pack slot, along with the location of the slot.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct VAUXPack {
+    /// Location of the pack slot within the frame.
+    pub position: VAUXPackPosition,
+
+    /// The pack stored in the slot.
+    pub pack: pack::Pack,
+}
+
+impl Frame {
+    /// Deserialize the packs in every VAUX pack slot of the frame.
+    ///
+    /// There are 45 slots in each DIF sequence, and they are returned in the order that they are
+    /// stored in the file.  Empty slots are returned as [`pack::Pack::NoInfo`].  Each pack has the
+    /// same meaning as the return value of [`pack::Pack::from_raw`], and any pack parsing error is
+    /// returned alongside it.
+    pub fn vaux_packs(&self) -> Vec<(VAUXPack, Option<pack::RawError>)> {
+        let ctx = self.pack_context();
+        let mut packs = Vec::with_capacity(
+            self.channels.iter().map(|c| c.dif_sequences.len()).sum::<usize>() * VAUX_PACK_COUNT,
+        );
+        for (channel_index, channel) in self.channels.iter().enumerate() {
+            for (sequence_index, sequence) in channel.dif_sequences.iter().enumerate() {
+                for (block_index, block) in sequence.vaux.iter().enumerate() {
+                    for slot in 0..VAUX_PACKS_PER_BLOCK {
+                        let position = VAUXPackPosition {
+                            channel: channel_index,
+                            dif_sequence: sequence_index,
+                            block: block_index,
+                            slot,
+                        };
+                        let (pack, err) = block.pack_at(position.data_offset(), &ctx);
+                        packs.push((VAUXPack { position, pack }, err));
+                    }
+                }
+            }
+        }
+        packs
+    }
+
+    /// Serialize the pack into the given VAUX pack slot of the frame.
+    ///
+    /// Only the bytes occupied by the pack slot are modified.  The function will panic if the
+    /// position does not exist in the frame.
+    pub fn set_vaux_pack(&mut self, position: &VAUXPackPosition, pack: &pack::Pack) {
+        let ctx = self.pack_context();
+        self.channels[position.channel].dif_sequences[position.dif_sequence].vaux[position.block]
+            .set_pack_at(position.data_offset(), pack, &ctx);
+    }
+}
@@ -0,0 +1,95 @@
+use chrono::NaiveDate;
+use googletest::prelude::*;
+use rstest::rstest;
+
+use super::*;
+use crate::file::testutil::{read_test_frames, SONY_GOOD_QUALITY};
+
+#[googletest::test]
+#[rstest]
+#[case::even_sequence_first_slot(0, 0, 0, PackArea::Optional)]
+#[case::even_sequence_main_start(0, 2, 9, PackArea::Main)]
+#[case::even_sequence_before_main(4, 2, 8, PackArea::Optional)]
+#[case::even_sequence_main_end(4, 2, 14, PackArea::Main)]
+#[case::odd_sequence_main_start(1, 0, 0, PackArea::Main)]
+#[case::odd_sequence_main_end(9, 0, 5, PackArea::Main)]
+#[case::odd_sequence_after_main(9, 0, 6, PackArea::Optional)]
+#[case::odd_sequence_last_slot(11, 2, 14, PackArea::Optional)]
+fn test_vaux_pack_position_area(
+    #[case] dif_sequence: usize,
+    #[case] block: usize,
+    #[case] slot: usize,
+    #[case] expected: PackArea,
+) {
+    let position = VAUXPackPosition { channel: 0, dif_sequence, block, slot };
+    expect_that!(position.area(), eq(expected));
+}
+
+#[googletest::test]
+fn test_frame_vaux_packs() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let packs = frames[0].vaux_packs();
+    expect_that!(packs.len(), eq(10 * VAUX_PACK_COUNT));
+    expect_that!(packs.iter().all(|(_, err)| err.is_none()), eq(true));
+
+    // Every DIF sequence has the four main area packs, followed by two empty main area slots
+    let main: Vec<_> = packs.iter().filter(|(p, _)| p.position.area() == PackArea::Main).collect();
+    expect_that!(main.len(), eq(10 * 6));
+    for chunk in main.chunks_exact(6) {
+        expect_that!(chunk[0].0.pack.pack_type(), eq(pack::Type::VAUXSource));
+        expect_that!(chunk[1].0.pack.pack_type(), eq(pack::Type::VAUXSourceControl));
+        expect_that!(chunk[2].0.pack.pack_type(), eq(pack::Type::VAUXRecordingDate));
+        expect_that!(chunk[3].0.pack.pack_type(), eq(pack::Type::VAUXRecordingTime));
+        expect_that!(chunk[4].0.pack.pack_type(), eq(pack::Type::NoInfo));
+        expect_that!(chunk[5].0.pack.pack_type(), eq(pack::Type::NoInfo));
+    }
+    expect_that!(
+        main[6].0.position,
+        eq(VAUXPackPosition { channel: 0, dif_sequence: 1, block: 0, slot: 0 })
+    );
+
+    // The camera packs are only in the optional area of the first DIF sequence
+    let optional: Vec<_> = packs
+        .iter()
+        .filter(|(p, _)| p.position.area() == PackArea::Optional)
+        .filter(|(p, _)| p.pack.pack_type() != pack::Type::NoInfo)
+        .collect();
+    expect_that!(optional.len(), eq(3));
+    expect_that!(optional[0].0.pack.pack_type(), eq(pack::Type::CameraConsumer1));
+    expect_that!(
+        optional[2].0.position,
+        eq(VAUXPackPosition { channel: 0, dif_sequence: 0, block: 0, slot: 2 })
+    );
+}
+
+#[googletest::test]
+fn test_frame_set_vaux_pack() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let mut frame = frames[0].clone();
+    let position = VAUXPackPosition { channel: 0, dif_sequence: 0, block: 2, slot: 11 };
+    let (original, _) = frame.vaux_packs()[position.pack_number()];
+    expect_that!(original.position, eq(position));
+
+    // Change the recording date to the next day
+    let pack::Pack::VAUXRecordingDate(mut date) = original.pack else {
+        panic!("expected a recording date pack, got {:?}", original.pack);
+    };
+    date.0 = garde::Unvalidated::new(pack::RecordingDate {
+        date: Some(NaiveDate::from_ymd_opt(2024, 7, 25).unwrap()),
+        ..*date.0
+    })
+    .validate_with(&frame.pack_context())
+    .unwrap();
+    frame.set_vaux_pack(&position, &pack::Pack::VAUXRecordingDate(date));
+
+    // Only that one pack slot is affected
+    let updated = frame.vaux_packs();
+    for ((before, _), (after, _)) in frames[0].vaux_packs().iter().zip(updated.iter()) {
+        if before.position == position {
+            expect_that!(after.pack, eq(pack::Pack::VAUXRecordingDate(date)));
+            expect_that!(after.pack, not(eq(before.pack)));
+        } else {
+            expect_that!(after, eq(before));
+        }
+    }
+}
@@ -5,6 +5,7 @@
 use itertools::Itertools;
 use num::{rational::Ratio, CheckedMul, ToPrimitive};
 use rsmpeg::{avformat::AVInputFormat, avutil};
+use serde::{Deserialize, Serialize};
 use snafu::{prelude::*, FromString};
 
 use crate::{ffutil, ioutil};
@@ -16,7 +17,7 @@
 ///
 /// The choice of system determines the entire layout of a DV file, as well as how many parts of
 /// the DV file are interpreted.
-#[derive(Debug, Eq, PartialEq, Clone, Copy, Display)]
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy, Display, Serialize, Deserialize)]
 pub enum System {
     /// IEC 61834-2: SD format for 525-60 system
     ///
@@ -61,10 +62,15 @@ pub struct Info {
     /// The DV format supports a variety of channel layouts, including mono, combinations of mono
     /// and stereo sound, etc.  FFmpeg doesn't interpret the channel layout subcode data, and
     /// always assumes stereo, so that's what we'll do as well.
+    ///
+    /// Each channel on the videotape holds up to two stereo audio streams.  There are two of them
+    /// when the audio is recorded in the 32 kHz 12-bit four-channel mode (CH1/CH2 and CH3/CH4),
+    /// which is common on consumer camcorders.  FFmpeg expands the 12-bit samples, so the streams
+    /// still consist of 16-bit samples.  See [`crate::audio`] for more details.
     //
     // NOTE: DVCPRO50 at https://archive.org/details/SMPTEColorBarsBadTracking has no audio...
     // So zero audio channels is apparently a thing.
-    #[garde(range(min = 0, max = 2))]
+    #[garde(range(min = 0, max = 4), custom(audio_stereo_stream_count_valid(&self)))]
     pub audio_stereo_stream_count: u8,
 
     /// Audio sample rate in Hz.
@@ -386,6 +392,23 @@ fn video_duration_validations<T>(info: &Info) -> impl FnOnce(&T, &()) -> garde::
     }
 }
 
+fn audio_stereo_stream_count_valid(info: &Info) -> impl FnOnce(&u8, &()) -> garde::Result + '_ {
+    move |stream_count, _| {
+        // Frame size errors are reported by video_duration_validations
+        let Ok((channel_count, _)) = info.try_video_frame_info() else {
+            return Ok(());
+        };
+        if *stream_count > channel_count * 2 {
+            return Err(garde::Error::new(format!(
+                "Audio stereo stream count {stream_count} is too high for a frame with \
+                {channel_count} channel(s), which can hold at most {} stereo stream(s)",
+                channel_count * 2
+            )));
+        }
+        Ok(())
+    }
+}
+
 fn audio_sample_rate_valid(info: &Info) -> impl FnOnce(&Option<u32>, &()) -> garde::Result + '_ {
     move |sample_rate, _| {
         // Sample rate should be present if and only if there are some audio streams
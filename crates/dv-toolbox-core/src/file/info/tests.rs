@@ -169,6 +169,40 @@ struct InfoValidationTestCase<'a> {
             ideal_audio_samples_per_frame: None,
         }),
     },
+    "ntsc_32k_4_stream_2_channel",
+    InfoValidationTestCase {
+        info: Info {
+            file_size: 960_000,
+            video_frame_rate: Ratio::<u32>::new(30_000, 1_001),
+            video_duration: Ratio::<u128>::new(1_001 * 4, 30_000),
+            audio_stereo_stream_count: 4,
+            audio_sample_rate: Some(32_000),
+        },
+        err: None,
+        derived: Some(DerivedFields {
+            video_frame_count: 4,
+            video_frame_size: 240_000,
+            video_frame_channel_count: 2,
+            video_frame_dif_sequence_count: 10,
+            system: System::Sys525_60,
+            ideal_audio_samples_per_frame: Some(Ratio::<u32>::new(16_016, 15)),
+        }),
+    },
+    "too_many_audio_streams",
+    InfoValidationTestCase {
+        info: Info {
+            file_size: 600_000,
+            video_frame_rate: Ratio::<u32>::new(30_000, 1_001),
+            video_duration: Ratio::<u128>::new(1_001 * 5, 30_000),
+            audio_stereo_stream_count: 3,
+            audio_sample_rate: Some(32_000),
+        },
+        err: Some(
+            "audio_stereo_stream_count: Audio stereo stream count 3 is too high for a frame \
+            with 1 channel(s), which can hold at most 2 stereo stream(s)\n",
+        ),
+        derived: None,
+    },
     "non_integer_frame_count",
     InfoValidationTestCase {
         info: Info {
@@ -287,6 +321,8 @@ struct InfoValidationTestCase<'a> {
 #[case::pal_44_1k(function_name!())]
 #[case::ntsc_no_audio_2_channel(function_name!())]
 #[case::pal_no_audio_2_channel(function_name!())]
+#[case::ntsc_32k_4_stream_2_channel(function_name!())]
+#[case::too_many_audio_streams(function_name!())]
 #[case::non_integer_frame_count(function_name!())]
 #[case::zero_length(function_name!())]
 #[case::weird_file_size(function_name!())]
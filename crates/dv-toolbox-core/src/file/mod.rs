@@ -1,5 +1,12 @@
 //! Structures and functions related to working with entire DV files.
 
 mod info;
+mod reader;
+mod writer;
+
+#[cfg(test)]
+pub(crate) mod testutil;
 
 pub use info::*;
+pub use reader::*;
+pub use writer::*;
@@ -0,0 +1,108 @@
+use std::io;
+
+use snafu::prelude::*;
+
+use super::{ValidInfo, ValidInfoMethods};
+use crate::{dif, ioutil};
+
+#[cfg(test)]
+mod tests;
+
+/// Reads [`dif::Frame`] values from a bare DV file.
+///
+/// The geometry of each frame is determined from the [`ValidInfo`] of the file: every frame is
+/// exactly [`ValidInfoMethods::video_frame_size`] bytes long, and is split into
+/// [`ValidInfoMethods::video_frame_channel_count`] channels that each have
+/// [`ValidInfoMethods::video_frame_dif_sequence_count`] DIF sequences.
+///
+/// The reader can also be used as an [`Iterator`] over the remaining frames in the file.
+#[derive(Debug)]
+pub struct FrameReader<R: io::Read + io::Seek> {
+    reader: R,
+    file_info: ValidInfo,
+    next_frame_index: u64,
+}
+
+impl<R: io::Read + io::Seek> FrameReader<R> {
+    /// Creates a new frame reader that reads from the given bare DV file.
+    ///
+    /// The current position in the file will be ignored.  The function will always seek to the
+    /// start, so that the first frame is read next.
+    pub fn new(reader: R, file_info: ValidInfo) -> FrameResult<Self> {
+        let mut frame_reader = Self { reader, file_info, next_frame_index: 0 };
+        frame_reader.seek_frame(0)?;
+        Ok(frame_reader)
+    }
+
+    /// Information about the file that the frames are read from.
+    pub fn file_info(&self) -> &ValidInfo {
+        &self.file_info
+    }
+
+    /// Zero-based index of the frame that will be returned by the next call to
+    /// [`FrameReader::read_frame`].
+    pub fn next_frame_index(&self) -> u64 {
+        self.next_frame_index
+    }
+
+    /// Seeks to the frame with the given zero-based index, so that it is read next.
+    ///
+    /// Seeking to the position immediately after the last frame is allowed; the next read will
+    /// then indicate that there are no more frames.
+    pub fn seek_frame(&mut self, frame_index: u64) -> FrameResult<()> {
+        ensure_whatever!(
+            frame_index <= self.file_info.video_frame_count(),
+            "Frame index {frame_index} is beyond the end of the file, which has {} frames",
+            self.file_info.video_frame_count()
+        );
+        let position = frame_index * u64::from(self.file_info.video_frame_size());
+        ioutil::retry_if_interrupted(|| self.reader.seek(io::SeekFrom::Start(position)))
+            .with_whatever_context(|_| format!("Could not seek to frame {frame_index}"))?;
+        self.next_frame_index = frame_index;
+        Ok(())
+    }
+
+    /// Reads the next frame from the file.
+    ///
+    /// Returns [`None`] if there are no more frames in the file.
+    pub fn read_frame(&mut self) -> FrameResult<Option<dif::Frame>> {
+        if self.next_frame_index >= self.file_info.video_frame_count() {
+            return Ok(None);
+        }
+        let mut raw = vec![0; usize::try_from(self.file_info.video_frame_size()).unwrap()];
+        self.reader.read_exact(&mut raw).with_whatever_context(|_| {
+            format!("Could not read frame {} from the file", self.next_frame_index)
+        })?;
+        self.next_frame_index += 1;
+        Ok(Some(dif::Frame::from_raw(&raw, &self.file_info)))
+    }
+
+    /// Unwraps this reader, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: io::Read + io::Seek> Iterator for FrameReader<R> {
+    type Item = FrameResult<dif::Frame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_frame().transpose()
+    }
+}
+
+/// Result type for calls related to reading and writing frames.
+pub type FrameResult<T, E = FrameError> = std::result::Result<T, E>;
+
+/// Error type for calls related to reading and writing frames.
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum FrameError {
+    #[snafu(whatever, display("{message}"))]
+    Whatever {
+        message: String,
+        #[snafu(source(from(Box<dyn std::error::Error>, Some)))]
+        source: Option<Box<dyn std::error::Error>>,
+        backtrace: snafu::Backtrace,
+    },
+}
@@ -0,0 +1,84 @@
+use std::{fs::File, io::Cursor};
+
+use display_error_chain::ErrorChainExt;
+use googletest::prelude::*;
+
+use super::*;
+use crate::{dif::SectionType, file::testutil::SONY_GOOD_QUALITY, testutil::*};
+
+fn open_sony_good_quality() -> FrameReader<File> {
+    let file = File::open(test_resource("dv_multiframe/sony_good_quality.dv")).unwrap();
+    FrameReader::new(file, *SONY_GOOD_QUALITY).unwrap()
+}
+
+#[googletest::test]
+fn test_read_all_frames() {
+    let frames: Vec<_> = open_sony_good_quality().collect::<FrameResult<_>>().unwrap();
+
+    expect_that!(frames.len(), eq(5));
+    for frame in frames {
+        expect_that!(frame.channels.len(), eq(1));
+        for channel in frame.channels {
+            expect_that!(channel.dif_sequences.len(), eq(10));
+            for (seq_num, seq) in channel.dif_sequences.iter().enumerate() {
+                let seq_num = u8::try_from(seq_num).unwrap();
+                // Each block should have been placed into the section that its ID identifies
+                let sections = [
+                    (SectionType::Header, std::slice::from_ref(&seq.header)),
+                    (SectionType::Subcode, &seq.subcode[..]),
+                    (SectionType::VAUX, &seq.vaux[..]),
+                    (SectionType::Audio, &seq.audio[..]),
+                    (SectionType::Video, &seq.video[..]),
+                ];
+                for (section_type, blocks) in sections {
+                    for (block_num, block) in blocks.iter().enumerate() {
+                        expect_that!(block.id.section_type, eq(section_type));
+                        expect_that!(block.id.dif_sequence_number.value(), eq(seq_num));
+                        expect_that!(usize::from(block.id.dif_block_number), eq(block_num));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[googletest::test]
+fn test_seek_frame() {
+    let mut reader = open_sony_good_quality();
+    let first = reader.read_frame().unwrap().unwrap();
+    expect_that!(reader.next_frame_index(), eq(1));
+
+    // Seek to the last frame, which should be followed by the end of the file
+    reader.seek_frame(4).unwrap();
+    let last = reader.read_frame().unwrap().unwrap();
+    expect_that!(last, not(eq(&first)));
+    expect_that!(reader.read_frame().unwrap(), none());
+    expect_that!(reader.next(), none());
+
+    // Seek back to the first frame
+    reader.seek_frame(0).unwrap();
+    expect_that!(reader.read_frame().unwrap(), some(eq(&first)));
+
+    // Seeking past the end is not allowed
+    expect_that!(
+        reader.seek_frame(6).map_err(|e| e.chain().to_string()),
+        err(eq("Frame index 6 is beyond the end of the file, which has 5 frames"))
+    );
+}
+
+#[googletest::test]
+fn test_read_truncated_file() {
+    let mut data = std::fs::read(test_resource("dv_multiframe/sony_good_quality.dv")).unwrap();
+    data.truncate(data.len() - 1);
+    let mut reader = FrameReader::new(Cursor::new(data), *SONY_GOOD_QUALITY).unwrap();
+
+    for _ in 0..4 {
+        expect_that!(reader.read_frame(), ok(some(anything())));
+    }
+    expect_that!(
+        reader.read_frame().map_err(|e| e.chain().to_string()),
+        err(eq(
+            "Could not read frame 4 from the file\nCaused by:\n  -> failed to fill whole buffer"
+        ))
+    );
+}
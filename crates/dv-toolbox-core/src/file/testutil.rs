@@ -0,0 +1,70 @@
+use std::{fs::File, io::Cursor, sync::LazyLock};
+
+use garde::Unvalidated;
+use num::rational::Ratio;
+
+use super::*;
+use crate::{dif, video::Plane};
+
+/// File information for the `dv_multiframe/sony_good_quality.dv` test file.
+///
+/// The information is constructed directly, instead of being read with FFmpeg, so that tests of
+/// DIF-level functionality aren't dependent on FFmpeg.
+pub(crate) static SONY_GOOD_QUALITY: LazyLock<ValidInfo> = LazyLock::new(|| {
+    Unvalidated::new(Info {
+        file_size: 600_000,
+        video_frame_rate: Ratio::<u32>::new(30_000, 1_001),
+        video_duration: Ratio::<u128>::new(1_001 * 5, 30_000),
+        audio_stereo_stream_count: 2,
+        audio_sample_rate: Some(32_000),
+    })
+    .validate()
+    .unwrap()
+});
+
+/// File information for a single blank 625-50 frame.
+pub(crate) static PAL_SINGLE_FRAME: LazyLock<ValidInfo> = LazyLock::new(|| {
+    Unvalidated::new(Info {
+        file_size: 144_000,
+        video_frame_rate: Ratio::<u32>::from(25),
+        video_duration: Ratio::<u128>::new(1, 25),
+        audio_stereo_stream_count: 0,
+        audio_sample_rate: None,
+    })
+    .validate()
+    .unwrap()
+});
+
+/// Reads all frames from the given test resource file.
+pub(crate) fn read_test_frames(path: &str, file_info: &ValidInfo) -> Vec<dif::Frame> {
+    let file = File::open(crate::testutil::test_resource(path)).unwrap();
+    FrameReader::new(file, *file_info).unwrap().collect::<FrameResult<_>>().unwrap()
+}
+
+/// Creates a frame reader that reads the given frames from memory.
+pub(crate) fn frame_reader(
+    frames: &[dif::Frame],
+    file_info: &ValidInfo,
+) -> FrameReader<Cursor<Vec<u8>>> {
+    let raw = frames.iter().flat_map(dif::Frame::to_raw).collect();
+    FrameReader::new(Cursor::new(raw), *file_info).unwrap()
+}
+
+/// Smooth synthetic picture: diagonal gradients in each plane, with a bright rectangle.  Use
+/// different `seed` values for the planes of a picture, and encode them into a frame with
+/// [`crate::video::encode_frame`] to make synthetic test frames.
+pub(crate) fn synthetic_plane(width: usize, height: usize, seed: usize) -> Plane {
+    let mut plane = Plane::new(width, height, 0);
+    for y in 0..height {
+        for x in 0..width {
+            let value =
+                if (width / 3..width / 2).contains(&x) && (height / 4..height / 2).contains(&y) {
+                    235
+                } else {
+                    16 + (x * 160 / width + y * 60 / height + seed) % 200
+                };
+            plane.data[y * width + x] = u8::try_from(value).unwrap();
+        }
+    }
+    plane
+}
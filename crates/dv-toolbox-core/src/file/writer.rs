@@ -0,0 +1,75 @@
+use std::io;
+
+use snafu::prelude::*;
+
+use super::{FrameResult, ValidInfo, ValidInfoMethods};
+use crate::dif;
+
+#[cfg(test)]
+mod tests;
+
+/// Writes [`dif::Frame`] values to a bare DV file.
+///
+/// Every DIF block is written exactly as it is stored in the frame.  Blocks that were not
+/// modified after reading them with [`super::FrameReader`] will therefore be written out
+/// byte-for-byte identical to how they were read.  To modify the packs in a frame, use
+/// [`dif::DIFBlock::set_pack_at`] along with [`dif::Frame::pack_context`].
+#[derive(Debug)]
+pub struct FrameWriter<W: io::Write> {
+    writer: W,
+    file_info: ValidInfo,
+}
+
+impl<W: io::Write> FrameWriter<W> {
+    /// Creates a new frame writer that writes frames with the geometry described by `file_info`.
+    ///
+    /// Frames are written starting at the current position of `writer`.
+    pub fn new(writer: W, file_info: ValidInfo) -> Self {
+        Self { writer, file_info }
+    }
+
+    /// Information about the file that the frames are written to.
+    pub fn file_info(&self) -> &ValidInfo {
+        &self.file_info
+    }
+
+    /// Writes a frame to the file.
+    ///
+    /// The frame must have the same format as the file being written to, and must have the
+    /// expected number of channels and DIF sequences.
+    pub fn write_frame(&mut self, frame: &dif::Frame) -> FrameResult<()> {
+        self.file_info
+            .check_similar(&frame.file_info)
+            .whatever_context("Frame does not have the same format as the file being written")?;
+        ensure_whatever!(
+            frame.channels.len() == usize::from(self.file_info.video_frame_channel_count()),
+            "Frame has {} channels, but {} channels were expected",
+            frame.channels.len(),
+            self.file_info.video_frame_channel_count()
+        );
+        for (channel_num, channel) in frame.channels.iter().enumerate() {
+            ensure_whatever!(
+                channel.dif_sequences.len()
+                    == usize::from(self.file_info.video_frame_dif_sequence_count()),
+                "Channel {channel_num} of the frame has {} DIF sequences, but {} DIF sequences \
+                were expected",
+                channel.dif_sequences.len(),
+                self.file_info.video_frame_dif_sequence_count()
+            );
+        }
+
+        self.writer.write_all(&frame.to_raw()).whatever_context("Could not write frame to file")
+    }
+
+    /// Flushes any buffered frames to the underlying writer.
+    pub fn flush(&mut self) -> FrameResult<()> {
+        self.writer.flush().whatever_context("Could not flush frames to file")
+    }
+
+    /// Unwraps this writer, returning the underlying writer.
+    ///
+    /// The writer is not flushed; call [`FrameWriter::flush`] first if needed.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
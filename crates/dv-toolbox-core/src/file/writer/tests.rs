@@ -0,0 +1,96 @@
+use std::io::Cursor;
+
+use chrono::NaiveDate;
+use display_error_chain::ErrorChainExt;
+use garde::Unvalidated;
+use googletest::prelude::*;
+use num::rational::Ratio;
+
+use super::*;
+use crate::{
+    file::{
+        testutil::{read_test_frames, SONY_GOOD_QUALITY},
+        Info, UnvalidatedInfo,
+    },
+    pack,
+    testutil::*,
+};
+
+fn write_frames(frames: &[dif::Frame]) -> Vec<u8> {
+    let mut writer = FrameWriter::new(Cursor::new(Vec::new()), *SONY_GOOD_QUALITY);
+    for frame in frames {
+        writer.write_frame(frame).unwrap();
+    }
+    writer.flush().unwrap();
+    writer.into_inner().into_inner()
+}
+
+#[googletest::test]
+fn test_write_unmodified_frames() {
+    let original = std::fs::read(test_resource("dv_multiframe/sony_good_quality.dv")).unwrap();
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+
+    expect_that!(write_frames(&frames), eq(&original));
+}
+
+#[googletest::test]
+fn test_write_modified_pack() {
+    let original = std::fs::read(test_resource("dv_multiframe/sony_good_quality.dv")).unwrap();
+    let mut frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+
+    // Change the VAUX recording date in the third VAUX block of the first DIF sequence
+    let ctx = frames[0].pack_context();
+    let block = &mut frames[0].channels[0].dif_sequences[0].vaux[2];
+    let (pack::Pack::VAUXRecordingDate(date), None) = block.pack_at(55, &ctx) else {
+        panic!("expected a valid VAUX recording date pack");
+    };
+    let mut date = **date;
+    date.date = NaiveDate::from_ymd_opt(2024, 7, 9);
+    date.weekday = None;
+    let pack =
+        pack::Pack::VAUXRecordingDate(Unvalidated::new(date).validate_with(&ctx).unwrap().into());
+    block.set_pack_at(55, &pack, &ctx);
+    expect_that!(block.pack_at(55, &ctx).0, eq(pack));
+
+    // Only the bytes of the modified pack may differ from the original file
+    let written = write_frames(&frames);
+    let pack_start = 5 * 80 + 3 + 55;
+    expect_that!(written.len(), eq(original.len()));
+    expect_that!(written[..pack_start], eq(&original[..pack_start]));
+    expect_that!(written[pack_start..pack_start + 5], eq([0x62, 0xFF, 0xC9, 0xE7, 0x24]));
+    expect_that!(written[pack_start + 5..], eq(&original[pack_start + 5..]));
+}
+
+#[googletest::test]
+fn test_write_wrong_format() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let pal_info = UnvalidatedInfo::new(Info {
+        file_size: 144_000,
+        video_frame_rate: Ratio::<u32>::from(25),
+        video_duration: Ratio::<u128>::new(1, 25),
+        audio_stereo_stream_count: 2,
+        audio_sample_rate: Some(32_000),
+    })
+    .validate()
+    .unwrap();
+
+    let mut writer = FrameWriter::new(Cursor::new(Vec::new()), pal_info);
+    expect_that!(
+        writer.write_frame(&frames[0]).map_err(|e| e.chain().to_string()),
+        err(eq("Frame does not have the same format as the file being written\n\
+            Caused by:\n  \
+            -> Video frame rate 30000/1001 does not match 25"))
+    );
+}
+
+#[googletest::test]
+fn test_write_wrong_sequence_count() {
+    let mut frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    frames[0].channels[0].dif_sequences.pop();
+
+    let mut writer = FrameWriter::new(Cursor::new(Vec::new()), *SONY_GOOD_QUALITY);
+    expect_that!(
+        writer.write_frame(&frames[0]).map_err(|e| e.chain().to_string()),
+        err(eq("Channel 0 of the frame has 9 DIF sequences, but 10 DIF sequences were expected"))
+    );
+}
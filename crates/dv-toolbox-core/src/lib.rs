@@ -8,11 +8,17 @@
 // TODO: Dead code and unused imports are sometimes allowed while this crate is under development.
 // Eventually, they should be removed.
 
+pub mod audio;
+pub mod dif;
 #[allow(dead_code)]
 mod ffutil;
 pub mod file;
 mod ioutil;
+pub mod merge;
 pub mod pack;
+pub mod scene;
+pub mod timecode;
+pub mod video;
 
 #[cfg(test)]
 mod testutil;
@@ -0,0 +1,316 @@
+use std::{collections::HashMap, io};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use snafu::prelude::*;
+
+use super::MergeResult;
+use crate::{
+    dif,
+    file::{FrameReader, ValidInfoMethods},
+    pack,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// What is known about where a frame was recorded on the tape, for the purpose of finding the same
+/// frame in several captures of the tape.
+///
+/// Each value is the consensus of the copies stored in the frame; see [`dif::FrameMetadata`].
+/// Values that could not be read are None.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct FrameIdentity {
+    /// Time of the frame from the title timecode pack in the subcode.
+    pub title_timecode: Option<pack::TimeValueWithRequiredFrame>,
+
+    /// Date that the frame was recorded, from the VAUX recording date pack, or from the AAUX
+    /// recording date pack if there is no VAUX one.
+    pub recording_date: Option<NaiveDate>,
+
+    /// Time that the frame was recorded, from the VAUX recording time pack, or from the AAUX
+    /// recording time pack if there is no VAUX one.
+    pub recording_time: Option<pack::TimeValueWithOptionalFrame>,
+
+    /// Absolute track number of the first track of the frame, which counts tracks from the start
+    /// of the tape.  See [`dif::SubcodeSection::absolute_track_number`].
+    pub absolute_track_number: Option<u32>,
+}
+
+/// Value of a [`FrameIdentity`] that is specific enough to identify a single frame on its own.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+enum IdentityKey {
+    TitleTimecode(pack::TimeValueWithRequiredFrame),
+    AbsoluteTrackNumber(u32),
+}
+
+impl FrameIdentity {
+    /// Read the identity of the frame from its metadata packs and subcode.
+    pub fn from_frame(frame: &dif::Frame) -> Self {
+        let metadata = dif::FrameMetadata::from_frame(frame);
+        let pack = |vaux_type, aaux_type| {
+            metadata.pack(vaux_type).or_else(|| metadata.aaux_pack(0, aaux_type))
+        };
+        let recording_date =
+            match pack(pack::Type::VAUXRecordingDate, pack::Type::AAUXRecordingDate) {
+                Some(pack::Pack::VAUXRecordingDate(date) | pack::Pack::AAUXRecordingDate(date)) => {
+                    date.date
+                }
+                _ => None,
+            };
+        let recording_time =
+            match pack(pack::Type::VAUXRecordingTime, pack::Type::AAUXRecordingTime) {
+                Some(pack::Pack::VAUXRecordingTime(time) | pack::Pack::AAUXRecordingTime(time)) => {
+                    time.time
+                }
+                _ => None,
+            };
+
+        // Each DIF sequence of a 25 mbps frame is recorded in its own track, so the absolute
+        // track number of the frame can be found from any DIF sequence with an intact one.
+        let ctx = frame.pack_context();
+        let absolute_track_number = frame.channels.first().and_then(|channel| {
+            channel.dif_sequences.iter().enumerate().find_map(|(index, sequence)| {
+                let (subcode, _) = dif::SubcodeSection::from_blocks(&sequence.subcode, &ctx);
+                subcode.absolute_track_number()?.number.checked_sub(u32::try_from(index).ok()?)
+            })
+        });
+
+        Self {
+            title_timecode: match metadata.pack(pack::Type::TitleTimecode) {
+                Some(pack::Pack::TitleTimecode(timecode)) => Some(timecode.timecode.time),
+                _ => None,
+            },
+            recording_date,
+            recording_time,
+            absolute_track_number,
+        }
+    }
+
+    /// Values of the identity that can identify a single frame on their own.
+    fn keys(&self) -> impl Iterator<Item = IdentityKey> {
+        [
+            self.title_timecode.map(IdentityKey::TitleTimecode),
+            self.absolute_track_number.map(IdentityKey::AbsoluteTrackNumber),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    /// Whether the identity has enough information to find the frame in other captures: either a
+    /// title timecode or an absolute track number.
+    pub fn is_identifiable(&self) -> bool {
+        self.keys().next().is_some()
+    }
+
+    /// Whether two identities could belong to the same frame on the tape.
+    ///
+    /// Both identities must share a title timecode or an absolute track number, and every value
+    /// that both identities have must be equal.  The recording date and time only distinguish
+    /// frames that were recorded at different times but have the same title timecode.
+    pub fn matches(&self, other: &Self) -> bool {
+        fn agree<T: PartialEq>(a: Option<T>, b: Option<T>) -> bool {
+            a.zip(b).map_or(true, |(a, b)| a == b)
+        }
+        let shares_key = self.keys().any(|key| other.keys().any(|other_key| key == other_key));
+        shares_key
+            && agree(self.title_timecode, other.title_timecode)
+            && agree(self.recording_date, other.recording_date)
+            && agree(self.recording_time, other.recording_time)
+            && agree(self.absolute_track_number, other.absolute_track_number)
+    }
+}
+
+/// A single frame of the tape, along with where it was found in each capture.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct AlignedFrame {
+    /// Identity of the frame, as read from the first capture that has it.
+    pub identity: FrameIdentity,
+
+    /// Zero-based index of the frame within each capture, in the order that the captures were
+    /// given.  The value is None if the capture does not have the frame.
+    pub frames: Vec<Option<u64>>,
+}
+
+/// How the frames of a single capture relate to the aligned frames.
+#[derive(Debug, PartialEq, Eq, Clone, Default, Serialize, Deserialize)]
+pub struct CaptureAlignment {
+    /// Frames of the capture that repeat the frame right before them, which happens when the
+    /// capture duplicated a frame.  They are not part of the aligned frames.
+    pub inserted_frames: Vec<u64>,
+
+    /// Indices of the aligned frames that the capture does not have, which happens when the
+    /// capture skipped them.  See [`Alignment::frames`].
+    pub dropped_frames: Vec<usize>,
+
+    /// Frames of the capture that could not be identified, because they have neither a title
+    /// timecode nor an absolute track number, or because they only match aligned frames out of
+    /// sequence.  They are not part of the aligned frames.
+    pub unmatched_frames: Vec<u64>,
+}
+
+/// Frames of several captures of a tape, lined up with each other.
+#[derive(Debug, PartialEq, Eq, Clone, Default, Serialize, Deserialize)]
+pub struct Alignment {
+    /// Every frame found in at least one capture, in the order that they were recorded on the
+    /// tape.
+    pub frames: Vec<AlignedFrame>,
+
+    /// How each capture relates to the aligned frames, in the order that the captures were
+    /// given.
+    pub captures: Vec<CaptureAlignment>,
+}
+
+/// Where a frame of a capture was placed while aligning it with the previous captures.
+enum Placement {
+    /// The frame matched an existing aligned frame.
+    Matched(usize),
+
+    /// The frame is new, and is placed before the aligned frame with the given index.
+    New(usize),
+
+    /// The frame repeats the frame right before it.
+    Inserted,
+
+    /// The frame could not be identified, or could not be placed in sequence.
+    Unmatched,
+}
+
+/// Place the frames of a capture among the frames that were aligned so far.
+///
+/// Frames are matched in order: each frame is matched with the first aligned frame after the
+/// previous match that has a matching identity.  Frames that match nothing become new frames.
+///
+/// A match that skips over aligned frames must be corroborated by the next frame of the capture
+/// with a different identity, which must match the aligned frame right after it.  Otherwise, a
+/// single frame whose damaged identity happens to match a frame much further along the tape would
+/// pull the rest of the capture out of line.  A frame whose only matches are not corroborated is
+/// left unmatched.
+fn place_frames(aligned: &[AlignedFrame], identities: &[FrameIdentity]) -> Vec<Placement> {
+    let mut index: HashMap<IdentityKey, Vec<usize>> = HashMap::new();
+    for (position, frame) in aligned.iter().enumerate() {
+        for key in frame.identity.keys() {
+            index.entry(key).or_default().push(position);
+        }
+    }
+
+    let mut next = 0;
+    let mut previous: Option<&FrameIdentity> = None;
+    let mut placements = Vec::with_capacity(identities.len());
+    for (frame, identity) in identities.iter().enumerate() {
+        if !identity.is_identifiable() {
+            placements.push(Placement::Unmatched);
+            continue;
+        }
+        if previous.is_some_and(|p| p.matches(identity)) {
+            placements.push(Placement::Inserted);
+            continue;
+        }
+        let mut candidates: Vec<_> = identity
+            .keys()
+            .filter_map(|key| index.get(&key))
+            .flatten()
+            .copied()
+            .filter(|position| *position >= next && aligned[*position].identity.matches(identity))
+            .collect();
+        candidates.sort_unstable();
+        candidates.dedup();
+        if candidates.is_empty() {
+            placements.push(Placement::New(next));
+            previous = Some(identity);
+            continue;
+        }
+
+        let following = identities[frame + 1..]
+            .iter()
+            .find(|other| other.is_identifiable() && !other.matches(identity));
+        let corroborated = |position: usize| {
+            position == next
+                || following.map_or(true, |following| {
+                    aligned.get(position + 1).is_some_and(|a| a.identity.matches(following))
+                })
+        };
+        match candidates.into_iter().find(|position| corroborated(*position)) {
+            Some(position) => {
+                next = position + 1;
+                placements.push(Placement::Matched(position));
+                previous = Some(identity);
+            }
+            None => placements.push(Placement::Unmatched),
+        }
+    }
+    placements
+}
+
+/// Line up the frames of several captures of a tape, given the identity of every frame of each
+/// capture.
+///
+/// The first capture is taken as the starting point, and the frames of each further capture are
+/// then matched against the frames found so far.  Frames that are missing from the earlier
+/// captures are added in between, right after the frame that was matched before them, so that
+/// every identifiable frame is part of the alignment.
+pub fn align_captures(captures: &[Vec<FrameIdentity>]) -> Alignment {
+    let mut alignment = Alignment {
+        frames: Vec::new(),
+        captures: vec![CaptureAlignment::default(); captures.len()],
+    };
+    for (capture, identities) in captures.iter().enumerate() {
+        let placements = place_frames(&alignment.frames, identities);
+
+        let mut new_frames: Vec<Vec<AlignedFrame>> = vec![Vec::new(); alignment.frames.len() + 1];
+        for (frame, (identity, placement)) in (0..).zip(identities.iter().zip(placements)) {
+            match placement {
+                Placement::Matched(position) => {
+                    alignment.frames[position].frames[capture] = Some(frame);
+                }
+                Placement::New(position) => {
+                    let mut frames = vec![None; captures.len()];
+                    frames[capture] = Some(frame);
+                    new_frames[position].push(AlignedFrame { identity: *identity, frames });
+                }
+                Placement::Inserted => alignment.captures[capture].inserted_frames.push(frame),
+                Placement::Unmatched => alignment.captures[capture].unmatched_frames.push(frame),
+            }
+        }
+
+        let old_frames = std::mem::take(&mut alignment.frames);
+        for (new, old) in new_frames.iter_mut().zip(old_frames.into_iter().map(Some).chain([None]))
+        {
+            alignment.frames.append(new);
+            alignment.frames.extend(old);
+        }
+    }
+
+    for (capture, capture_alignment) in alignment.captures.iter_mut().enumerate() {
+        capture_alignment.dropped_frames = (0..alignment.frames.len())
+            .filter(|position| alignment.frames[*position].frames[capture].is_none())
+            .collect();
+    }
+    alignment
+}
+
+/// Read the identity of every frame of a capture, starting from the first frame.
+pub fn read_frame_identities<R: io::Read + io::Seek>(
+    capture: &mut FrameReader<R>,
+) -> MergeResult<Vec<FrameIdentity>> {
+    capture.seek_frame(0).whatever_context("Could not seek to the start of the capture")?;
+    let mut identities =
+        Vec::with_capacity(usize::try_from(capture.file_info().video_frame_count()).unwrap());
+    while let Some(frame) = capture.read_frame().whatever_context("Could not read the capture")? {
+        identities.push(FrameIdentity::from_frame(&frame));
+    }
+    Ok(identities)
+}
+
+/// Line up the frames of several captures of a tape; see [`align_captures`].
+pub fn align_files<R: io::Read + io::Seek>(
+    captures: &mut [FrameReader<R>],
+) -> MergeResult<Alignment> {
+    let mut identities = Vec::with_capacity(captures.len());
+    for (index, capture) in captures.iter_mut().enumerate() {
+        identities.push(read_frame_identities(capture).with_whatever_context(|_| {
+            format!("Could not identify the frames of capture {index}")
+        })?);
+    }
+    Ok(align_captures(&identities))
+}
@@ -0,0 +1,197 @@
+use googletest::prelude::*;
+use rstest::rstest;
+
+use super::*;
+use crate::file::testutil::{frame_reader, read_test_frames, SONY_GOOD_QUALITY};
+
+fn timecode(second: u8, frame: u8) -> pack::TimeValueWithRequiredFrame {
+    pack::TimeValue { hour: 0, minute: 0, second, drop_frame: true, frame }
+}
+
+/// Identity of the given frame of a made up recording, which has both a title timecode and an
+/// absolute track number.
+fn identity(frame: u8) -> FrameIdentity {
+    FrameIdentity {
+        title_timecode: Some(timecode(frame / 30, frame % 30)),
+        recording_date: NaiveDate::from_ymd_opt(2024, 7, 8),
+        recording_time: None,
+        absolute_track_number: Some(u32::from(frame) * 10),
+    }
+}
+
+#[googletest::test]
+fn test_frame_identity_from_frame() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    expect_that!(
+        FrameIdentity::from_frame(&frames[0]),
+        eq(FrameIdentity {
+            title_timecode: Some(timecode(2, 17)),
+            recording_date: NaiveDate::from_ymd_opt(2024, 7, 8),
+            recording_time: Some(pack::TimeValue {
+                hour: 19,
+                minute: 55,
+                second: 58,
+                drop_frame: true,
+                frame: None
+            }),
+            absolute_track_number: Some(770),
+        })
+    );
+
+    // The absolute track number can be recovered from later DIF sequences.
+    let mut damaged = frames[4].clone();
+    for sequence in &mut damaged.channels[0].dif_sequences[..3] {
+        sequence.subcode[0].data.fill(0xFF);
+        sequence.subcode[1].data.fill(0xFF);
+    }
+    expect_that!(FrameIdentity::from_frame(&damaged).absolute_track_number, some(eq(810)));
+}
+
+#[googletest::test]
+#[rstest]
+#[case::same(identity(5), true)]
+#[case::different_timecode(identity(6), false)]
+#[case::timecode_only(FrameIdentity { absolute_track_number: None, ..identity(5) }, true)]
+#[case::track_only(FrameIdentity { title_timecode: None, ..identity(5) }, true)]
+#[case::different_date(
+    FrameIdentity { recording_date: NaiveDate::from_ymd_opt(2023, 1, 1), ..identity(5) },
+    false
+)]
+#[case::date_only(
+    FrameIdentity { recording_date: identity(5).recording_date, ..FrameIdentity::default() },
+    false
+)]
+#[case::nothing(FrameIdentity::default(), false)]
+fn test_frame_identity_matches(#[case] other: FrameIdentity, #[case] expected: bool) {
+    expect_that!(identity(5).matches(&other), eq(expected));
+    expect_that!(other.matches(&identity(5)), eq(expected));
+}
+
+#[googletest::test]
+fn test_align_captures() {
+    let first: Vec<_> = [0, 1, 2, 3, 4, 5, 6, 7].map(identity).into();
+    // Skips frames 2 and 3, duplicates frame 5, and has an unidentifiable frame.  The capture
+    // also ran for longer than the first one.
+    let mut second: Vec<_> = [0, 1, 4, 5, 5, 6, 7, 7, 8, 9].map(identity).into();
+    second[7] = FrameIdentity::default();
+    // Starts late, and has a frame that the other captures skipped.
+    let third: Vec<_> = [3, 4, 5, 6, 7, 8, 10].map(identity).into();
+
+    let alignment = align_captures(&[first, second, third]);
+    let rows: Vec<_> = alignment
+        .frames
+        .iter()
+        .map(|f| (f.identity.absolute_track_number.unwrap() / 10, f.frames.clone()))
+        .collect();
+    expect_that!(
+        rows,
+        eq(&vec![
+            (0, vec![Some(0), Some(0), None]),
+            (1, vec![Some(1), Some(1), None]),
+            (2, vec![Some(2), None, None]),
+            (3, vec![Some(3), None, Some(0)]),
+            (4, vec![Some(4), Some(2), Some(1)]),
+            (5, vec![Some(5), Some(3), Some(2)]),
+            (6, vec![Some(6), Some(5), Some(3)]),
+            (7, vec![Some(7), Some(6), Some(4)]),
+            (8, vec![None, Some(8), Some(5)]),
+            // New frames are placed right after the previous match of their capture.
+            (10, vec![None, None, Some(6)]),
+            (9, vec![None, Some(9), None]),
+        ])
+    );
+    expect_that!(
+        alignment.captures,
+        eq(&vec![
+            CaptureAlignment {
+                inserted_frames: vec![],
+                dropped_frames: vec![8, 9, 10],
+                unmatched_frames: vec![],
+            },
+            CaptureAlignment {
+                inserted_frames: vec![4],
+                dropped_frames: vec![2, 3, 9],
+                unmatched_frames: vec![7],
+            },
+            CaptureAlignment {
+                inserted_frames: vec![],
+                dropped_frames: vec![0, 1, 2, 10],
+                unmatched_frames: vec![],
+            },
+        ])
+    );
+}
+
+#[googletest::test]
+fn test_align_captures_repeated_timecode() {
+    // The title timecode restarts with a new recording, which is told apart by its date.
+    let new_recording = |frame| FrameIdentity {
+        recording_date: NaiveDate::from_ymd_opt(2024, 8, 1),
+        ..identity(frame)
+    };
+    let timecode_only =
+        |identity: FrameIdentity| FrameIdentity { absolute_track_number: None, ..identity };
+    let first = vec![identity(0), identity(1), new_recording(0), new_recording(1)];
+    let second = first.iter().copied().map(timecode_only).skip(1).collect();
+
+    let alignment = align_captures(&[first, second]);
+    let rows: Vec<_> = alignment.frames.iter().map(|f| f.frames.clone()).collect();
+    expect_that!(
+        rows,
+        eq(&vec![
+            vec![Some(0), None],
+            vec![Some(1), Some(0)],
+            vec![Some(2), Some(1)],
+            vec![Some(3), Some(2)],
+        ])
+    );
+}
+
+#[googletest::test]
+fn test_align_captures_out_of_sequence_identity() {
+    let first: Vec<_> = (0..10).map(identity).collect();
+    // The identity of frame 3 was damaged into that of frame 8, which is much further along.
+    let mut second = first.clone();
+    second[3] = identity(8);
+
+    let alignment = align_captures(&[first, second]);
+    let rows: Vec<_> = alignment.frames.iter().map(|f| f.frames.clone()).collect();
+    let expected: Vec<_> =
+        (0..10).map(|frame| vec![Some(frame), (frame != 3).then_some(frame)]).collect();
+    expect_that!(rows, eq(&expected));
+    expect_that!(
+        alignment.captures[1],
+        eq(&CaptureAlignment {
+            inserted_frames: vec![],
+            dropped_frames: vec![3],
+            unmatched_frames: vec![3],
+        })
+    );
+}
+
+#[googletest::test]
+fn test_align_files() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let reader = |indices: &[usize]| {
+        let selected: Vec<_> = indices.iter().map(|i| frames[*i].clone()).collect();
+        frame_reader(&selected, &SONY_GOOD_QUALITY)
+    };
+    // The second capture dropped frame 2, and duplicated frame 1 in its place.
+    let mut captures = [reader(&[0, 1, 2, 3, 4]), reader(&[0, 1, 1, 3, 4])];
+    let alignment = align_files(&mut captures).unwrap();
+
+    let rows: Vec<_> = alignment.frames.iter().map(|f| f.frames.clone()).collect();
+    expect_that!(
+        rows,
+        eq(&vec![
+            vec![Some(0), Some(0)],
+            vec![Some(1), Some(1)],
+            vec![Some(2), None],
+            vec![Some(3), Some(3)],
+            vec![Some(4), Some(4)],
+        ])
+    );
+    expect_that!(alignment.frames[2].identity, eq(FrameIdentity::from_frame(&frames[2])));
+    expect_that!(alignment.captures[1].inserted_frames, eq(&vec![2]));
+    expect_that!(alignment.captures[1].dropped_frames, eq(&vec![2]));
+}
@@ -0,0 +1,101 @@
+use std::{fmt, io};
+
+use serde::{Deserialize, Serialize};
+use snafu::prelude::*;
+
+use super::{merge_frames, FrameMergeReport, MergeResult};
+use crate::file::{FrameReader, FrameWriter, ValidInfoMethods};
+
+#[cfg(test)]
+mod tests;
+
+/// Report of how every frame of a file was merged from several captures.
+///
+/// The [`fmt::Display`] implementation prints one line for each frame that took DIF blocks from
+/// captures other than the first one, such as
+/// `frame 1234, 1480 blocks from capture 0, 20 blocks from capture 1, 3 defects remaining`.
+#[derive(Debug, PartialEq, Eq, Clone, Default, Serialize, Deserialize)]
+pub struct MergeReport {
+    /// Every frame of the file, in order.
+    pub frames: Vec<FrameMergeReport>,
+}
+
+impl MergeReport {
+    /// Creates an empty report.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Total number of DIF blocks that were taken from captures other than the first one.
+    pub fn replaced_block_count(&self) -> usize {
+        self.frames.iter().map(|f| f.replaced_blocks.len()).sum()
+    }
+
+    /// Total number of defects that remain across all merged frames.
+    pub fn remaining_defects(&self) -> usize {
+        self.frames.iter().map(|f| f.remaining_defects).sum()
+    }
+}
+
+impl fmt::Display for MergeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for frame in self.frames.iter().filter(|f| !f.replaced_blocks.is_empty()) {
+            write!(f, "frame {}", frame.frame_index)?;
+            for (capture, count) in frame.block_counts.iter().enumerate() {
+                if *count > 0 {
+                    write!(f, ", {count} blocks from capture {capture}")?;
+                }
+            }
+            writeln!(f, ", {} defects remaining", frame.remaining_defects)?;
+        }
+        Ok(())
+    }
+}
+
+/// Merge several captures of a tape into a single DV file; see [`merge_frames`].
+///
+/// The captures must already be aligned, so that frames with the same index hold the same
+/// picture, and they must have the same number of frames.  Every frame is read from the start of
+/// the captures, merged, and written to `writer`.
+pub fn merge_files<R: io::Read + io::Seek, W: io::Write>(
+    captures: &mut [FrameReader<R>],
+    writer: &mut FrameWriter<W>,
+) -> MergeResult<MergeReport> {
+    let Some(first) = captures.first() else {
+        whatever!("At least one capture is needed to merge files");
+    };
+    let frame_count = first.file_info().video_frame_count();
+    for (index, capture) in captures.iter().enumerate().skip(1) {
+        ensure_whatever!(
+            capture.file_info().video_frame_count() == frame_count,
+            "Capture {index} has {} frames, but capture 0 has {frame_count} frames",
+            capture.file_info().video_frame_count()
+        );
+    }
+    for (index, capture) in captures.iter_mut().enumerate() {
+        capture
+            .seek_frame(0)
+            .with_whatever_context(|_| format!("Could not seek to the start of capture {index}"))?;
+    }
+
+    let mut report = MergeReport::new();
+    for frame_index in 0..frame_count {
+        let mut frames = Vec::with_capacity(captures.len());
+        for (index, capture) in captures.iter_mut().enumerate() {
+            let frame = capture.read_frame().with_whatever_context(|_| {
+                format!("Could not read frame {frame_index} from capture {index}")
+            })?;
+            frames.push(
+                frame.whatever_context(format!(
+                    "Capture {index} ended before frame {frame_index}"
+                ))?,
+            );
+        }
+        let (merged, frame_report) = merge_frames(frame_index, &frames)?;
+        writer
+            .write_frame(&merged)
+            .with_whatever_context(|_| format!("Could not write merged frame {frame_index}"))?;
+        report.frames.push(frame_report);
+    }
+    Ok(report)
+}
@@ -0,0 +1,63 @@
+use std::io::Cursor;
+
+use googletest::prelude::*;
+use num::rational::Ratio;
+
+use super::*;
+use crate::{
+    file::{
+        testutil::{frame_reader, read_test_frames, SONY_GOOD_QUALITY},
+        Info, UnvalidatedInfo,
+    },
+    testutil::*,
+};
+
+#[googletest::test]
+fn test_merge_files() {
+    let original = std::fs::read(test_resource("dv_multiframe/sony_good_quality.dv")).unwrap();
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+
+    let mut first = frames.clone();
+    first[1].channels[0].dif_sequences[2].video[7].data.fill(0xFF);
+    first[3].channels[0].dif_sequences[0].video[0].data.fill(0xFF);
+    let mut second = frames.clone();
+    second[3].channels[0].dif_sequences[9].video[134].data.fill(0xFF);
+    second[4].channels[0].dif_sequences[0].video[0].data.fill(0xFF);
+
+    let mut captures =
+        [frame_reader(&first, &SONY_GOOD_QUALITY), frame_reader(&second, &SONY_GOOD_QUALITY)];
+    // The captures are read from the start, regardless of their current position.
+    captures[1].seek_frame(2).unwrap();
+    let mut writer = FrameWriter::new(Cursor::new(Vec::new()), *SONY_GOOD_QUALITY);
+    let report = merge_files(&mut captures, &mut writer).unwrap();
+
+    expect_that!(writer.into_inner().into_inner(), eq(&original));
+    expect_that!(report.frames.len(), eq(5));
+    expect_that!(report.replaced_block_count(), eq(2));
+    expect_that!(report.remaining_defects(), eq(0));
+    expect_that!(
+        report.to_string(),
+        eq("frame 1, 1499 blocks from capture 0, 1 blocks from capture 1, 0 defects remaining\n\
+            frame 3, 1499 blocks from capture 0, 1 blocks from capture 1, 0 defects remaining\n")
+    );
+}
+
+#[googletest::test]
+fn test_merge_files_different_lengths() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let shorter = UnvalidatedInfo::new(Info {
+        file_size: 480_000,
+        video_duration: Ratio::<u128>::new(1_001 * 4, 30_000),
+        ..**SONY_GOOD_QUALITY
+    })
+    .validate()
+    .unwrap();
+
+    let mut captures =
+        [frame_reader(&frames, &SONY_GOOD_QUALITY), frame_reader(&frames[..4], &shorter)];
+    let mut writer = FrameWriter::new(Cursor::new(Vec::new()), *SONY_GOOD_QUALITY);
+    expect_that!(
+        merge_files(&mut captures, &mut writer).unwrap_err().to_string(),
+        eq("Capture 1 has 4 frames, but capture 0 has 5 frames")
+    );
+}
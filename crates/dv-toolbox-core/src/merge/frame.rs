@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use snafu::prelude::*;
+
+use super::MergeResult;
+use crate::{
+    audio,
+    dif::{self, DIFBlockPosition, SectionType},
+    file::ValidInfoMethods,
+    pack,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Sections of a DIF sequence that hold DIF blocks, in the order that they are merged.
+const SECTIONS: [SectionType; 5] = [
+    SectionType::Header,
+    SectionType::Subcode,
+    SectionType::VAUX,
+    SectionType::Audio,
+    SectionType::Video,
+];
+
+/// Signs of damage found in each DIF block of a single frame.
+///
+/// Every DIF block starts out with zero defects, and gains defects as follows:
+///
+/// - Subcode, VAUX, and audio DIF blocks: one for each pack that is [`pack::Pack::Invalid`], or
+///   that is missing where the other DIF sequences have a pack.  See
+///   [`dif::FrameMetadata::dissenting_packs`].
+/// - Audio DIF blocks: one for each sample that holds the error code of the audio quantization
+///   of their audio block channel.  See [`audio_quantizations`].
+/// - Video DIF blocks: one for a [`dif::MacroblockDamage::Concealed`] macroblock, two for an
+///   [`dif::MacroblockDamage::Errored`] macroblock, and three for a
+///   [`dif::MacroblockDamage::Missing`] macroblock.
+///
+/// The number of defects is only meaningful when comparing copies of the same DIF block.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct FrameDefects {
+    /// Number of defects of the DIF blocks that have any.
+    blocks: HashMap<DIFBlockPosition, usize>,
+}
+
+impl FrameDefects {
+    /// Find the defects of every DIF block in the frame.
+    ///
+    /// `quantizations` holds the audio quantization of each audio block channel, which is used to
+    /// recognize the error code in audio samples.  It should be decided once for all captures of
+    /// the frame with [`audio_quantizations`], so that a capture that lost its AAUX source packs
+    /// is judged in the same way as the others.  Audio block channels that are not listed are
+    /// checked for 16-bit error codes.
+    pub fn from_frame(frame: &dif::Frame, quantizations: &[pack::AudioQuantization]) -> Self {
+        let mut defects = Self::default();
+        let metadata = dif::FrameMetadata::from_frame(frame);
+
+        for dissent in &metadata.dissenting_packs {
+            if dissent.reason != dif::DissentReason::Disagrees {
+                defects.add(pack_block_position(&dissent.position), 1);
+            }
+        }
+
+        for (channel, channel_data) in frame.channels.iter().enumerate() {
+            let sequences_per_half = channel_data.dif_sequences.len() / 2;
+            for (dif_sequence, sequence) in channel_data.dif_sequences.iter().enumerate() {
+                let audio_block_channel = channel * 2 + dif_sequence / sequences_per_half;
+                let quantization = quantizations
+                    .get(audio_block_channel)
+                    .copied()
+                    .unwrap_or(pack::AudioQuantization::Linear16Bit);
+                for (block, audio_block) in sequence.audio.iter().enumerate() {
+                    defects.add(
+                        DIFBlockPosition {
+                            channel,
+                            dif_sequence,
+                            section: SectionType::Audio,
+                            block,
+                        },
+                        audio::block_error_sample_count(audio_block, quantization),
+                    );
+                }
+            }
+        }
+
+        let damage = dif::DamageMap::from_frame(frame);
+        for (row, macroblocks) in damage.rows().enumerate() {
+            for (block, macroblock) in macroblocks.iter().enumerate() {
+                let count = match macroblock {
+                    dif::MacroblockDamage::Good => 0,
+                    dif::MacroblockDamage::Concealed => 1,
+                    dif::MacroblockDamage::Errored => 2,
+                    dif::MacroblockDamage::Missing => 3,
+                };
+                let position = DIFBlockPosition {
+                    channel: row / damage.dif_sequences_per_channel,
+                    dif_sequence: row % damage.dif_sequences_per_channel,
+                    section: SectionType::Video,
+                    block,
+                };
+                defects.add(position, count);
+            }
+        }
+        defects
+    }
+
+    fn add(&mut self, position: DIFBlockPosition, count: usize) {
+        if count > 0 {
+            *self.blocks.entry(position).or_default() += count;
+        }
+    }
+
+    /// Number of defects of the DIF block at the given position.
+    pub fn get(&self, position: &DIFBlockPosition) -> usize {
+        self.blocks.get(position).copied().unwrap_or_default()
+    }
+
+    /// Total number of defects across all DIF blocks of the frame.
+    pub fn total(&self) -> usize {
+        self.blocks.values().sum()
+    }
+}
+
+/// Decide the audio quantization of each audio block channel of a frame, from the consensus AAUX
+/// source packs of all captures of the frame.
+///
+/// The quantization found in the most captures is used, preferring the earliest capture when
+/// there is a tie.  Audio block channels where no capture has a valid AAUX source pack are
+/// assumed to be 16-bit.
+pub fn audio_quantizations(captures: &[dif::Frame]) -> Vec<pack::AudioQuantization> {
+    let metadata: Vec<_> = captures.iter().map(dif::FrameMetadata::from_frame).collect();
+    let channel_count = captures.first().map_or(0, |frame| frame.channels.len() * 2);
+    (0..channel_count)
+        .map(|audio_block_channel| {
+            let mut votes: Vec<(pack::AudioQuantization, usize)> = Vec::new();
+            for capture in &metadata {
+                if let Some(pack::Pack::AAUXSource(source)) =
+                    capture.aaux_pack(audio_block_channel, pack::Type::AAUXSource)
+                {
+                    match votes.iter_mut().find(|(q, _)| *q == source.quantization) {
+                        Some((_, count)) => *count += 1,
+                        None => votes.push((source.quantization, 1)),
+                    }
+                }
+            }
+            votes
+                .iter()
+                .fold(None, |best: Option<&(_, usize)>, vote| match best {
+                    Some(best) if best.1 >= vote.1 => Some(best),
+                    _ => Some(vote),
+                })
+                .map_or(pack::AudioQuantization::Linear16Bit, |(quantization, _)| *quantization)
+        })
+        .collect()
+}
+
+/// Returns the position of the DIF block that holds a copy of a pack.
+fn pack_block_position(position: &dif::PackPosition) -> DIFBlockPosition {
+    match *position {
+        dif::PackPosition::Subcode { channel, dif_sequence, sync_block } => DIFBlockPosition {
+            channel,
+            dif_sequence,
+            section: SectionType::Subcode,
+            block: sync_block / dif::SUBCODE_SYNC_BLOCKS_PER_BLOCK,
+        },
+        dif::PackPosition::VAUX(vaux) => DIFBlockPosition {
+            channel: vaux.channel,
+            dif_sequence: vaux.dif_sequence,
+            section: SectionType::VAUX,
+            block: vaux.block,
+        },
+        dif::PackPosition::AAUX(aaux) => DIFBlockPosition {
+            channel: aaux.channel,
+            dif_sequence: aaux.dif_sequence,
+            section: SectionType::Audio,
+            block: aaux.block,
+        },
+    }
+}
+
+/// A DIF block of a merged frame, along with the capture that it was taken from.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub struct BlockSource {
+    /// Location of the DIF block within the frame.
+    pub position: DIFBlockPosition,
+
+    /// Index of the capture that the DIF block was taken from.
+    pub capture: usize,
+}
+
+/// Record of how a single frame was merged from several captures.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct FrameMergeReport {
+    /// Zero-based index of the frame within the file.
+    pub frame_index: u64,
+
+    /// Number of DIF blocks taken from each capture, in the order that the captures were given.
+    pub block_counts: Vec<usize>,
+
+    /// Every DIF block that was taken from a capture other than the first one, ordered by
+    /// channel, DIF sequence, section, and block.  All other DIF blocks were taken from the first
+    /// capture.
+    pub replaced_blocks: Vec<BlockSource>,
+
+    /// Total number of defects in each capture; see [`FrameDefects`].
+    pub capture_defects: Vec<usize>,
+
+    /// Total number of defects that remain in the merged frame.
+    pub remaining_defects: usize,
+}
+
+/// Merge the same frame from several captures of a tape, by choosing the copy of each DIF block
+/// that has the fewest defects.
+///
+/// The captures must already be aligned, so that they all hold the same frame.  When several
+/// copies of a DIF block have equally few defects, the copy from the earliest capture is chosen.
+/// The first capture should therefore be the one that is trusted the most.
+///
+/// An error is returned if no captures are given, or if they do not all have the same format.
+pub fn merge_frames(
+    frame_index: u64,
+    captures: &[dif::Frame],
+) -> MergeResult<(dif::Frame, FrameMergeReport)> {
+    let Some(first) = captures.first() else {
+        whatever!("At least one capture is needed to merge frame {frame_index}");
+    };
+    let shape = |frame: &dif::Frame| {
+        frame.channels.iter().map(|c| c.dif_sequences.len()).collect::<Vec<_>>()
+    };
+    for (index, capture) in captures.iter().enumerate().skip(1) {
+        first.file_info.check_similar(&capture.file_info).with_whatever_context(|_| {
+            format!("Capture {index} does not have the same format as capture 0")
+        })?;
+        ensure_whatever!(
+            shape(capture) == shape(first),
+            "Capture {index} does not have the same channels and DIF sequences as capture 0"
+        );
+    }
+
+    let quantizations = audio_quantizations(captures);
+    let defects: Vec<_> =
+        captures.iter().map(|capture| FrameDefects::from_frame(capture, &quantizations)).collect();
+    let mut merged = first.clone();
+    let mut report = FrameMergeReport {
+        frame_index,
+        block_counts: vec![0; captures.len()],
+        replaced_blocks: Vec::new(),
+        capture_defects: defects.iter().map(FrameDefects::total).collect(),
+        remaining_defects: 0,
+    };
+    for (channel, channel_data) in first.channels.iter().enumerate() {
+        for (dif_sequence, sequence) in channel_data.dif_sequences.iter().enumerate() {
+            for section in SECTIONS {
+                for block in 0..sequence.section(section).len() {
+                    let position = DIFBlockPosition { channel, dif_sequence, section, block };
+                    let (capture, count) = defects
+                        .iter()
+                        .map(|d| d.get(&position))
+                        .enumerate()
+                        .min_by_key(|(_, count)| *count)
+                        .unwrap();
+                    report.block_counts[capture] += 1;
+                    report.remaining_defects += count;
+                    if capture != 0 {
+                        *merged.block_mut(&position) = *captures[capture].block(&position);
+                        report.replaced_blocks.push(BlockSource { position, capture });
+                    }
+                }
+            }
+        }
+    }
+    Ok((merged, report))
+}
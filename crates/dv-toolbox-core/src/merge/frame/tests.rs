@@ -0,0 +1,185 @@
+use display_error_chain::ErrorChainExt;
+use googletest::prelude::*;
+use num::rational::Ratio;
+
+use super::*;
+use crate::{
+    dif::{AAUXPackPosition, MacroblockHeader, MacroblockStatus, VAUXPackPosition},
+    file::{
+        testutil::{read_test_frames, SONY_GOOD_QUALITY},
+        Info, UnvalidatedInfo,
+    },
+    testutil::from_hex,
+};
+
+fn position(dif_sequence: usize, section: SectionType, block: usize) -> DIFBlockPosition {
+    DIFBlockPosition { channel: 0, dif_sequence, section, block }
+}
+
+/// Mark the macroblock of a video DIF block with the given status.
+fn set_status(frame: &mut dif::Frame, dif_sequence: usize, block: usize, status: MacroblockStatus) {
+    let data = &mut frame.channels[0].dif_sequences[dif_sequence].video[block].data;
+    let header = MacroblockHeader { status, ..MacroblockHeader::from_raw(data) };
+    header.write_raw(data);
+}
+
+#[googletest::test]
+fn test_frame_defects() {
+    let mut frame =
+        read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY).remove(0);
+    let quantizations = audio_quantizations(&[frame.clone()]);
+    expect_that!(quantizations, each(eq(&pack::AudioQuantization::NonLinear12Bit)));
+    expect_that!(FrameDefects::from_frame(&frame, &quantizations).total(), eq(0));
+
+    let ctx = frame.pack_context();
+    let invalid = VAUXPackPosition { channel: 0, dif_sequence: 1, block: 0, slot: 2 };
+    frame.set_vaux_pack(&invalid, &pack::Pack::from_raw(&from_hex("62 23 31 53 74"), &ctx).0);
+    let dropout = AAUXPackPosition { channel: 0, dif_sequence: 3, block: 0 };
+    frame.set_aaux_pack(&dropout, &pack::Pack::from_raw(&[0xFF; 5], &ctx).0);
+    frame.channels[0].dif_sequences[4].subcode[1].data[3..8].fill(0xFF);
+    frame.channels[0].dif_sequences[3].audio[0].data[5..8].copy_from_slice(&[0x80, 0x80, 0x00]);
+    set_status(&mut frame, 2, 40, MacroblockStatus::ConcealedWithPreviousFrame);
+    set_status(&mut frame, 2, 41, MacroblockStatus::ErrorUnknownConcealment);
+    frame.channels[0].dif_sequences[2].video[42].data.fill(0xFF);
+
+    let defects = FrameDefects::from_frame(&frame, &quantizations);
+    expect_that!(defects.get(&position(1, SectionType::VAUX, 0)), eq(1));
+    expect_that!(defects.get(&position(3, SectionType::Audio, 0)), eq(3));
+    expect_that!(defects.get(&position(4, SectionType::Subcode, 1)), eq(1));
+    expect_that!(defects.get(&position(2, SectionType::Video, 40)), eq(1));
+    expect_that!(defects.get(&position(2, SectionType::Video, 41)), eq(2));
+    expect_that!(defects.get(&position(2, SectionType::Video, 42)), eq(3));
+    expect_that!(defects.get(&position(2, SectionType::Video, 43)), eq(0));
+    expect_that!(defects.total(), eq(11));
+}
+
+#[googletest::test]
+fn test_merge_frames_undamaged() {
+    let frame =
+        read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY).remove(0);
+    let (merged, report) = merge_frames(7, &[frame.clone(), frame.clone()]).unwrap();
+
+    expect_that!(merged, eq(&frame));
+    expect_that!(
+        report,
+        eq(&FrameMergeReport {
+            frame_index: 7,
+            block_counts: vec![1_500, 0],
+            replaced_blocks: vec![],
+            capture_defects: vec![0, 0],
+            remaining_defects: 0,
+        })
+    );
+}
+
+#[googletest::test]
+fn test_merge_frames_damaged() {
+    let original =
+        read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY).remove(0);
+
+    // Each capture is damaged in different places.
+    let mut first = original.clone();
+    set_status(&mut first, 2, 40, MacroblockStatus::ErrorUnknownConcealment);
+    first.channels[0].dif_sequences[3].audio[0].data[5..8].copy_from_slice(&[0x80, 0x80, 0x00]);
+    first.channels[0].dif_sequences[6].video[100].data.fill(0xFF);
+
+    let mut second = original.clone();
+    second.channels[0].dif_sequences[4].subcode[1].data[3..8].fill(0xFF);
+    second.channels[0].dif_sequences[6].video[100].data.fill(0xFF);
+
+    let mut third = original.clone();
+    set_status(&mut third, 6, 100, MacroblockStatus::ConcealedWithPreviousFrame);
+    third.channels[0].dif_sequences[3].audio[0].data[5..8].copy_from_slice(&[0x80, 0x12, 0x00]);
+
+    let (merged, report) = merge_frames(0, &[first, second, third.clone()]).unwrap();
+
+    // Block 100 is damaged in every capture, so the least damaged copy is used.  The damaged
+    // audio block of the first capture is replaced by the one from the second capture, since
+    // the earlier capture wins when they have the same number of defects.
+    let mut expected = original.clone();
+    expected.channels[0].dif_sequences[6].video[100] =
+        third.channels[0].dif_sequences[6].video[100];
+    expect_that!(merged, eq(&expected));
+    expect_that!(
+        report,
+        eq(&FrameMergeReport {
+            frame_index: 0,
+            block_counts: vec![1_497, 2, 1],
+            replaced_blocks: vec![
+                BlockSource { position: position(2, SectionType::Video, 40), capture: 1 },
+                BlockSource { position: position(3, SectionType::Audio, 0), capture: 1 },
+                BlockSource { position: position(6, SectionType::Video, 100), capture: 2 },
+            ],
+            capture_defects: vec![7, 4, 2],
+            remaining_defects: 1,
+        })
+    );
+}
+
+#[googletest::test]
+fn test_merge_frames_lost_audio_source() {
+    let original =
+        read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY).remove(0);
+
+    // The first capture lost every AAUX source pack of CH1, and has a 12-bit error sample.
+    let mut first = original.clone();
+    let no_info = pack::Pack::from_raw(&[0xFF; 5], &first.pack_context()).0;
+    for (aaux_pack, _) in original.aaux_packs() {
+        if aaux_pack.audio_block_channel == 0
+            && aaux_pack.pack.pack_type() == pack::Type::AAUXSource
+        {
+            first.set_aaux_pack(&aaux_pack.position, &no_info);
+        }
+    }
+    first.channels[0].dif_sequences[3].audio[0].data[5..8].copy_from_slice(&[0x80, 0x80, 0x00]);
+    expect_that!(
+        audio_quantizations(&[first.clone(), original.clone()]),
+        each(eq(&pack::AudioQuantization::NonLinear12Bit))
+    );
+
+    // The quantization of the second capture is used to find the error sample in the first
+    // capture, so the clean audio block of the second capture is chosen.
+    let (merged, report) = merge_frames(0, &[first, original.clone()]).unwrap();
+    expect_that!(
+        merged.channels[0].dif_sequences[3].audio[0],
+        eq(original.channels[0].dif_sequences[3].audio[0])
+    );
+    expect_that!(
+        report.replaced_blocks,
+        contains(eq(&BlockSource { position: position(3, SectionType::Audio, 0), capture: 1 }))
+    );
+}
+
+#[googletest::test]
+fn test_merge_frames_errors() {
+    let frame =
+        read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY).remove(0);
+    expect_that!(
+        merge_frames(3, &[]).unwrap_err().to_string(),
+        eq("At least one capture is needed to merge frame 3")
+    );
+
+    let mut dissimilar = frame.clone();
+    dissimilar.file_info = UnvalidatedInfo::new(Info {
+        file_size: 144_000,
+        video_frame_rate: Ratio::<u32>::from(25),
+        video_duration: Ratio::<u128>::new(1, 25),
+        audio_stereo_stream_count: 2,
+        audio_sample_rate: Some(32_000),
+    })
+    .validate()
+    .unwrap();
+    expect_that!(
+        merge_frames(0, &[frame.clone(), dissimilar]).map_err(|e| e.chain().to_string()),
+        err(eq("Capture 1 does not have the same format as capture 0\n\
+            Caused by:\n  \
+            -> Video frame rate 25 does not match 30000/1001"))
+    );
+
+    let mut truncated = frame.clone();
+    truncated.channels[0].dif_sequences.pop();
+    expect_that!(
+        merge_frames(0, &[frame, truncated]).unwrap_err().to_string(),
+        eq("Capture 1 does not have the same channels and DIF sequences as capture 0")
+    );
+}
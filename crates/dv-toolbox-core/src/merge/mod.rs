@@ -0,0 +1,46 @@
+//! Merging of several captures of the same tape into a single DV file.
+//!
+//! Archivists often capture the same tape several times, and each playback has dropouts in
+//! different places.  Since the captures hold the same DIF blocks apart from the damage, a better
+//! copy of the tape can be assembled by choosing the least damaged copy of each DIF block.
+//!
+//! The [`merge_frames`] function does this for a single frame, given the same frame from every
+//! capture.  The damage of each copy of a DIF block is judged by the signs of damage that are
+//! specific to its section: the macroblock status of video DIF blocks (see [`dif::DamageMap`]),
+//! error samples in audio DIF blocks (see [`crate::audio::AudioErrorReport`]), and invalid or
+//! missing packs (see [`dif::FrameMetadata::dissenting_packs`]).  The [`merge_files`] function
+//! merges entire files whose frames are already aligned with each other, and produces a
+//! [`MergeReport`] recording which capture each DIF block came from.
+//!
+//! Captures rarely line up by themselves, since a capture can skip or duplicate frames.  The
+//! [`align_captures`] and [`align_files`] functions match up the frames of several captures using
+//! the [`FrameIdentity`] of each frame: its title timecode, recording date and time, and absolute
+//! track number.
+//!
+//! [`dif::DamageMap`]: crate::dif::DamageMap
+//! [`dif::FrameMetadata::dissenting_packs`]: crate::dif::FrameMetadata::dissenting_packs
+
+pub use align::*;
+pub use file::*;
+pub use frame::*;
+use snafu::prelude::*;
+
+mod align;
+mod file;
+mod frame;
+
+/// Result type for calls related to merging captures.
+pub type MergeResult<T, E = MergeError> = std::result::Result<T, E>;
+
+/// Error type for when captures could not be merged.
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum MergeError {
+    #[snafu(whatever, display("{message}"))]
+    Whatever {
+        message: String,
+        #[snafu(source(from(Box<dyn std::error::Error>, Some)))]
+        source: Option<Box<dyn std::error::Error>>,
+        backtrace: snafu::Backtrace,
+    },
+}
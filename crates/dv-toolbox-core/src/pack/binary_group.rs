@@ -1,8 +1,11 @@
-use arbitrary_int::u4;
+use arbitrary_int::{u1, u4, u6};
 use bitbybit::bitfield;
+use chrono::{Days, NaiveDate};
 use garde::Validate;
 use serde::{Deserialize, Serialize};
 
+use super::BinaryGroupFlag;
+
 #[cfg(test)]
 mod tests;
 
@@ -15,8 +18,8 @@
 /// It's probably not safe to assume that these binary group values remain the same
 /// throughout a single frame.
 ///
-/// At this time, given the wide variety of possible values, this package makes no attempt to
-/// further parse the binary groups.
+/// The raw groups are kept as they were read.  Use [`BinaryGroup::contents`] along with the
+/// [`BinaryGroupFlag`] of the timecode pack to decode them into [`BinaryGroupContents`].
 ///
 /// In the context of DV, this data is defined at:
 ///
@@ -69,3 +72,205 @@ fn to_raw(&self, _ctx: &super::PackContext) -> super::RawPackData {
         RawBinaryGroup::builder().with_group_data(self.group_data).build().raw_value().to_le_bytes()
     }
 }
+
+/// Date stored in the binary groups, in one of the two forms of SMPTE 309M.
+///
+/// Either way, the date is stored as six binary-coded decimal digits in BG1 through BG6, with the
+/// least significant digit in BG1.
+///
+/// - SMPTE 309M - Transmission of Date and Time Zone Information in Binary Groups of Time and
+///   Control Code
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Validate, Serialize, Deserialize)]
+pub enum BinaryGroupDate {
+    /// The day in BG1 and BG2, the month in BG3 and BG4, and the two-digit year in BG5 and BG6.
+    YearMonthDay {
+        /// The last two digits of the year, in range `[0, 99]`.
+        #[garde(range(max = 99))]
+        year: u8,
+
+        /// The month, normally in range `[1, 12]`, and at most 99.
+        #[garde(range(max = 99))]
+        month: u8,
+
+        /// The day of the month, normally in range `[1, 31]`, and at most 99.
+        #[garde(range(max = 99))]
+        day: u8,
+    },
+
+    /// The Modified Julian Date: the number of days since 1858-11-17, in range `[0, 999999]`.
+    ModifiedJulianDate(#[garde(range(max = 999_999))] u32),
+}
+
+/// Date and time zone stored in the binary groups.
+///
+/// The date is stored in BG1 through BG6; see [`BinaryGroupDate`].  The time zone code takes up
+/// BG7 and the low two bits of BG8.  The next bit of BG8 is the date format flag, which tells the
+/// two forms of the date apart.  The last bit of BG8 is reserved.
+///
+/// - SMPTE 309M - Transmission of Date and Time Zone Information in Binary Groups of Time and
+///   Control Code
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Validate, Serialize, Deserialize)]
+pub struct BinaryGroupDateTimeZone {
+    /// The date, in the form given by the date format flag.
+    #[garde(dive)]
+    pub date: BinaryGroupDate,
+
+    /// The time zone code, as recorded.  Its meaning is given by the table of time zone codes in
+    /// SMPTE 309M.
+    #[garde(skip)]
+    pub time_zone_code: u6,
+
+    /// Reserved bit at the top of BG8; should normally be set to 0.
+    #[garde(skip)]
+    pub reserved: u1,
+}
+
+impl BinaryGroupDateTimeZone {
+    /// Returns the date, if it is a valid date.
+    ///
+    /// Like [`super::RecordingDate::date`], 75 is used as the Y2K rollover threshold of a
+    /// two-digit year.
+    pub fn date(&self) -> Option<NaiveDate> {
+        match self.date {
+            BinaryGroupDate::YearMonthDay { year, month, day } => {
+                let year = i32::from(year) + if year < 75 { 2000 } else { 1900 };
+                NaiveDate::from_ymd_opt(year, month.into(), day.into())
+            }
+            BinaryGroupDate::ModifiedJulianDate(days) => NaiveDate::from_ymd_opt(1858, 11, 17)
+                .unwrap()
+                .checked_add_days(Days::new(days.into())),
+        }
+    }
+}
+
+/// Bits of the time zone code in the byte made up of BG7 and BG8.
+const TIME_ZONE_CODE_MASK: u8 = 0x3F;
+
+/// Date format flag in the byte made up of BG7 and BG8, which is set when the date is a Modified
+/// Julian Date.
+const DATE_FORMAT_MJD: u8 = 0x40;
+
+/// Position of the reserved bit in the byte made up of BG7 and BG8.
+const RESERVED_SHIFT: u8 = 7;
+
+/// Contents of a [`BinaryGroup`], as determined by the [`BinaryGroupFlag`] of the timecode pack
+/// that goes with it.
+///
+/// - IEC 60461:2010 Section 7.4 - Use of the binary groups
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Validate, Serialize, Deserialize)]
+pub enum BinaryGroupContents {
+    /// The contents are unspecified or reserved, so the raw groups are kept as they are.
+    Unspecified(#[garde(skip)] [u4; 8]),
+
+    /// Four 8-bit characters of an ISO/IEC 646 or ISO/IEC 2022 character set.  Each character is
+    /// stored in two groups, with the low nibble first: BG1 and BG2 hold the first character.
+    ///
+    /// - IEC 60461:2010 Section 7.4.3 - Eight-bit character set and unspecified clock time
+    Characters(#[garde(skip)] [u8; 4]),
+
+    /// Date and time zone according to SMPTE 309M.
+    ///
+    /// - IEC 60461:2010 Section 7.4.4 - Date/time zone and unspecified clock time
+    DateTimeZone(#[garde(dive)] BinaryGroupDateTimeZone),
+
+    /// Four 8-bit words of a page/line multiplex system according to SMPTE 262M, stored in the
+    /// same way as [`BinaryGroupContents::Characters`].  The words are not decoded further, since
+    /// their meaning depends on the page that they belong to.
+    ///
+    /// - IEC 60461:2010 Section 7.4.5 - Page/line multiplex system and unspecified clock time
+    PageLine(#[garde(skip)] [u8; 4]),
+}
+
+impl BinaryGroup {
+    /// Decode the groups according to the binary group flag of the timecode pack that goes with
+    /// this pack.
+    ///
+    /// Returns [`None`] if the groups don't hold a valid value for the flag, such as a date with
+    /// a digit greater than 9.
+    ///
+    /// The flag is always taken at face value.  In particular,
+    /// [`BinaryGroupFlag::TimeClockGroupPageLine`] is also the value that consumer equipment
+    /// records in the simplified timecode format, where every unused bit is set.  Such equipment
+    /// records no binary group pack, so there is nothing to decode; a binary group pack that is
+    /// present alongside that flag is decoded as [`BinaryGroupContents::PageLine`].
+    pub fn contents(&self, flag: BinaryGroupFlag) -> Option<BinaryGroupContents> {
+        let bytes = self.bytes();
+        Some(match flag {
+            BinaryGroupFlag::TimeUnspecifiedGroupUnspecified
+            | BinaryGroupFlag::TimeClockGroupUnspecified
+            | BinaryGroupFlag::TimeUnassignedGroupReserved => {
+                BinaryGroupContents::Unspecified(self.group_data)
+            }
+            BinaryGroupFlag::TimeUnspecifiedGroup8BitCodes => {
+                BinaryGroupContents::Characters(bytes)
+            }
+            BinaryGroupFlag::TimeUnspecifiedGroupDateTimeZone
+            | BinaryGroupFlag::TimeClockGroupDateTimeZone => {
+                let bcd = |byte: u8| {
+                    let (tens, units) = (byte >> 4, byte & 0xF);
+                    (tens <= 9 && units <= 9).then_some(tens * 10 + units)
+                };
+                let (low, middle, high) = (bcd(bytes[0])?, bcd(bytes[1])?, bcd(bytes[2])?);
+                let date = if bytes[3] & DATE_FORMAT_MJD != 0 {
+                    BinaryGroupDate::ModifiedJulianDate(
+                        u32::from(high) * 10000 + u32::from(middle) * 100 + u32::from(low),
+                    )
+                } else {
+                    BinaryGroupDate::YearMonthDay { year: high, month: middle, day: low }
+                };
+                BinaryGroupContents::DateTimeZone(BinaryGroupDateTimeZone {
+                    date,
+                    time_zone_code: u6::new(bytes[3] & TIME_ZONE_CODE_MASK),
+                    reserved: u1::new(bytes[3] >> RESERVED_SHIFT),
+                })
+            }
+            BinaryGroupFlag::TimeUnspecifiedGroupPageLine
+            | BinaryGroupFlag::TimeClockGroupPageLine => BinaryGroupContents::PageLine(bytes),
+        })
+    }
+
+    /// The groups as four bytes, with the odd-numbered group in the low nibble of each byte.
+    fn bytes(&self) -> [u8; 4] {
+        std::array::from_fn(|i| {
+            self.group_data[i * 2].value() | self.group_data[i * 2 + 1].value() << 4
+        })
+    }
+
+    /// Creates the groups from four bytes; the opposite of [`BinaryGroup::bytes`].
+    fn from_bytes(bytes: [u8; 4]) -> Self {
+        Self { group_data: std::array::from_fn(|i| u4::new(bytes[i / 2] >> (i % 2 * 4) & 0xF)) }
+    }
+}
+
+impl TryFrom<BinaryGroupContents> for BinaryGroup {
+    type Error = garde::Report;
+
+    /// Encode the contents back into groups; the opposite of [`BinaryGroup::contents`].
+    ///
+    /// An error is returned if a field of [`BinaryGroupContents::DateTimeZone`] does not fit in
+    /// its groups: a year, month, or day greater than 99, or a Modified Julian Date greater than
+    /// 999999.
+    fn try_from(contents: BinaryGroupContents) -> Result<Self, Self::Error> {
+        contents.validate()?;
+        Ok(match contents {
+            BinaryGroupContents::Unspecified(group_data) => Self { group_data },
+            BinaryGroupContents::Characters(bytes) | BinaryGroupContents::PageLine(bytes) => {
+                Self::from_bytes(bytes)
+            }
+            BinaryGroupContents::DateTimeZone(date_time_zone) => {
+                let bcd = |value: u8| (value / 10) << 4 | (value % 10);
+                let (low, middle, high, format) = match date_time_zone.date {
+                    BinaryGroupDate::YearMonthDay { year, month, day } => (day, month, year, 0),
+                    BinaryGroupDate::ModifiedJulianDate(days) => {
+                        let digits = |divisor: u32| u8::try_from(days / divisor % 100).unwrap();
+                        (digits(1), digits(100), digits(10000), DATE_FORMAT_MJD)
+                    }
+                };
+                let last = date_time_zone.time_zone_code.value()
+                    | format
+                    | date_time_zone.reserved.value() << RESERVED_SHIFT;
+                Self::from_bytes([bcd(low), bcd(middle), bcd(high), last])
+            }
+        })
+    }
+}
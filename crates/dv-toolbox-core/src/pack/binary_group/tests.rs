@@ -1,4 +1,6 @@
-use arbitrary_int::u4;
+use arbitrary_int::{u1, u4, u6, Number};
+use chrono::NaiveDate;
+use googletest::prelude::*;
 use rstest::rstest;
 use stdext::function_name;
 use testutil::*;
@@ -104,3 +106,118 @@ fn test_vaux_binary_group_binary(#[case] test_function_name: &str) {
     let tc = VAUX_BINARY_GROUP_BINARY_TEST_CASES.get_test_case(test_function_name);
     run_pack_binary_test_case(tc);
 }
+
+#[googletest::test]
+#[rstest]
+#[case::unspecified(
+    "64 12 34 56 78",
+    BinaryGroupFlag::TimeClockGroupUnspecified,
+    Some(BinaryGroupContents::Unspecified([2, 1, 4, 3, 6, 5, 8, 7].map(u4::new)))
+)]
+#[case::reserved(
+    "64 FF FF FF FF",
+    BinaryGroupFlag::TimeUnassignedGroupReserved,
+    Some(BinaryGroupContents::Unspecified([u4::MAX; 8]))
+)]
+#[case::characters(
+    "64 44 56 20 21",
+    BinaryGroupFlag::TimeUnspecifiedGroup8BitCodes,
+    Some(BinaryGroupContents::Characters(*b"DV !"))
+)]
+#[case::date_time_zone(
+    "64 27 08 97 11",
+    BinaryGroupFlag::TimeClockGroupDateTimeZone,
+    Some(BinaryGroupContents::DateTimeZone(BinaryGroupDateTimeZone {
+        date: BinaryGroupDate::YearMonthDay { year: 97, month: 8, day: 27 },
+        time_zone_code: u6::new(0x11),
+        reserved: u1::new(0)
+    }))
+)]
+#[case::date_time_zone_mjd(
+    "64 73 05 05 72",
+    BinaryGroupFlag::TimeUnspecifiedGroupDateTimeZone,
+    Some(BinaryGroupContents::DateTimeZone(BinaryGroupDateTimeZone {
+        date: BinaryGroupDate::ModifiedJulianDate(50573),
+        time_zone_code: u6::new(0x32),
+        reserved: u1::new(0)
+    }))
+)]
+#[case::date_time_zone_reserved_bit(
+    "64 27 08 97 91",
+    BinaryGroupFlag::TimeClockGroupDateTimeZone,
+    Some(BinaryGroupContents::DateTimeZone(BinaryGroupDateTimeZone {
+        date: BinaryGroupDate::YearMonthDay { year: 97, month: 8, day: 27 },
+        time_zone_code: u6::new(0x11),
+        reserved: u1::new(1)
+    }))
+)]
+#[case::date_time_zone_bad_digit(
+    "64 27 0A 97 11",
+    BinaryGroupFlag::TimeUnspecifiedGroupDateTimeZone,
+    None
+)]
+#[case::page_line(
+    "64 01 23 45 67",
+    BinaryGroupFlag::TimeClockGroupPageLine,
+    Some(BinaryGroupContents::PageLine([0x01, 0x23, 0x45, 0x67]))
+)]
+fn test_binary_group_contents(
+    #[case] input: &str,
+    #[case] flag: BinaryGroupFlag,
+    #[case] expected: Option<BinaryGroupContents>,
+) {
+    let input = from_hex(input);
+    let (pack, _) = Pack::from_raw(&input, &NTSC);
+    let Pack::VAUXBinaryGroup(binary_group) = pack else {
+        panic!("not a binary group pack: {pack:?}");
+    };
+    let contents = binary_group.contents(flag);
+    expect_that!(contents, eq(expected));
+
+    // Encoding the contents gives back the same pack.
+    if let Some(contents) = contents {
+        let encoded =
+            Pack::VAUXBinaryGroup(validated(BinaryGroup::try_from(contents).unwrap(), *NTSC));
+        expect_that!(encoded.to_raw(&NTSC).to_vec(), eq(&input));
+    }
+}
+
+#[googletest::test]
+#[rstest]
+#[case::year(BinaryGroupDate::YearMonthDay { year: 100, month: 8, day: 27 })]
+#[case::month(BinaryGroupDate::YearMonthDay { year: 97, month: 100, day: 27 })]
+#[case::day(BinaryGroupDate::YearMonthDay { year: 97, month: 8, day: 100 })]
+#[case::mjd(BinaryGroupDate::ModifiedJulianDate(1_000_000))]
+fn test_binary_group_from_contents_out_of_range(#[case] date: BinaryGroupDate) {
+    let contents = BinaryGroupContents::DateTimeZone(BinaryGroupDateTimeZone {
+        date,
+        time_zone_code: u6::new(0),
+        reserved: u1::new(0),
+    });
+    expect_that!(BinaryGroup::try_from(contents), err(anything()));
+}
+
+#[googletest::test]
+#[rstest]
+#[case::before_2000(
+    BinaryGroupDate::YearMonthDay { year: 97, month: 8, day: 27 },
+    Some(NaiveDate::from_ymd_opt(1997, 8, 27).unwrap())
+)]
+#[case::after_2000(
+    BinaryGroupDate::YearMonthDay { year: 24, month: 8, day: 27 },
+    Some(NaiveDate::from_ymd_opt(2024, 8, 27).unwrap())
+)]
+#[case::bad_month(BinaryGroupDate::YearMonthDay { year: 24, month: 0, day: 27 }, None)]
+#[case::mjd_epoch(
+    BinaryGroupDate::ModifiedJulianDate(0),
+    Some(NaiveDate::from_ymd_opt(1858, 11, 17).unwrap())
+)]
+#[case::mjd(
+    BinaryGroupDate::ModifiedJulianDate(50573),
+    Some(NaiveDate::from_ymd_opt(1997, 5, 5).unwrap())
+)]
+fn test_binary_group_date(#[case] date: BinaryGroupDate, #[case] expected: Option<NaiveDate>) {
+    let date_time_zone =
+        BinaryGroupDateTimeZone { date, time_zone_code: u6::new(0), reserved: u1::new(0) };
+    expect_that!(date_time_zone.date(), eq(expected));
+}
@@ -38,12 +38,12 @@
 mod date;
 mod time;
 mod types;
-mod util;
+pub(crate) mod util;
 mod vaux_source;
 mod vaux_source_control;
 
 #[cfg(test)]
-mod testutil;
+pub(crate) mod testutil;
 
 /// Unvalidated contents of a DV data pack.
 ///
@@ -1,4 +1,9 @@
-use std::sync::LazyLock;
+use std::{
+    cmp::Ordering,
+    fmt,
+    ops::{Add, AddAssign, Sub, SubAssign},
+    sync::LazyLock,
+};
 
 use arbitrary_int::{u2, u3, u4, Number};
 use bitbybit::{bitenum, bitfield};
@@ -169,6 +174,37 @@ fn check_frame_number(
     }
 }
 
+impl fmt::Display for TimeValueWithOptionalFrame {
+    /// Format the time value as a string.  The string format is defined in the documentation for
+    /// [`TimeValue`].
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}:{:02}", self.hour, self.minute, self.second)?;
+        match self.frame {
+            None => Ok(()),
+            Some(frame) => match self.drop_frame {
+                true => write!(f, ";{frame:02}"),
+                false => write!(f, ":{frame:02}"),
+            },
+        }
+    }
+}
+
+impl fmt::Display for TimeValueWithRequiredFrame {
+    /// Format the time value as a string.  The string format is defined in the documentation for
+    /// [`TimeValue`].
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Reuse the implementation from the version with optional frame numbers.
+        TimeValueWithOptionalFrame {
+            hour: self.hour,
+            minute: self.minute,
+            second: self.second,
+            drop_frame: self.drop_frame,
+            frame: Some(self.frame),
+        }
+        .fmt(f)
+    }
+}
+
 impl Serialize for TimeValueWithOptionalFrame {
     /// Serialize the time value to a string.  The string format is defined in the documentation
     /// for [`TimeValue`].
@@ -176,18 +212,7 @@ fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        let time_str = match self.frame {
-            None => format!("{:02}:{:02}:{:02}", self.hour, self.minute, self.second),
-            Some(frame) => match self.drop_frame {
-                true => {
-                    format!("{:02}:{:02}:{:02};{:02}", self.hour, self.minute, self.second, frame)
-                }
-                false => {
-                    format!("{:02}:{:02}:{:02}:{:02}", self.hour, self.minute, self.second, frame)
-                }
-            },
-        };
-        serializer.serialize_str(time_str.as_str())
+        serializer.serialize_str(self.to_string().as_str())
     }
 }
 
@@ -198,15 +223,7 @@ fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        // Reuse the implementation from the version with optional frame numbers.
-        TimeValueWithOptionalFrame {
-            hour: self.hour,
-            minute: self.minute,
-            second: self.second,
-            drop_frame: self.drop_frame,
-            frame: Some(self.frame),
-        }
-        .serialize(serializer)
+        serializer.serialize_str(self.to_string().as_str())
     }
 }
 
@@ -283,6 +300,187 @@ fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     }
 }
 
+/// Number of frames counted by timecodes in each second of the system.  For NTSC systems, this is
+/// the nominal 30 frames per second rather than the true frame rate.
+pub(crate) fn timecode_frame_rate(system: System) -> u32 {
+    match system {
+        System::Sys525_60 => 30,
+        System::Sys625_50 => 25,
+    }
+}
+
+/// Number of frame numbers skipped at the start of each minute when drop frame is in use.
+const DROPPED_FRAMES_PER_MINUTE: u32 = 2;
+
+/// Whether time values of the system skip frame numbers.  Drop frame only applies to NTSC
+/// systems, and is ignored for other systems.
+fn uses_drop_frame(system: System, drop_frame: bool) -> bool {
+    drop_frame && system == System::Sys525_60
+}
+
+impl TimeValueWithRequiredFrame {
+    /// Returns the number of distinct time values in a 24 hour period, after which the time value
+    /// wraps back to `00:00:00:00`.
+    pub fn frames_per_day(system: System, drop_frame: bool) -> u32 {
+        let frames = timecode_frame_rate(system) * 60 * 60 * 24;
+        if uses_drop_frame(system, drop_frame) {
+            // Frames are dropped in 9 out of every 10 minutes.
+            frames - DROPPED_FRAMES_PER_MINUTE * 24 * 54
+        } else {
+            frames
+        }
+    }
+
+    /// Returns the number of frames from `00:00:00:00` up to this time value.
+    ///
+    /// Frame numbers skipped by drop frame are not counted, so consecutive frames of a video
+    /// always have consecutive frame indices.  The time value is assumed to be valid for the
+    /// system.
+    pub fn to_frame_index(&self, system: System) -> u32 {
+        let minutes = u32::from(self.hour) * 60 + u32::from(self.minute);
+        let index = (minutes * 60 + u32::from(self.second)) * timecode_frame_rate(system)
+            + u32::from(self.frame);
+        if uses_drop_frame(system, self.drop_frame) {
+            index - DROPPED_FRAMES_PER_MINUTE * (minutes - minutes / 10)
+        } else {
+            index
+        }
+    }
+
+    /// Returns the time value that is the given number of frames after `00:00:00:00`.  This is
+    /// the reverse of [`TimeValue::to_frame_index`].
+    ///
+    /// Frame indices past the end of a day wrap back around to `00:00:00:00`.
+    pub fn from_frame_index(frame_index: u32, system: System, drop_frame: bool) -> Self {
+        let rate = timecode_frame_rate(system);
+        let mut index = frame_index % Self::frames_per_day(system, drop_frame);
+        if uses_drop_frame(system, drop_frame) {
+            // Add back the frame numbers that were skipped before this frame.  The first minute
+            // of every 10 minutes doesn't skip any.
+            let minute_frames = rate * 60 - DROPPED_FRAMES_PER_MINUTE;
+            let ten_minute_frames = rate * 60 + minute_frames * 9;
+            let remainder = index % ten_minute_frames;
+            let minutes = if remainder < rate * 60 {
+                0
+            } else {
+                (remainder - DROPPED_FRAMES_PER_MINUTE) / minute_frames
+            };
+            index += DROPPED_FRAMES_PER_MINUTE * (index / ten_minute_frames * 9 + minutes);
+        }
+        let seconds = index / rate;
+        Self {
+            hour: u8::try_from(seconds / 3600).unwrap(),
+            minute: u8::try_from(seconds / 60 % 60).unwrap(),
+            second: u8::try_from(seconds % 60).unwrap(),
+            drop_frame,
+            frame: u8::try_from(index % rate).unwrap(),
+        }
+    }
+
+    /// Returns the time value that is the given number of frames later, or earlier if the
+    /// number is negative.  The result wraps around at 24 hours in either direction.
+    pub fn add_frames(&self, frames: i64, system: System) -> Self {
+        let frames_per_day = i64::from(Self::frames_per_day(system, self.drop_frame));
+        let index = (i64::from(self.to_frame_index(system)) + frames).rem_euclid(frames_per_day);
+        Self::from_frame_index(u32::try_from(index).unwrap(), system, self.drop_frame)
+    }
+
+    /// Returns the time value of the frame that follows this one.
+    pub fn next_frame(&self, system: System) -> Self {
+        self.add_frames(1, system)
+    }
+}
+
+impl PartialOrd for TimeValueWithRequiredFrame {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TimeValueWithRequiredFrame {
+    /// Time values are ordered chronologically within a single day.  Time values that only differ
+    /// in their drop frame flag are ordered arbitrarily, but consistently.
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.hour, self.minute, self.second, self.frame, self.drop_frame).cmp(&(
+            other.hour,
+            other.minute,
+            other.second,
+            other.frame,
+            other.drop_frame,
+        ))
+    }
+}
+
+/// A [`TimeValueWithRequiredFrame`] along with the system whose frames it counts.  This allows
+/// adding and subtracting frame counts with arithmetic operators.
+///
+/// Adding or subtracting an [`i64`] moves the time value by that many frames, wrapping around at
+/// 24 hours.  Subtracting two values returns the number of frames between them, without any
+/// wrapping.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct SystemTimeValue {
+    /// The time value.
+    pub time: TimeValueWithRequiredFrame,
+
+    /// The system that determines how many frames are counted per second.
+    pub system: System,
+}
+
+impl SystemTimeValue {
+    /// Returns the number of frames from `00:00:00:00` up to this time value.
+    pub fn to_frame_index(&self) -> u32 {
+        self.time.to_frame_index(self.system)
+    }
+
+    /// Returns the time value of the frame that follows this one.
+    pub fn next_frame(&self) -> Self {
+        *self + 1
+    }
+}
+
+impl Add<i64> for SystemTimeValue {
+    type Output = Self;
+
+    fn add(self, frames: i64) -> Self {
+        Self { time: self.time.add_frames(frames, self.system), system: self.system }
+    }
+}
+
+impl AddAssign<i64> for SystemTimeValue {
+    fn add_assign(&mut self, frames: i64) {
+        *self = *self + frames;
+    }
+}
+
+impl Sub<i64> for SystemTimeValue {
+    type Output = Self;
+
+    fn sub(self, frames: i64) -> Self {
+        self + -frames
+    }
+}
+
+impl SubAssign<i64> for SystemTimeValue {
+    fn sub_assign(&mut self, frames: i64) {
+        *self = *self - frames;
+    }
+}
+
+impl Sub for SystemTimeValue {
+    type Output = i64;
+
+    /// Returns the number of frames from `rhs` to `self`, which is negative if `rhs` is later in
+    /// the day.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the two values are for different systems.
+    fn sub(self, rhs: Self) -> i64 {
+        assert_eq!(self.system, rhs.system, "cannot subtract time values of different systems");
+        i64::from(self.to_frame_index()) - i64::from(rhs.to_frame_index())
+    }
+}
+
 super::util::required_enum! {
     /// Indicates whether color frame identification was intentionally applied to the timecode by
     /// the original source.
@@ -656,6 +854,42 @@ fn to_raw(&self, ctx: &super::PackContext) -> super::RawPackData {
     }
 }
 
+/// Number of 0 bits in the sync word `0011_1111_1111_1101` that ends every linear time code (LTC)
+/// code word.
+const LTC_SYNC_WORD_ZERO_COUNT: u32 = 3;
+
+impl Timecode<TimeValueWithRequiredFrame> {
+    /// Returns the [`PolarityCorrection`] that gives the linear time code (LTC) code word of this
+    /// timecode an even number of 0 bits, given the values of the other fields.
+    ///
+    /// The 80-bit code word is made up of the 32 bits of the timecode, the 32 bits of the
+    /// `binary_group` that goes with it, and the 16-bit sync word.
+    ///
+    /// - IEC 60461:2010 Section 8.2.6 - Biphase mark polarity correction
+    pub fn even_parity_polarity_correction(
+        &self,
+        binary_group: &super::BinaryGroup,
+    ) -> PolarityCorrection {
+        let bcd_ones = |value: u8| (value % 10).count_ones() + (value / 10).count_ones();
+        let timecode_ones = bcd_ones(self.time.hour)
+            + bcd_ones(self.time.minute)
+            + bcd_ones(self.time.second)
+            + bcd_ones(self.time.frame)
+            + u32::from(self.time.drop_frame)
+            + u32::from(self.color_frame == ColorFrame::Synchronized)
+            + (self.binary_group_flag as u8).count_ones();
+        let binary_group_ones: u32 =
+            binary_group.group_data.iter().map(|group| group.value().count_ones()).sum();
+        // 31 bits of the timecode are counted above; the polarity correction bit is the 32nd.
+        let zeros = (31 - timecode_ones) + (32 - binary_group_ones) + LTC_SYNC_WORD_ZERO_COUNT;
+        if zeros % 2 == 0 {
+            PolarityCorrection::Odd
+        } else {
+            PolarityCorrection::Even
+        }
+    }
+}
+
 impl super::ValidPackDataTrait<Timecode<TimeValueWithRequiredFrame>>
     for super::ValidPack<Timecode<TimeValueWithRequiredFrame>>
 {
@@ -1,10 +1,12 @@
+use arbitrary_int::u4;
+use googletest::prelude::*;
 use rstest::rstest;
 use serde_test::Token;
 use stdext::function_name;
 use testutil::*;
 
 use super::super::*;
-use crate::{pack::testutil::PackBinaryTestCase, testutil::*};
+use crate::{file::System, pack::testutil::PackBinaryTestCase, testutil::*};
 
 const ZERO_TIMECODE: TitleTimecode = TitleTimecode {
     timecode: Timecode::<TimeValueWithRequiredFrame> {
@@ -1038,3 +1040,259 @@ fn test_recording_time_serde(#[case] test_function_name: &str) {
     let tc = RECORDING_TIME_SERDE_TEST_CASES.get_test_case(test_function_name);
     serde_test::assert_tokens(&tc.value, tc.tokens);
 }
+
+// ==================== POLARITY CORRECTION TESTING ====================
+// Tests the parity bit of the linear time code (LTC) code word.
+
+fn binary_group(groups: [u8; 8]) -> BinaryGroup {
+    BinaryGroup { group_data: groups.map(u4::new) }
+}
+
+#[googletest::test]
+#[rstest]
+// Timecode and binary groups have 31 + 32 zeros, plus 3 in the sync word: the bit must be 1.
+#[case::all_zero(ZERO_TIMECODE.timecode, binary_group([0; 8]), PolarityCorrection::Odd)]
+// One more 1 bit in the binary groups makes the count odd without the bit: the bit must be 0.
+#[case::binary_group_bit(
+    ZERO_TIMECODE.timecode,
+    binary_group([1, 0, 0, 0, 0, 0, 0, 0]),
+    PolarityCorrection::Even
+)]
+// 00:00:00;01 with drop frame: two 1 bits in the timecode.
+#[case::timecode_bits(
+    Timecode { time: time_value(0, 0, 0, true, 1), ..ZERO_TIMECODE.timecode },
+    binary_group([0; 8]),
+    PolarityCorrection::Odd
+)]
+// 12:34:56;17, color frame, binary group flag 0b110, and binary groups 0x1234_5678.
+#[case::everything(
+    Timecode {
+        time: time_value(12, 34, 56, true, 17),
+        color_frame: ColorFrame::Synchronized,
+        binary_group_flag: BinaryGroupFlag::TimeClockGroupDateTimeZone,
+        ..ZERO_TIMECODE.timecode
+    },
+    binary_group([8, 7, 6, 5, 4, 3, 2, 1]),
+    PolarityCorrection::Odd
+)]
+fn test_even_parity_polarity_correction(
+    #[case] timecode: Timecode<TimeValueWithRequiredFrame>,
+    #[case] binary_group: BinaryGroup,
+    #[case] expected: PolarityCorrection,
+) {
+    expect_that!(timecode.even_parity_polarity_correction(&binary_group), eq(expected));
+
+    // The encoded code word, including the 0b0011_1111_1111_1101 sync word, has an even number
+    // of 0 bits in both systems.
+    for ctx in [*NTSC, *PAL] {
+        let timecode = Timecode { polarity_correction: expected, ..timecode };
+        let timecode_raw = u32::from_le_bytes(validated(timecode, ctx).to_raw(&ctx));
+        let binary_group_raw = u32::from_le_bytes(validated(binary_group, ctx).to_raw(&ctx));
+        let zeros =
+            timecode_raw.count_zeros() + binary_group_raw.count_zeros() + 0x3FFD_u16.count_zeros();
+        expect_that!(zeros % 2, eq(0));
+    }
+}
+
+// ==================== ARITHMETIC TESTING ====================
+// Tests conversion to/from frame indices and adding frames to time values.
+
+fn time_value(
+    hour: u8,
+    minute: u8,
+    second: u8,
+    drop_frame: bool,
+    frame: u8,
+) -> TimeValueWithRequiredFrame {
+    TimeValueWithRequiredFrame { hour, minute, second, drop_frame, frame }
+}
+
+#[googletest::test]
+#[rstest]
+#[case::ntsc_zero(time_value(0, 0, 0, false, 0), System::Sys525_60, 0)]
+#[case::ntsc_one_second(time_value(0, 0, 1, false, 0), System::Sys525_60, 30)]
+#[case::ntsc_one_minute(time_value(0, 1, 0, false, 0), System::Sys525_60, 1800)]
+#[case::ntsc_last(time_value(23, 59, 59, false, 29), System::Sys525_60, 2_591_999)]
+#[case::ntsc_drop_frame_before_minute(time_value(0, 0, 59, true, 29), System::Sys525_60, 1799)]
+#[case::ntsc_drop_frame_after_minute(time_value(0, 1, 0, true, 2), System::Sys525_60, 1800)]
+#[case::ntsc_drop_frame_ten_minutes(time_value(0, 10, 0, true, 0), System::Sys525_60, 17982)]
+#[case::ntsc_drop_frame_eleven_minutes(time_value(0, 11, 0, true, 2), System::Sys525_60, 19782)]
+#[case::ntsc_drop_frame_hour(time_value(1, 0, 0, true, 0), System::Sys525_60, 107_892)]
+#[case::ntsc_drop_frame_last(time_value(23, 59, 59, true, 29), System::Sys525_60, 2_589_407)]
+#[case::pal_one_second(time_value(0, 0, 1, false, 0), System::Sys625_50, 25)]
+#[case::pal_hour(time_value(1, 0, 0, false, 0), System::Sys625_50, 90000)]
+#[case::pal_ignores_drop_frame(time_value(0, 1, 0, true, 0), System::Sys625_50, 1500)]
+#[case::pal_last(time_value(23, 59, 59, false, 24), System::Sys625_50, 2_159_999)]
+fn test_frame_index(
+    #[case] time: TimeValueWithRequiredFrame,
+    #[case] system: System,
+    #[case] frame_index: u32,
+) {
+    expect_that!(time.to_frame_index(system), eq(frame_index));
+    expect_that!(
+        TimeValueWithRequiredFrame::from_frame_index(frame_index, system, time.drop_frame),
+        eq(time)
+    );
+}
+
+#[googletest::test]
+#[rstest]
+#[case::ntsc(System::Sys525_60, false)]
+#[case::ntsc_drop_frame(System::Sys525_60, true)]
+#[case::pal(System::Sys625_50, false)]
+fn test_frame_index_round_trip(#[case] system: System, #[case] drop_frame: bool) {
+    // Every frame index of the day must map to a distinct, valid time value that maps back.
+    let ctx = match system {
+        System::Sys525_60 => *NTSC,
+        System::Sys625_50 => *PAL,
+    };
+    let frames_per_day = TimeValueWithRequiredFrame::frames_per_day(system, drop_frame);
+    let mut previous = None;
+    for frame_index in 0..frames_per_day {
+        let time = TimeValueWithRequiredFrame::from_frame_index(frame_index, system, drop_frame);
+        assert_eq!(time.to_frame_index(system), frame_index);
+        assert!(time.validate_with(&ctx).is_ok(), "{time:?} is not valid");
+        assert!(previous.map_or(true, |previous| previous < time));
+        previous = Some(time);
+    }
+    expect_that!(
+        TimeValueWithRequiredFrame::from_frame_index(frames_per_day, system, drop_frame),
+        eq(time_value(0, 0, 0, drop_frame, 0))
+    );
+}
+
+#[googletest::test]
+#[rstest]
+#[case::ntsc_frame(
+    time_value(0, 0, 0, false, 0),
+    System::Sys525_60,
+    1,
+    time_value(0, 0, 0, false, 1)
+)]
+#[case::ntsc_second(
+    time_value(0, 0, 0, false, 29),
+    System::Sys525_60,
+    1,
+    time_value(0, 0, 1, false, 0)
+)]
+#[case::ntsc_minute(
+    time_value(0, 0, 59, false, 29),
+    System::Sys525_60,
+    1,
+    time_value(0, 1, 0, false, 0)
+)]
+#[case::ntsc_drop_frame_minute(
+    time_value(0, 0, 59, true, 29),
+    System::Sys525_60,
+    1,
+    time_value(0, 1, 0, true, 2)
+)]
+#[case::ntsc_drop_frame_ten_minutes(
+    time_value(0, 9, 59, true, 29),
+    System::Sys525_60,
+    1,
+    time_value(0, 10, 0, true, 0)
+)]
+#[case::ntsc_drop_frame_backwards(
+    time_value(1, 23, 0, true, 2),
+    System::Sys525_60,
+    -1,
+    time_value(1, 22, 59, true, 29)
+)]
+#[case::ntsc_drop_frame_hour(
+    time_value(1, 0, 0, true, 0),
+    System::Sys525_60,
+    107_892,
+    time_value(2, 0, 0, true, 0)
+)]
+#[case::ntsc_wrap_forwards(
+    time_value(23, 59, 59, true, 29),
+    System::Sys525_60,
+    1,
+    time_value(0, 0, 0, true, 0)
+)]
+#[case::ntsc_wrap_backwards(
+    time_value(0, 0, 0, false, 0),
+    System::Sys525_60,
+    -1,
+    time_value(23, 59, 59, false, 29)
+)]
+#[case::ntsc_wrap_multiple_days(
+    time_value(12, 0, 0, false, 0),
+    System::Sys525_60,
+    2_592_000 * 3 + 1,
+    time_value(12, 0, 0, false, 1)
+)]
+#[case::pal_second(
+    time_value(0, 0, 0, false, 24),
+    System::Sys625_50,
+    1,
+    time_value(0, 0, 1, false, 0)
+)]
+#[case::pal_minute(
+    time_value(0, 0, 59, true, 24),
+    System::Sys625_50,
+    1,
+    time_value(0, 1, 0, true, 0)
+)]
+#[case::pal_wrap(
+    time_value(23, 59, 59, false, 24),
+    System::Sys625_50,
+    1,
+    time_value(0, 0, 0, false, 0)
+)]
+fn test_add_frames(
+    #[case] time: TimeValueWithRequiredFrame,
+    #[case] system: System,
+    #[case] frames: i64,
+    #[case] expected: TimeValueWithRequiredFrame,
+) {
+    expect_that!(time.add_frames(frames, system), eq(expected));
+    expect_that!(expected.add_frames(-frames, system), eq(time));
+
+    let value = SystemTimeValue { time, system };
+    expect_that!(value + frames, eq(SystemTimeValue { time: expected, system }));
+    expect_that!(SystemTimeValue { time: expected, system } - frames, eq(value));
+    if frames == 1 {
+        expect_that!(time.next_frame(system), eq(expected));
+        expect_that!(value.next_frame(), eq(SystemTimeValue { time: expected, system }));
+    }
+}
+
+#[googletest::test]
+fn test_system_time_value_operators() {
+    let system = System::Sys525_60;
+    let mut value = SystemTimeValue { time: time_value(0, 0, 59, true, 28), system };
+    value += 2;
+    expect_that!(value.time, eq(time_value(0, 1, 0, true, 2)));
+    value -= 3;
+    expect_that!(value.time, eq(time_value(0, 0, 59, true, 27)));
+
+    let later = SystemTimeValue { time: time_value(0, 10, 0, true, 0), system };
+    expect_that!(later - value, eq(17982 - 1797));
+    expect_that!(value - later, eq(1797 - 17982));
+    expect_that!(later.to_frame_index(), eq(17982));
+}
+
+#[googletest::test]
+fn test_time_value_ordering() {
+    let mut values = vec![
+        time_value(1, 0, 0, false, 0),
+        time_value(0, 0, 59, false, 29),
+        time_value(0, 1, 0, false, 0),
+        time_value(0, 0, 0, false, 1),
+        time_value(0, 0, 0, false, 0),
+    ];
+    values.sort();
+    expect_that!(
+        values,
+        elements_are![
+            eq(&time_value(0, 0, 0, false, 0)),
+            eq(&time_value(0, 0, 0, false, 1)),
+            eq(&time_value(0, 0, 59, false, 29)),
+            eq(&time_value(0, 1, 0, false, 0)),
+            eq(&time_value(1, 0, 0, false, 0)),
+        ]
+    );
+    expect_true!(time_value(0, 0, 1, false, 0) > time_value(0, 0, 0, false, 29));
+}
@@ -20,7 +20,7 @@ macro_rules! type_macro {
         /// For more information about the contents of a pack, refer to the documentation of
         /// the corresponding struct type wrapped by the enumeration.  The enum variants themselves
         /// only have minimal documentation.
-        #[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+        #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
         #[serde(tag = "pack_type", content = "unknown_value")]
         pub enum Type {
             $($(#[$attr])* $name,)*
@@ -0,0 +1,365 @@
+use std::{fmt, io};
+
+use chrono::{NaiveDateTime, TimeDelta};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    dif::{self, FrameMetadata, RecordingTimestamp},
+    file::{FrameReader, FrameResult, System, ValidInfoMethods},
+    pack,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Signs of a scene boundary found in a single frame.
+///
+/// Flags are [`None`] if the frame has no consensus pack to read them from.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct SceneMarkers {
+    /// Recording start point of the VAUX source control pack.
+    pub video_start_point: Option<bool>,
+
+    /// Recording start point of the AAUX source control packs.  It is set if any audio block
+    /// channel has it set.
+    pub audio_start_point: Option<bool>,
+
+    /// Recording end point of the AAUX source control packs.  It is set if any audio block
+    /// channel has it set.
+    pub audio_end_point: Option<bool>,
+
+    /// When the frame was recorded.
+    pub timestamp: Option<RecordingTimestamp>,
+}
+
+impl SceneMarkers {
+    /// Read the scene markers from the consensus metadata of a frame.
+    pub fn from_metadata(metadata: &FrameMetadata, system: System) -> Self {
+        let video_start_point = match metadata.pack(pack::Type::VAUXSourceControl) {
+            Some(pack::Pack::VAUXSourceControl(source_control)) => {
+                Some(source_control.recording_start_point)
+            }
+            _ => None,
+        };
+        // Recording start and end points of each audio block channel.
+        let audio_points: Vec<_> = (0..metadata.aaux_packs.len())
+            .filter_map(|channel| {
+                match metadata.aaux_pack(channel, pack::Type::AAUXSourceControl) {
+                    Some(pack::Pack::AAUXSourceControl(source_control)) => Some((
+                        source_control.recording_start_point,
+                        source_control.recording_end_point,
+                    )),
+                    _ => None,
+                }
+            })
+            .collect();
+        let any = |flag: fn(&(bool, bool)) -> bool| {
+            (!audio_points.is_empty()).then(|| audio_points.iter().any(flag))
+        };
+        Self {
+            video_start_point,
+            audio_start_point: any(|(start, _)| *start),
+            audio_end_point: any(|(_, end)| *end),
+            timestamp: RecordingTimestamp::from_metadata(metadata, system),
+        }
+    }
+
+    /// Read the scene markers of a frame.
+    pub fn from_frame(frame: &dif::Frame) -> Self {
+        Self::from_metadata(&FrameMetadata::from_frame(frame), frame.file_info.system())
+    }
+}
+
+/// Why a scene was found to start at a frame.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub enum SceneBoundaryReason {
+    /// The frame is the first frame that was analyzed.
+    StartOfFile,
+
+    /// The VAUX source control pack starts flagging a recording start point.
+    VideoStartPoint,
+
+    /// The AAUX source control packs start flagging a recording start point.
+    AudioStartPoint,
+
+    /// The AAUX source control packs stopped flagging a recording end point.
+    AudioEndPoint,
+
+    /// The recording date and time jumped backwards, or further forward than the time between
+    /// the frames allows.
+    TimestampJump,
+}
+
+impl fmt::Display for SceneBoundaryReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::StartOfFile => "start of file",
+            Self::VideoStartPoint => "video start point",
+            Self::AudioStartPoint => "audio start point",
+            Self::AudioEndPoint => "audio end point",
+            Self::TimestampJump => "timestamp jump",
+        })
+    }
+}
+
+/// How certain it is that a scene really starts where it was found.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Serialize, Deserialize)]
+pub enum SceneConfidence {
+    /// Only a single recording start or end point flag marks the boundary.  Such flags are
+    /// sometimes set by a dropout, or by equipment that sets them inconsistently.
+    Low,
+
+    /// The boundary is marked by a jump in the recording timestamp, or by several flags.
+    Medium,
+
+    /// The boundary is marked by both a jump in the recording timestamp and at least one flag,
+    /// or it is the start of the file.
+    High,
+}
+
+impl SceneConfidence {
+    /// Judge the confidence of a scene boundary from the reasons it was found.
+    pub fn from_reasons(reasons: &[SceneBoundaryReason]) -> Self {
+        let timestamp_jump = reasons.contains(&SceneBoundaryReason::TimestampJump);
+        let flags = reasons
+            .iter()
+            .filter(|reason| {
+                !matches!(
+                    reason,
+                    SceneBoundaryReason::StartOfFile | SceneBoundaryReason::TimestampJump
+                )
+            })
+            .count();
+        if reasons.contains(&SceneBoundaryReason::StartOfFile) || (timestamp_jump && flags > 0) {
+            Self::High
+        } else if timestamp_jump || flags > 1 {
+            Self::Medium
+        } else {
+            Self::Low
+        }
+    }
+}
+
+/// A single recording found by the [`SceneDetector`].
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Scene {
+    /// Index of the first frame of the scene.
+    pub frame_index: u64,
+
+    /// Number of frames in the scene.
+    pub frame_count: u64,
+
+    /// Recording timestamp of the first frame of the scene that has one.
+    pub start: Option<RecordingTimestamp>,
+
+    /// Recording timestamp of the last frame of the scene that has one.
+    pub end: Option<RecordingTimestamp>,
+
+    /// Why the scene was found to start at [`Scene::frame_index`].
+    pub reasons: Vec<SceneBoundaryReason>,
+
+    /// How certain it is that the scene starts at [`Scene::frame_index`].
+    pub confidence: SceneConfidence,
+}
+
+/// Scenes found across a file, in order.
+///
+/// The [`fmt::Display`] implementation prints one line for each scene, such as
+/// `scene 2, frames 300-899, 2024-07-08 19:55:58 to 2024-07-08 19:56:18, video start point,
+/// timestamp jump (high confidence)`.
+#[derive(Debug, PartialEq, Eq, Clone, Default, Serialize, Deserialize)]
+pub struct SceneList {
+    /// The scenes, which together cover every frame that was analyzed.
+    pub scenes: Vec<Scene>,
+}
+
+impl fmt::Display for SceneList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (number, scene) in (1..).zip(&self.scenes) {
+            write!(f, "scene {number}, ")?;
+            match scene.frame_count {
+                1 => write!(f, "frame {}", scene.frame_index)?,
+                _ => write!(
+                    f,
+                    "frames {}-{}",
+                    scene.frame_index,
+                    scene.frame_index + scene.frame_count - 1
+                )?,
+            }
+            match (scene.start, scene.end) {
+                (Some(start), Some(end)) => write!(f, ", {start} to {end}")?,
+                _ => write!(f, ", no timestamp")?,
+            }
+            for reason in &scene.reasons {
+                write!(f, ", {reason}")?;
+            }
+            let confidence = match scene.confidence {
+                SceneConfidence::Low => "low",
+                SceneConfidence::Medium => "medium",
+                SceneConfidence::High => "high",
+            };
+            writeln!(f, " ({confidence} confidence)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Options for the [`SceneDetector`].
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct SceneDetectionOptions {
+    /// How much further the recording timestamp may move forward than the time between two
+    /// frames, before it is considered a jump.  The recording time usually only has a resolution
+    /// of one second, so this should be at least one second.
+    pub max_timestamp_gap: TimeDelta,
+}
+
+impl Default for SceneDetectionOptions {
+    fn default() -> Self {
+        Self { max_timestamp_gap: TimeDelta::seconds(1) }
+    }
+}
+
+/// Last known value of a recording start or end point flag.
+#[derive(Debug, Default)]
+struct FlagState {
+    previous: Option<bool>,
+}
+
+impl FlagState {
+    /// Update the state with the flag of the next frame.  Returns whether the flag changed from
+    /// `from` to the opposite value.  Frames where the flag is unknown are skipped over.
+    fn changed(&mut self, flag: Option<bool>, from: bool) -> bool {
+        let Some(flag) = flag else {
+            return false;
+        };
+        let changed = self.previous == Some(from) && flag != from;
+        self.previous = Some(flag);
+        changed
+    }
+}
+
+/// Walks the [`SceneMarkers`] of consecutive frames, and splits them into scenes.
+///
+/// A scene boundary is found at:
+/// - the first frame where a recording start point flag is set, after a frame where it was not
+///   set.
+/// - the first frame where the recording end point flag is no longer set, after a frame where it
+///   was set.
+/// - a frame whose recording timestamp is earlier than the previous recording timestamp, or
+///   later by more than the time between the frames plus
+///   [`SceneDetectionOptions::max_timestamp_gap`].  Only timestamps with both a date and a time
+///   are compared, using the local time.
+///
+/// Frames where a pack is missing are skipped over when looking for changes.  Since the flags are
+/// repeated for a full second, boundaries found within one second of the start of the current
+/// scene are merged into it, and only add to its reasons.
+#[derive(Debug)]
+pub struct SceneDetector {
+    options: SceneDetectionOptions,
+    scenes: Vec<Scene>,
+    video_start_point: FlagState,
+    audio_start_point: FlagState,
+    audio_end_point: FlagState,
+
+    /// Index and local date and time of the last frame with a full recording timestamp.
+    previous_timestamp: Option<(u64, NaiveDateTime)>,
+}
+
+impl SceneDetector {
+    /// Creates a detector that has not seen any frames yet.
+    pub fn new(options: SceneDetectionOptions) -> Self {
+        Self {
+            options,
+            scenes: Vec::new(),
+            video_start_point: FlagState::default(),
+            audio_start_point: FlagState::default(),
+            audio_end_point: FlagState::default(),
+            previous_timestamp: None,
+        }
+    }
+
+    /// Add the next frame of the file to the detection.
+    ///
+    /// Frames must be added in increasing order of their index, without gaps.
+    pub fn add_frame(&mut self, frame_index: u64, frame: &dif::Frame) {
+        self.add_markers(frame_index, frame.file_info.system(), &SceneMarkers::from_frame(frame));
+    }
+
+    /// Add the scene markers of the next frame of the file to the detection.
+    ///
+    /// Frames must be added in increasing order of their index, without gaps.
+    pub fn add_markers(&mut self, frame_index: u64, system: System, markers: &SceneMarkers) {
+        let mut reasons = Vec::new();
+        if self.scenes.is_empty() {
+            reasons.push(SceneBoundaryReason::StartOfFile);
+        }
+        if self.video_start_point.changed(markers.video_start_point, false) {
+            reasons.push(SceneBoundaryReason::VideoStartPoint);
+        }
+        if self.audio_start_point.changed(markers.audio_start_point, false) {
+            reasons.push(SceneBoundaryReason::AudioStartPoint);
+        }
+        if self.audio_end_point.changed(markers.audio_end_point, true) {
+            reasons.push(SceneBoundaryReason::AudioEndPoint);
+        }
+        if let Some(timestamp) = markers.timestamp.and_then(|t| t.naive_date_time()) {
+            if let Some((previous_index, previous)) = self.previous_timestamp {
+                let frames = i64::try_from(frame_index - previous_index).unwrap();
+                let elapsed = TimeDelta::nanoseconds(
+                    frames * 1_000_000_000 / i64::from(pack::timecode_frame_rate(system)),
+                );
+                let moved = timestamp - previous;
+                if moved < TimeDelta::zero() || moved > elapsed + self.options.max_timestamp_gap {
+                    reasons.push(SceneBoundaryReason::TimestampJump);
+                }
+            }
+            self.previous_timestamp = Some((frame_index, timestamp));
+        }
+
+        let merge_window = u64::from(pack::timecode_frame_rate(system));
+        match self.scenes.last_mut() {
+            Some(scene) if reasons.is_empty() || frame_index < scene.frame_index + merge_window => {
+                for reason in reasons {
+                    if !scene.reasons.contains(&reason) {
+                        scene.reasons.push(reason);
+                    }
+                }
+                scene.confidence = SceneConfidence::from_reasons(&scene.reasons);
+                scene.frame_count = frame_index + 1 - scene.frame_index;
+                if markers.timestamp.is_some() {
+                    scene.start = scene.start.or(markers.timestamp);
+                    scene.end = markers.timestamp;
+                }
+            }
+            _ => self.scenes.push(Scene {
+                frame_index,
+                frame_count: 1,
+                start: markers.timestamp,
+                end: markers.timestamp,
+                confidence: SceneConfidence::from_reasons(&reasons),
+                reasons,
+            }),
+        }
+    }
+
+    /// Finish the detection and return the scenes.
+    pub fn finish(self) -> SceneList {
+        SceneList { scenes: self.scenes }
+    }
+}
+
+/// Find the scenes of every frame in a file; see [`SceneDetector`].
+pub fn detect_scenes<R: io::Read + io::Seek>(
+    reader: &mut FrameReader<R>,
+    options: SceneDetectionOptions,
+) -> FrameResult<SceneList> {
+    reader.seek_frame(0)?;
+    let mut detector = SceneDetector::new(options);
+    loop {
+        let frame_index = reader.next_frame_index();
+        match reader.read_frame()? {
+            Some(frame) => detector.add_frame(frame_index, &frame),
+            None => return Ok(detector.finish()),
+        }
+    }
+}
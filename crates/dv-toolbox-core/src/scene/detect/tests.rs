@@ -0,0 +1,232 @@
+use chrono::NaiveDate;
+use googletest::prelude::*;
+
+use super::*;
+use crate::file::testutil::{frame_reader, read_test_frames, SONY_GOOD_QUALITY};
+
+/// Recording timestamp that is the given number of seconds after the start of the tape.
+fn at(seconds: i64) -> Option<RecordingTimestamp> {
+    let start = NaiveDate::from_ymd_opt(2024, 7, 8).unwrap().and_hms_opt(19, 55, 58).unwrap();
+    Some(RecordingTimestamp::NoTimeZone(start + TimeDelta::seconds(seconds)))
+}
+
+/// Markers of a recording that runs continuously for the given number of frames, starting at the
+/// given number of seconds after the start of the tape.  The start points are set for the first
+/// second of the recording, and the end point for the last second.
+fn recording(frame_count: u64, seconds: i64) -> Vec<SceneMarkers> {
+    (0..frame_count)
+        .map(|i| SceneMarkers {
+            video_start_point: Some(i < 30),
+            audio_start_point: Some(i < 30),
+            audio_end_point: Some(i + 30 >= frame_count),
+            timestamp: at(seconds + i64::try_from(i / 30).unwrap()),
+        })
+        .collect()
+}
+
+fn detect(markers: &[SceneMarkers]) -> SceneList {
+    let mut detector = SceneDetector::new(SceneDetectionOptions::default());
+    for (frame_index, markers) in (0..).zip(markers) {
+        detector.add_markers(frame_index, System::Sys525_60, markers);
+    }
+    detector.finish()
+}
+
+fn scene(
+    frame_index: u64,
+    frame_count: u64,
+    start: Option<RecordingTimestamp>,
+    end: Option<RecordingTimestamp>,
+    reasons: &[SceneBoundaryReason],
+) -> Scene {
+    Scene {
+        frame_index,
+        frame_count,
+        start,
+        end,
+        reasons: reasons.to_vec(),
+        confidence: SceneConfidence::from_reasons(reasons),
+    }
+}
+
+#[googletest::test]
+fn test_no_markers() {
+    let scenes = detect(&[SceneMarkers::default(); 100]);
+    expect_that!(
+        scenes.scenes,
+        elements_are![eq(&scene(0, 100, None, None, &[SceneBoundaryReason::StartOfFile]))]
+    );
+    expect_that!(
+        scenes.to_string(),
+        eq("scene 1, frames 0-99, no timestamp, start of file \
+        (high confidence)\n")
+    );
+}
+
+#[googletest::test]
+fn test_all_markers() {
+    let mut markers = recording(300, 0);
+    markers.extend(recording(90, 3600));
+    let scenes = detect(&markers);
+    expect_that!(
+        scenes.scenes,
+        elements_are![
+            eq(&scene(0, 300, at(0), at(9), &[SceneBoundaryReason::StartOfFile])),
+            eq(&scene(
+                300,
+                90,
+                at(3600),
+                at(3602),
+                &[
+                    SceneBoundaryReason::VideoStartPoint,
+                    SceneBoundaryReason::AudioStartPoint,
+                    SceneBoundaryReason::AudioEndPoint,
+                    SceneBoundaryReason::TimestampJump,
+                ]
+            )),
+        ]
+    );
+    expect_that!(
+        scenes.to_string(),
+        eq("scene 1, frames 0-299, 2024-07-08 19:55:58 to 2024-07-08 19:56:07, start of file \
+                (high confidence)\n\
+            scene 2, frames 300-389, 2024-07-08 20:55:58 to 2024-07-08 20:56:00, video start \
+                point, audio start point, audio end point, timestamp jump (high confidence)\n")
+    );
+}
+
+#[googletest::test]
+fn test_start_point_only() {
+    // Some camcorders only set the start point, and don't record a date and time.
+    let mut markers = recording(100, 0);
+    markers.extend(recording(100, 0));
+    for markers in &mut markers {
+        markers.audio_start_point = None;
+        markers.audio_end_point = None;
+        markers.timestamp = None;
+    }
+    let scenes = detect(&markers);
+    expect_that!(
+        scenes.scenes,
+        elements_are![
+            eq(&scene(0, 100, None, None, &[SceneBoundaryReason::StartOfFile])),
+            eq(&scene(100, 100, None, None, &[SceneBoundaryReason::VideoStartPoint])),
+        ]
+    );
+    expect_that!(scenes.scenes[1].confidence, eq(SceneConfidence::Low));
+}
+
+#[googletest::test]
+fn test_timestamp_only() {
+    let mut markers = recording(100, 0);
+    markers.extend(recording(100, 6));
+    markers.extend(recording(100, 5));
+    for markers in &mut markers {
+        markers.video_start_point = None;
+        markers.audio_start_point = None;
+        markers.audio_end_point = None;
+    }
+    let scenes = detect(&markers);
+    // The second recording starts too late after the first one to be a continuation of it, and
+    // the third recording goes backwards in time.
+    expect_that!(
+        scenes.scenes,
+        elements_are![
+            eq(&scene(0, 100, at(0), at(3), &[SceneBoundaryReason::StartOfFile])),
+            eq(&scene(100, 100, at(6), at(9), &[SceneBoundaryReason::TimestampJump])),
+            eq(&scene(200, 100, at(5), at(8), &[SceneBoundaryReason::TimestampJump])),
+        ]
+    );
+    expect_that!(scenes.scenes[1].confidence, eq(SceneConfidence::Medium));
+}
+
+#[googletest::test]
+fn test_missing_markers() {
+    let mut markers = recording(100, 0);
+    markers.extend(recording(100, 60));
+    // A dropout around the boundary removes the packs from a few frames.  The boundary is found
+    // at the first frame that has them again.
+    for markers in &mut markers[95..105] {
+        *markers = SceneMarkers::default();
+    }
+    let scenes = detect(&markers);
+    expect_that!(
+        scenes.scenes,
+        elements_are![
+            eq(&scene(0, 105, at(0), at(3), &[SceneBoundaryReason::StartOfFile])),
+            eq(&scene(
+                105,
+                95,
+                at(60),
+                at(63),
+                &[
+                    SceneBoundaryReason::VideoStartPoint,
+                    SceneBoundaryReason::AudioStartPoint,
+                    SceneBoundaryReason::AudioEndPoint,
+                    SceneBoundaryReason::TimestampJump,
+                ]
+            )),
+        ]
+    );
+}
+
+#[googletest::test]
+fn test_merge_close_boundaries() {
+    let mut markers = recording(100, 0);
+    // The date and time only start being recorded a few frames after the start point.
+    for markers in &mut markers[..5] {
+        markers.timestamp = None;
+    }
+    markers.extend(recording(100, 0));
+    for markers in &mut markers[100..110] {
+        markers.video_start_point = Some(false);
+    }
+    let scenes = detect(&markers);
+    expect_that!(
+        scenes.scenes,
+        elements_are![
+            eq(&scene(0, 100, at(0), at(3), &[SceneBoundaryReason::StartOfFile])),
+            eq(&scene(
+                100,
+                100,
+                at(0),
+                at(3),
+                &[
+                    SceneBoundaryReason::AudioStartPoint,
+                    SceneBoundaryReason::AudioEndPoint,
+                    SceneBoundaryReason::TimestampJump,
+                    SceneBoundaryReason::VideoStartPoint,
+                ]
+            )),
+        ]
+    );
+}
+
+#[googletest::test]
+#[rstest::rstest]
+#[case::start_of_file(&[SceneBoundaryReason::StartOfFile], SceneConfidence::High)]
+#[case::single_flag(&[SceneBoundaryReason::AudioEndPoint], SceneConfidence::Low)]
+#[case::flags(
+    &[SceneBoundaryReason::VideoStartPoint, SceneBoundaryReason::AudioStartPoint],
+    SceneConfidence::Medium
+)]
+#[case::timestamp(&[SceneBoundaryReason::TimestampJump], SceneConfidence::Medium)]
+#[case::timestamp_and_flag(
+    &[SceneBoundaryReason::TimestampJump, SceneBoundaryReason::VideoStartPoint],
+    SceneConfidence::High
+)]
+fn test_confidence(#[case] reasons: &[SceneBoundaryReason], #[case] expected: SceneConfidence) {
+    expect_that!(SceneConfidence::from_reasons(reasons), eq(expected));
+}
+
+#[googletest::test]
+fn test_detect_scenes() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let mut reader = frame_reader(&frames, &SONY_GOOD_QUALITY);
+    reader.seek_frame(3).unwrap();
+    let scenes = detect_scenes(&mut reader, SceneDetectionOptions::default()).unwrap();
+    expect_that!(
+        scenes.scenes,
+        elements_are![eq(&scene(0, 5, at(0), at(0), &[SceneBoundaryReason::StartOfFile]))]
+    );
+}
@@ -0,0 +1,43 @@
+//! Detection of the separate recordings, or scenes, on a tape.
+//!
+//! A tape usually holds many recordings, made each time the camcorder was started and stopped.
+//! DV marks these places in several ways: the source control packs flag the first second of each
+//! recording with a recording start point (see [`crate::pack::VAUXSourceControl`] and
+//! [`crate::pack::AAUXSourceControl`]), the audio source control pack also flags the last second
+//! of each recording with a recording end point, and the recording date and time jump from one
+//! recording to the next (see [`crate::dif::RecordingTimestamp`]).
+//!
+//! The [`SceneDetector`] combines these signals into a list of [`Scene`] values, much like the
+//! autosplit feature of dvgrab.  The [`split_scenes`] and [`split_scenes_to_directory`]
+//! functions then copy each scene to its own bare DV file, named after a
+//! [`SceneFileNameTemplate`].  The frames are copied exactly as they were read, so nothing is
+//! re-encoded.
+//!
+//! Dropouts often corrupt the recording date and time of individual frames.  The
+//! [`repair_date_times`] and [`repair_file_date_times`] functions rebuild them by counting frames
+//! from the neighboring frames of the same scene.
+
+pub use detect::*;
+pub use repair::*;
+use snafu::prelude::*;
+pub use split::*;
+
+mod detect;
+mod repair;
+mod split;
+
+/// Result type for calls related to splitting and repairing scenes.
+pub type SceneResult<T, E = SceneError> = std::result::Result<T, E>;
+
+/// Error type for when scenes could not be split or repaired.
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum SceneError {
+    #[snafu(whatever, display("{message}"))]
+    Whatever {
+        message: String,
+        #[snafu(source(from(Box<dyn std::error::Error>, Some)))]
+        source: Option<Box<dyn std::error::Error>>,
+        backtrace: snafu::Backtrace,
+    },
+}
@@ -0,0 +1,312 @@
+use std::{collections::HashSet, fmt, io};
+
+use chrono::{Datelike, NaiveDate};
+use garde::Unvalidated;
+use serde::{Deserialize, Serialize};
+use snafu::prelude::*;
+
+use super::{SceneList, SceneResult};
+use crate::{
+    dif::{self, FrameMetadata, PackPosition, RecordingTimestamp},
+    file::{FrameReader, FrameWriter, System, ValidInfoMethods},
+    pack::{self, TimeValueWithOptionalFrame, TimeValueWithRequiredFrame},
+};
+
+#[cfg(test)]
+mod tests;
+
+/// The recording date and time packs of a frame.
+pub type RecordingDateTime = (pack::RecordingDate, pack::RecordingTime);
+
+/// Whether both packs hold a value that can be counted from.
+fn is_complete((date, time): &RecordingDateTime) -> bool {
+    date.date.is_some() && time.time.is_some()
+}
+
+/// Time of day of a recording time as a timecode.  Times without a frame number are counted as
+/// frame zero, without drop frame, so that every frame of a second has room to be counted.
+fn time_of_day(time: &TimeValueWithOptionalFrame) -> TimeValueWithRequiredFrame {
+    TimeValueWithRequiredFrame {
+        hour: time.hour,
+        minute: time.minute,
+        second: time.second,
+        drop_frame: time.drop_frame && time.frame.is_some(),
+        frame: time.frame.unwrap_or(0),
+    }
+}
+
+/// Number of frames per day used to count the date and time of the packs.
+fn frames_per_day(date_time: &RecordingDateTime, system: System) -> i64 {
+    let time = time_of_day(&date_time.1.time.unwrap());
+    i64::from(TimeValueWithRequiredFrame::frames_per_day(system, time.drop_frame))
+}
+
+/// Count the frames from the start of the calendar up to the date and time of the packs.
+fn to_frame_count(date_time: &RecordingDateTime, system: System) -> i64 {
+    let days = i64::from(date_time.0.date.unwrap().num_days_from_ce());
+    let time = time_of_day(&date_time.1.time.unwrap());
+    days * frames_per_day(date_time, system) + i64::from(time.to_frame_index(system))
+}
+
+/// Convert a count of frames back into packs, copying every other field from `template`.
+fn from_frame_count(
+    template: &RecordingDateTime,
+    frame_count: i64,
+    system: System,
+) -> RecordingDateTime {
+    let (template_date, template_time) = template;
+    let template_value = template_time.time.unwrap();
+    let per_day = frames_per_day(template, system);
+    let date = NaiveDate::from_num_days_from_ce_opt(
+        i32::try_from(frame_count.div_euclid(per_day)).unwrap(),
+    )
+    .unwrap();
+    let time = TimeValueWithRequiredFrame::from_frame_index(
+        u32::try_from(frame_count.rem_euclid(per_day)).unwrap(),
+        system,
+        time_of_day(&template_value).drop_frame,
+    );
+    (
+        pack::RecordingDate {
+            date: Some(date),
+            weekday: template_date.weekday.map(|_| date.weekday()),
+            ..*template_date
+        },
+        pack::RecordingTime {
+            time: Some(TimeValueWithOptionalFrame {
+                hour: time.hour,
+                minute: time.minute,
+                second: time.second,
+                drop_frame: template_value.drop_frame,
+                frame: template_value.frame.map(|_| time.frame),
+            }),
+            ..*template_time
+        },
+    )
+}
+
+/// Choose new recording date and time packs for consecutive frames of a file.
+///
+/// The `date_times` hold the consensus VAUX recording date and time packs of each frame, or
+/// [`None`] if either pack is missing or invalid.  A frame is damaged if it has no complete date
+/// and time, and is then rebuilt by counting frames at the nominal frame rate of the system from
+/// the nearest frame of the same scene that has one.  New packs are returned for each damaged
+/// frame that was rebuilt, or [`None`] if the frame should be left as it is.  Frames are never
+/// counted from another scene, and scenes without any complete date and time are left alone.
+///
+/// Most camcorders don't record a frame number, so the recorded time only changes once per
+/// second.  Such times are counted from the first frame that showed the time before the damage,
+/// which is the closest known frame to the start of that second, and are never counted past the
+/// time shown after the damage.  Frames before the first complete date and time of a scene are
+/// counted backwards from the last frame that showed that time.  Fields other than the date and
+/// time, such as the time zone, are copied from the frame that was counted from.
+pub fn repair_date_times(
+    date_times: &[Option<RecordingDateTime>],
+    scenes: &SceneList,
+    system: System,
+) -> Vec<Option<RecordingDateTime>> {
+    let mut new_date_times = vec![None; date_times.len()];
+    let rate = i64::from(pack::timecode_frame_rate(system));
+    let complete = |i: usize| date_times[i].filter(is_complete);
+    let has_frame = |date_time: &RecordingDateTime| date_time.1.time.unwrap().frame.is_some();
+
+    for scene in &scenes.scenes {
+        let start = usize::try_from(scene.frame_index).unwrap().min(date_times.len());
+        let end =
+            usize::try_from(scene.frame_index + scene.frame_count).unwrap().min(date_times.len());
+        let good: Vec<_> = (start..end).filter(|i| complete(*i).is_some()).collect();
+        for i in (start..end).filter(|i| complete(*i).is_none()) {
+            let next_good = good.partition_point(|g| *g < i);
+            let previous = next_good.checked_sub(1).map(|g| good[g]);
+            let next = good.get(next_good).copied();
+            let new = match (previous, next) {
+                (Some(p), _) => {
+                    let anchor = complete(p).unwrap();
+                    let mut first = p;
+                    while !has_frame(&anchor)
+                        && first > start
+                        && date_times[first - 1] == Some(anchor)
+                    {
+                        first -= 1;
+                    }
+                    let mut count =
+                        to_frame_count(&anchor, system) + i64::try_from(i - first).unwrap();
+                    if let Some(n) = next {
+                        count = count.min(to_frame_count(&complete(n).unwrap(), system));
+                    }
+                    from_frame_count(&anchor, count, system)
+                }
+                (None, Some(n)) => {
+                    let anchor = complete(n).unwrap();
+                    let mut last = n;
+                    let mut count = to_frame_count(&anchor, system);
+                    if !has_frame(&anchor) {
+                        while last + 1 < end && date_times[last + 1] == Some(anchor) {
+                            last += 1;
+                        }
+                        count += rate - 1;
+                    }
+                    count -= i64::try_from(last - i).unwrap();
+                    from_frame_count(&anchor, count, system)
+                }
+                (None, None) => continue,
+            };
+            new_date_times[i] = (Some(new) != date_times[i]).then_some(new);
+        }
+    }
+    new_date_times
+}
+
+/// Returns the positions of the subcode and VAUX packs of the frame that hold a VAUX recording
+/// date or time, including packs that are invalid.
+pub fn recording_date_time_positions(frame: &dif::Frame) -> Vec<(PackPosition, pack::Type)> {
+    frame
+        .subcode_and_vaux_packs()
+        .into_iter()
+        .map(|(position, pack)| (position, pack.pack_type()))
+        .filter(|(_, pack_type)| {
+            matches!(pack_type, pack::Type::VAUXRecordingDate | pack::Type::VAUXRecordingTime)
+        })
+        .collect()
+}
+
+/// Replace every VAUX recording date and time pack in the subcode and VAUX sections of the
+/// frame.
+///
+/// Besides the packs that already hold a recording date or time, the packs are also written to
+/// the given `positions`, unless they hold a valid pack of another type.  This restores the
+/// packs in places where they were lost to a dropout.  The positions are typically gathered from
+/// undamaged frames with [`recording_date_time_positions`].
+pub fn write_recording_date_time(
+    frame: &mut dif::Frame,
+    (date, time): &RecordingDateTime,
+    positions: &[(PackPosition, pack::Type)],
+) -> SceneResult<()> {
+    let ctx = frame.pack_context();
+    let date = Unvalidated::new(*date)
+        .validate_with(&ctx)
+        .whatever_context("New recording date is not valid")?;
+    let time = Unvalidated::new(*time)
+        .validate_with(&ctx)
+        .whatever_context("New recording time is not valid")?;
+    let date = pack::Pack::VAUXRecordingDate(date.into());
+    let time = pack::Pack::VAUXRecordingTime(time.into());
+    for (position, existing) in frame.subcode_and_vaux_packs() {
+        let new_pack = match existing {
+            pack::Pack::VAUXRecordingDate(_)
+            | pack::Pack::Invalid(pack::Type::VAUXRecordingDate, _) => &date,
+            pack::Pack::VAUXRecordingTime(_)
+            | pack::Pack::Invalid(pack::Type::VAUXRecordingTime, _) => &time,
+            pack::Pack::NoInfo(_) | pack::Pack::Invalid(..) => {
+                match positions.iter().find(|(p, _)| *p == position) {
+                    Some((_, pack::Type::VAUXRecordingDate)) => &date,
+                    Some((_, pack::Type::VAUXRecordingTime)) => &time,
+                    _ => continue,
+                }
+            }
+            _ => continue,
+        };
+        frame.set_pack(&position, new_pack);
+    }
+    Ok(())
+}
+
+/// A frame whose recording date and time was rebuilt.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct RepairedDateTime {
+    /// Index of the frame.
+    pub frame_index: u64,
+
+    /// What was left of the recording date and time before the repair, if anything.
+    pub previous: Option<RecordingTimestamp>,
+
+    /// The new recording date and time.
+    pub repaired: RecordingTimestamp,
+}
+
+/// Report of the frames whose recording date and time was rebuilt by [`repair_file_date_times`].
+///
+/// The [`fmt::Display`] implementation prints one line for each frame, such as
+/// `frame 120, recording date and time 2024-07-08 rewritten as 2024-07-08 19:55:58`.
+#[derive(Debug, PartialEq, Eq, Clone, Default, Serialize, Deserialize)]
+pub struct DateTimeRepairReport {
+    /// Number of frames in the file.
+    pub frame_count: u64,
+
+    /// Frames that were rewritten, in order.
+    pub repaired: Vec<RepairedDateTime>,
+}
+
+impl fmt::Display for DateTimeRepairReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for frame in &self.repaired {
+            write!(f, "frame {}, recording date and time ", frame.frame_index)?;
+            match frame.previous {
+                Some(previous) => write!(f, "{previous}")?,
+                None => write!(f, "missing,")?,
+            }
+            writeln!(f, " rewritten as {}", frame.repaired)?;
+        }
+        Ok(())
+    }
+}
+
+/// Rebuild the VAUX recording date and time of frames damaged by dropouts; see
+/// [`repair_date_times`].
+///
+/// The file is read twice: once to gather the recording dates and times, and once to rewrite
+/// them.  Every frame is written to `writer`, whether or not it was changed.  New packs are
+/// written to every subcode and VAUX position that held a VAUX recording date or time in any
+/// frame of the file; see [`write_recording_date_time`].  The `scenes` are typically found with
+/// [`super::detect_scenes`].
+pub fn repair_file_date_times<R: io::Read + io::Seek, W: io::Write>(
+    reader: &mut FrameReader<R>,
+    writer: &mut FrameWriter<W>,
+    scenes: &SceneList,
+) -> SceneResult<DateTimeRepairReport> {
+    let system = reader.file_info().system();
+    reader.seek_frame(0).whatever_context("Could not seek to the start of the file")?;
+    let mut date_times = Vec::new();
+    let mut previous = Vec::new();
+    let mut positions = HashSet::new();
+    while let Some(frame) = reader.read_frame().whatever_context("Could not read the file")? {
+        let metadata = FrameMetadata::from_frame(&frame);
+        let date = match metadata.pack(pack::Type::VAUXRecordingDate) {
+            Some(pack::Pack::VAUXRecordingDate(date)) => Some(*date.0),
+            _ => None,
+        };
+        let time = match metadata.pack(pack::Type::VAUXRecordingTime) {
+            Some(pack::Pack::VAUXRecordingTime(time)) => Some(*time.0),
+            _ => None,
+        };
+        date_times.push(date.zip(time));
+        previous.push(RecordingTimestamp::from_packs(date.as_ref(), time.as_ref(), system));
+        positions.extend(recording_date_time_positions(&frame));
+    }
+    let positions: Vec<_> = positions.into_iter().collect();
+    let new_date_times = repair_date_times(&date_times, scenes, system);
+
+    reader.seek_frame(0).whatever_context("Could not seek to the start of the file")?;
+    let mut report = DateTimeRepairReport {
+        frame_count: u64::try_from(date_times.len()).unwrap(),
+        repaired: Vec::new(),
+    };
+    for (frame_index, (new_date_time, previous)) in (0..).zip(new_date_times.iter().zip(previous)) {
+        let mut frame = reader
+            .read_frame()
+            .with_whatever_context(|_| format!("Could not read frame {frame_index}"))?
+            .with_whatever_context(|| format!("File ended before frame {frame_index}"))?;
+        if let Some(new_date_time @ (date, time)) = new_date_time {
+            write_recording_date_time(&mut frame, new_date_time, &positions)?;
+            report.repaired.push(RepairedDateTime {
+                frame_index,
+                previous,
+                repaired: RecordingTimestamp::from_packs(Some(date), Some(time), system).unwrap(),
+            });
+        }
+        writer
+            .write_frame(&frame)
+            .with_whatever_context(|_| format!("Could not write frame {frame_index}"))?;
+    }
+    Ok(report)
+}
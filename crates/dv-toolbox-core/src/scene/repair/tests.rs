@@ -0,0 +1,179 @@
+use std::io::Cursor;
+
+use arbitrary_int::u2;
+use chrono::NaiveDateTime;
+use googletest::prelude::*;
+use rstest::rstest;
+
+use super::*;
+use crate::{
+    file::testutil::{frame_reader, read_test_frames, SONY_GOOD_QUALITY},
+    scene::{Scene, SceneConfidence},
+};
+
+/// Recording date and time packs for the given day of July 2024, with a frame number if given.
+fn dt(day: u32, hour: u8, minute: u8, second: u8, frame: Option<u8>) -> Option<RecordingDateTime> {
+    let date = NaiveDate::from_ymd_opt(2024, 7, day).unwrap();
+    Some((
+        pack::RecordingDate {
+            date: Some(date),
+            weekday: Some(date.weekday()),
+            timezone: None,
+            daylight_saving_time: None,
+            reserved: u2::new(0x3),
+        },
+        pack::RecordingTime {
+            time: Some(TimeValueWithOptionalFrame {
+                hour,
+                minute,
+                second,
+                drop_frame: false,
+                frame,
+            }),
+            color_frame: pack::ColorFrame::Unsynchronized,
+            polarity_correction: pack::PolarityCorrection::Even,
+            binary_group_flag: pack::BinaryGroupFlag::TimeUnspecifiedGroupUnspecified,
+        },
+    ))
+}
+
+/// Recording date and time without a frame number, on the 8th of July 2024 at 19:55.
+fn s(second: u8) -> Option<RecordingDateTime> {
+    dt(8, 19, 55, second, None)
+}
+
+/// Recording date and time with a frame number, on the 8th of July 2024 at 19:55.
+fn f(second: u8, frame: u8) -> Option<RecordingDateTime> {
+    dt(8, 19, 55, second, Some(frame))
+}
+
+fn scenes(ranges: &[(u64, u64)]) -> SceneList {
+    SceneList {
+        scenes: ranges
+            .iter()
+            .map(|(frame_index, frame_count)| Scene {
+                frame_index: *frame_index,
+                frame_count: *frame_count,
+                start: None,
+                end: None,
+                reasons: Vec::new(),
+                confidence: SceneConfidence::Low,
+            })
+            .collect(),
+    }
+}
+
+#[googletest::test]
+#[rstest]
+#[case::frames(
+    vec![f(58, 28), f(58, 29), None, None, f(59, 2)],
+    &[(0, 5)],
+    vec![None, None, f(59, 0), f(59, 1), None]
+)]
+#[case::midnight(
+    vec![dt(8, 23, 59, 59, Some(29)), None],
+    &[(0, 2)],
+    vec![None, dt(9, 0, 0, 0, Some(0))]
+)]
+#[case::counted_from_start_of_second(
+    // The damaged frames are counted from frame 1, where the time changed to 58 seconds, and so
+    // frame 31 is the first frame to reach 59 seconds.
+    [vec![s(57), s(58), s(58)], vec![None; 30]].concat(),
+    &[(0, 33)],
+    [vec![None; 3], vec![s(58); 28], vec![s(59); 2]].concat()
+)]
+#[case::before_first_good_frame(
+    vec![None, None, f(0, 2)],
+    &[(0, 3)],
+    vec![f(0, 0), f(0, 1), None]
+)]
+#[case::before_first_good_frame_without_frame_number(
+    // The damaged frames are counted backwards from frame 31, assuming it was the last frame of
+    // its second.
+    [vec![None; 30], vec![s(10), s(10)]].concat(),
+    &[(0, 32)],
+    [vec![s(9); 2], vec![s(10); 28], vec![None; 2]].concat()
+)]
+#[case::scene_break(
+    vec![f(58, 0), None, None, f(10, 5)],
+    &[(0, 2), (2, 2)],
+    vec![None, f(58, 1), f(10, 4), None]
+)]
+#[case::no_good_frames(vec![None, s(58)], &[(0, 1), (1, 1)], vec![None, None])]
+#[case::incomplete(
+    vec![s(58), Some((s(0).unwrap().0, pack::RecordingTime { time: None, ..s(0).unwrap().1 }))],
+    &[(0, 2)],
+    vec![None, s(58)]
+)]
+fn test_repair_date_times(
+    #[case] date_times: Vec<Option<RecordingDateTime>>,
+    #[case] ranges: &[(u64, u64)],
+    #[case] expected: Vec<Option<RecordingDateTime>>,
+) {
+    expect_that!(repair_date_times(&date_times, &scenes(ranges), System::Sys525_60), eq(&expected));
+}
+
+#[googletest::test]
+fn test_repair_date_times_limited_by_next_frame() {
+    // The time is never counted past the time after the damage, even if more frames were lost
+    // than there are frames in a second.
+    let mut date_times = vec![None; 67];
+    date_times[0] = s(58);
+    date_times[66] = s(59);
+    let repaired = repair_date_times(&date_times, &scenes(&[(0, 67)]), System::Sys525_60);
+    expect_that!(repaired[29], eq(s(58)));
+    expect_that!(repaired[30], eq(s(59)));
+    expect_that!(repaired[65], eq(s(59)));
+}
+
+#[googletest::test]
+fn test_repair_date_times_copies_flags() {
+    // Without a weekday and with drop frame, unlike the other tests.
+    let with_flags = |date_time: Option<RecordingDateTime>| {
+        let (mut date, mut time) = date_time.unwrap();
+        date.weekday = None;
+        time.time.as_mut().unwrap().drop_frame = true;
+        Some((date, time))
+    };
+    expect_that!(
+        repair_date_times(&[with_flags(f(58, 0)), None], &scenes(&[(0, 2)]), System::Sys525_60),
+        eq(&vec![None, with_flags(f(58, 1))])
+    );
+}
+
+#[googletest::test]
+fn test_repair_file_date_times() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let mut damaged = frames.clone();
+    let no_info = pack::Pack::from_raw(&[0xFF; 5], &damaged[2].pack_context()).0;
+    for (position, _) in recording_date_time_positions(&frames[0]) {
+        damaged[2].set_pack(&position, &no_info);
+    }
+
+    // Rebuilding the damaged frame restores the original file.
+    let mut writer = FrameWriter::new(Cursor::new(Vec::new()), *SONY_GOOD_QUALITY);
+    let report = repair_file_date_times(
+        &mut frame_reader(&damaged, &SONY_GOOD_QUALITY),
+        &mut writer,
+        &scenes(&[(0, 5)]),
+    )
+    .unwrap();
+    let restored = frame_reader(&frames, &SONY_GOOD_QUALITY).into_inner().into_inner();
+    expect_that!(writer.into_inner().into_inner(), eq(&restored));
+    let repaired = NaiveDateTime::parse_from_str("2024-07-08 19:55:58", "%Y-%m-%d %H:%M:%S");
+    expect_that!(
+        report,
+        eq(&DateTimeRepairReport {
+            frame_count: 5,
+            repaired: vec![RepairedDateTime {
+                frame_index: 2,
+                previous: None,
+                repaired: RecordingTimestamp::NoTimeZone(repaired.unwrap()),
+            }],
+        })
+    );
+    expect_that!(
+        report.to_string(),
+        eq("frame 2, recording date and time missing, rewritten as 2024-07-08 19:55:58\n")
+    );
+}
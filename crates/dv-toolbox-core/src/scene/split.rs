@@ -0,0 +1,304 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Write as _},
+    fs, io,
+    path::Path,
+};
+
+use chrono::{
+    format::{Item, StrftimeItems},
+    NaiveDateTime,
+};
+use serde::{Deserialize, Serialize};
+use snafu::prelude::*;
+
+use super::{Scene, SceneList, SceneResult};
+use crate::{
+    file::{FrameReader, FrameWriter},
+    pack::TimeValueWithRequiredFrame,
+    timecode::FrameTimecode,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Format of the `{timestamp}` placeholder when no format is given.
+const DEFAULT_TIMESTAMP_FORMAT: &str = "%Y.%m.%d_%H-%M-%S";
+
+/// Characters that can't be part of a file name, because they separate path components on some
+/// operating system.
+const PATH_SEPARATORS: [char; 3] = ['/', '\\', ':'];
+
+/// A part of a [`SceneFileNameTemplate`].
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+enum TemplatePart {
+    Literal(String),
+    Scene { width: usize },
+    Frame,
+    Timestamp { format: String },
+    Timecode,
+}
+
+/// Template for the names of the files that scenes are written to.
+///
+/// The template is copied to the file name, with these placeholders replaced:
+/// - `{scene}`: one-based number of the scene in the [`SceneList`].  A minimum number of digits
+///   can be given, such as `{scene:03}`.
+/// - `{frame}`: zero-based index of the first frame of the scene in the source file.
+/// - `{timestamp}`: recording date and time of the first frame of the scene that has one, as
+///   local time.  A [`chrono::format::strftime`] format can be given, such as
+///   `{timestamp:%Y-%m-%d %H.%M.%S}`; the default format is `%Y.%m.%d_%H-%M-%S`, like dvgrab.
+/// - `{timecode}`: title timecode of the first frame of the scene, such as `00-01-02-03`.
+///
+/// Use `{{` and `}}` for literal braces.  A scene can't be named if the template needs a
+/// timestamp or timecode that the scene doesn't have.
+///
+/// The names are always plain file names: the template can't contain path separators (`/`, `\`
+/// or `:`), including in timestamp formats such as `%D` or `%T`, and a name can't be empty, `.` or
+/// `..`.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct SceneFileNameTemplate {
+    parts: Vec<TemplatePart>,
+}
+
+impl SceneFileNameTemplate {
+    /// Parses a template, such as `tape1-{timestamp}.dv`.
+    pub fn new(template: &str) -> SceneResult<Self> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.as_str().starts_with('{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.as_str().starts_with('}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let rest = chars.as_str();
+                    let end = rest.find('}').with_whatever_context(|| {
+                        format!("Placeholder in file name template {template:?} is not closed")
+                    })?;
+                    if !literal.is_empty() {
+                        parts.push(TemplatePart::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(Self::parse_placeholder(&rest[..end])?);
+                    chars = rest[end + 1..].chars();
+                }
+                '}' => whatever!("Unexpected }} in file name template {template:?}"),
+                _ => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(TemplatePart::Literal(literal));
+        }
+        ensure_whatever!(
+            !parts.iter().any(|part| matches!(
+                part,
+                TemplatePart::Literal(literal) if literal.contains(PATH_SEPARATORS)
+            )),
+            "File name template {template:?} contains a path separator"
+        );
+        Ok(Self { parts })
+    }
+
+    fn parse_placeholder(placeholder: &str) -> SceneResult<TemplatePart> {
+        let (name, spec) = match placeholder.split_once(':') {
+            Some((name, spec)) => (name, Some(spec)),
+            None => (placeholder, None),
+        };
+        Ok(match (name, spec) {
+            ("scene", None) => TemplatePart::Scene { width: 0 },
+            ("scene", Some(spec)) => TemplatePart::Scene {
+                width: spec.parse().ok().with_whatever_context(|| {
+                    format!("Scene number width {spec:?} is not a number")
+                })?,
+            },
+            ("frame", None) => TemplatePart::Frame,
+            ("timestamp", spec) => {
+                let format = spec.unwrap_or(DEFAULT_TIMESTAMP_FORMAT);
+                ensure_whatever!(
+                    !StrftimeItems::new(format).any(|item| item == Item::Error),
+                    "Timestamp format {format:?} is not valid"
+                );
+                // Formats such as %D and %T add separators that aren't in the format itself.
+                let sample = NaiveDateTime::default().format(format).to_string();
+                ensure_whatever!(
+                    !sample.contains(PATH_SEPARATORS),
+                    "Timestamp format {format:?} produces path separators"
+                );
+                TemplatePart::Timestamp { format: format.to_string() }
+            }
+            ("timecode", None) => TemplatePart::Timecode,
+            _ => whatever!("Unknown file name placeholder {{{placeholder}}}"),
+        })
+    }
+
+    /// Returns the file name of a scene.
+    ///
+    /// `scene_number` is the one-based number of the scene, and `timecode` is the title timecode
+    /// of its first frame, if it has one.
+    pub fn file_name(
+        &self,
+        scene_number: usize,
+        scene: &Scene,
+        timecode: Option<TimeValueWithRequiredFrame>,
+    ) -> SceneResult<String> {
+        let mut file_name = String::new();
+        for part in &self.parts {
+            match part {
+                TemplatePart::Literal(literal) => file_name.push_str(literal),
+                TemplatePart::Scene { width } => {
+                    write!(file_name, "{scene_number:0width$}").unwrap();
+                }
+                TemplatePart::Frame => write!(file_name, "{}", scene.frame_index).unwrap(),
+                TemplatePart::Timestamp { format } => {
+                    let timestamp = scene
+                        .start
+                        .and_then(|start| start.naive_date_time())
+                        .with_whatever_context(|| {
+                            format!("Scene {scene_number} has no recording date and time")
+                        })?;
+                    write!(file_name, "{}", timestamp.format(format)).ok().with_whatever_context(
+                        || format!("Could not format timestamp with {format:?}"),
+                    )?;
+                }
+                TemplatePart::Timecode => {
+                    let timecode = timecode.with_whatever_context(|| {
+                        format!("Scene {scene_number} has no title timecode on its first frame")
+                    })?;
+                    write!(
+                        file_name,
+                        "{:02}-{:02}-{:02}-{:02}",
+                        timecode.hour, timecode.minute, timecode.second, timecode.frame
+                    )
+                    .unwrap();
+                }
+            }
+        }
+        ensure_whatever!(
+            !matches!(file_name.as_str(), "" | "." | "..") && !file_name.contains(PATH_SEPARATORS),
+            "Scene {scene_number} would be written to {file_name:?}, which is not a file name"
+        );
+        Ok(file_name)
+    }
+}
+
+/// A file that a scene was written to by [`split_scenes`].
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+pub struct SceneFile {
+    /// Name of the file, from the [`SceneFileNameTemplate`].
+    pub file_name: String,
+
+    /// Index of the first frame of the scene in the source file.
+    pub frame_index: u64,
+
+    /// Number of frames written to the file.
+    pub frame_count: u64,
+}
+
+/// Report of the files written by [`split_scenes`].
+///
+/// The [`fmt::Display`] implementation prints one line for each file, such as
+/// `frames 300-899 written to 2024.07.08_19-55-58.dv`.
+#[derive(Debug, PartialEq, Eq, Clone, Default, Serialize, Deserialize)]
+pub struct SceneSplitReport {
+    /// The files, in the order of the scenes.
+    pub files: Vec<SceneFile>,
+}
+
+impl fmt::Display for SceneSplitReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for file in &self.files {
+            match file.frame_count {
+                1 => write!(f, "frame {}", file.frame_index)?,
+                _ => write!(
+                    f,
+                    "frames {}-{}",
+                    file.frame_index,
+                    file.frame_index + file.frame_count - 1
+                )?,
+            }
+            writeln!(f, " written to {}", file.file_name)?;
+        }
+        Ok(())
+    }
+}
+
+/// Write each scene of a file to its own bare DV file.
+///
+/// The frames of each scene are copied from `reader` exactly as they were read.  `create` is
+/// called with the file name of each scene to open the file to write it to.  All file names are
+/// worked out before any file is created, so that a scene that can't be named, or two scenes with
+/// the same name, are reported without writing anything.
+pub fn split_scenes<R: io::Read + io::Seek, W: io::Write>(
+    reader: &mut FrameReader<R>,
+    scenes: &SceneList,
+    template: &SceneFileNameTemplate,
+    mut create: impl FnMut(&str) -> io::Result<W>,
+) -> SceneResult<SceneSplitReport> {
+    let mut report = SceneSplitReport::default();
+    let mut scene_numbers = HashMap::new();
+    for (scene_number, scene) in (1..).zip(&scenes.scenes) {
+        reader
+            .seek_frame(scene.frame_index)
+            .with_whatever_context(|_| format!("Could not seek to scene {scene_number}"))?;
+        let timecode = match reader
+            .read_frame()
+            .with_whatever_context(|_| format!("Could not read scene {scene_number}"))?
+        {
+            Some(frame) => match FrameTimecode::from_frame(&frame) {
+                FrameTimecode::Valid(timecode) => Some(timecode),
+                FrameTimecode::Invalid | FrameTimecode::Missing => None,
+            },
+            None => None,
+        };
+        let file_name = template.file_name(scene_number, scene, timecode)?;
+        if let Some(other) = scene_numbers.insert(file_name.clone(), scene_number) {
+            whatever!("Scenes {other} and {scene_number} would both be written to {file_name}");
+        }
+        report.files.push(SceneFile {
+            file_name,
+            frame_index: scene.frame_index,
+            frame_count: scene.frame_count,
+        });
+    }
+
+    for file in &report.files {
+        let output = create(&file.file_name)
+            .with_whatever_context(|_| format!("Could not create {}", file.file_name))?;
+        let mut writer = FrameWriter::new(output, *reader.file_info());
+        reader
+            .seek_frame(file.frame_index)
+            .with_whatever_context(|_| format!("Could not seek to frame {}", file.frame_index))?;
+        for frame_index in file.frame_index..file.frame_index + file.frame_count {
+            let frame = reader
+                .read_frame()
+                .with_whatever_context(|_| format!("Could not read frame {frame_index}"))?
+                .with_whatever_context(|| format!("File ended before frame {frame_index}"))?;
+            writer
+                .write_frame(&frame)
+                .with_whatever_context(|_| format!("Could not write {}", file.file_name))?;
+        }
+        writer.flush().with_whatever_context(|_| format!("Could not write {}", file.file_name))?;
+    }
+    Ok(report)
+}
+
+/// Write each scene of a file to its own bare DV file in a directory; see [`split_scenes`].
+///
+/// Existing files are never overwritten: the split stops with an error if a file with the name
+/// of a scene already exists.
+pub fn split_scenes_to_directory<R: io::Read + io::Seek>(
+    reader: &mut FrameReader<R>,
+    scenes: &SceneList,
+    template: &SceneFileNameTemplate,
+    directory: &Path,
+) -> SceneResult<SceneSplitReport> {
+    split_scenes(reader, scenes, template, |file_name| {
+        fs::File::create_new(directory.join(file_name)).map(io::BufWriter::new)
+    })
+}
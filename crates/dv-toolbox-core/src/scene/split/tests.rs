@@ -0,0 +1,185 @@
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+use chrono::NaiveDate;
+use googletest::prelude::*;
+use rstest::rstest;
+
+use super::*;
+use crate::{
+    dif::RecordingTimestamp,
+    file::testutil::{frame_reader, read_test_frames, SONY_GOOD_QUALITY},
+    scene::{SceneBoundaryReason, SceneConfidence},
+    testutil::*,
+};
+
+fn scene(frame_index: u64, frame_count: u64, start: Option<RecordingTimestamp>) -> Scene {
+    Scene {
+        frame_index,
+        frame_count,
+        start,
+        end: start,
+        reasons: vec![SceneBoundaryReason::VideoStartPoint],
+        confidence: SceneConfidence::Low,
+    }
+}
+
+fn sony_start() -> Option<RecordingTimestamp> {
+    Some(RecordingTimestamp::NoTimeZone(
+        NaiveDate::from_ymd_opt(2024, 7, 8).unwrap().and_hms_opt(19, 55, 58).unwrap(),
+    ))
+}
+
+#[googletest::test]
+#[rstest]
+#[case::literal("scene.dv", "scene.dv")]
+#[case::scene("scene{scene}.dv", "scene12.dv")]
+#[case::scene_width("scene{scene:04}.dv", "scene0012.dv")]
+#[case::frame("{frame}.dv", "300.dv")]
+#[case::timestamp("tape-{timestamp}.dv", "tape-2024.07.08_19-55-58.dv")]
+#[case::timestamp_format("{timestamp:%Y%m%d %H%M}.dv", "20240708 1955.dv")]
+#[case::timecode("{timecode}.dv", "00-00-02-17.dv")]
+#[case::braces("{{{scene}}}.dv", "{12}.dv")]
+fn test_file_name(#[case] template: &str, #[case] expected: &str) {
+    let template = SceneFileNameTemplate::new(template).unwrap();
+    let timecode =
+        TimeValueWithRequiredFrame { hour: 0, minute: 0, second: 2, drop_frame: true, frame: 17 };
+    expect_that!(
+        template.file_name(12, &scene(300, 600, sony_start()), Some(timecode)),
+        ok(eq(expected))
+    );
+}
+
+#[googletest::test]
+#[rstest]
+#[case::not_closed(
+    "scene{scene.dv",
+    "Placeholder in file name template \"scene{scene.dv\" is not \
+    closed"
+)]
+#[case::unexpected_brace("scene}.dv", "Unexpected } in file name template \"scene}.dv\"")]
+#[case::unknown("{take}.dv", "Unknown file name placeholder {take}")]
+#[case::width("{scene:x}.dv", "Scene number width \"x\" is not a number")]
+#[case::timestamp_format("{timestamp:%Q}.dv", "Timestamp format \"%Q\" is not valid")]
+#[case::slash("../{scene}.dv", "File name template \"../{scene}.dv\" contains a path separator")]
+#[case::backslash(
+    "tape\\{scene}.dv",
+    "File name template \"tape\\\\{scene}.dv\" contains a path separator"
+)]
+#[case::timestamp_date("{timestamp:%D}.dv", "Timestamp format \"%D\" produces path separators")]
+#[case::timestamp_time("{timestamp:%T}.dv", "Timestamp format \"%T\" produces path separators")]
+fn test_template_errors(#[case] template: &str, #[case] expected: &str) {
+    expect_that!(SceneFileNameTemplate::new(template).unwrap_err().to_string(), eq(expected));
+}
+
+#[googletest::test]
+fn test_file_name_errors() {
+    let template = SceneFileNameTemplate::new("{timestamp}-{timecode}.dv").unwrap();
+    expect_that!(
+        template.file_name(2, &scene(0, 1, None), None).unwrap_err().to_string(),
+        eq("Scene 2 has no recording date and time")
+    );
+    expect_that!(
+        template.file_name(2, &scene(0, 1, sony_start()), None).unwrap_err().to_string(),
+        eq("Scene 2 has no title timecode on its first frame")
+    );
+}
+
+#[googletest::test]
+#[rstest]
+#[case::empty("{timestamp:}")]
+#[case::current("{timestamp:.}")]
+#[case::parent("..")]
+fn test_file_name_not_a_file_name(#[case] template: &str) {
+    let template = SceneFileNameTemplate::new(template).unwrap();
+    let file_name = template.file_name(2, &scene(0, 1, sony_start()), None);
+    expect_that!(file_name.unwrap_err().to_string(), ends_with("which is not a file name"));
+}
+
+/// Writer that stores what was written under the name of the file, so that it can be checked
+/// after the writer is dropped.
+struct SharedFile {
+    files: Rc<RefCell<BTreeMap<String, Vec<u8>>>>,
+    file_name: String,
+}
+
+impl io::Write for SharedFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.files.borrow_mut().entry(self.file_name.clone()).or_default().extend(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn split(
+    scenes: &[Scene],
+    template: &str,
+) -> (SceneResult<SceneSplitReport>, BTreeMap<String, Vec<u8>>) {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let mut reader = frame_reader(&frames, &SONY_GOOD_QUALITY);
+    let files = Rc::new(RefCell::new(BTreeMap::new()));
+    let result = split_scenes(
+        &mut reader,
+        &SceneList { scenes: scenes.to_vec() },
+        &SceneFileNameTemplate::new(template).unwrap(),
+        |file_name| Ok(SharedFile { files: Rc::clone(&files), file_name: file_name.to_string() }),
+    );
+    let files = files.borrow().clone();
+    (result, files)
+}
+
+#[googletest::test]
+fn test_split_scenes() {
+    let original = std::fs::read(test_resource("dv_multiframe/sony_good_quality.dv")).unwrap();
+    let frame_size = original.len() / 5;
+
+    let (report, files) =
+        split(&[scene(0, 2, sony_start()), scene(2, 3, sony_start())], "{scene}-{timecode}.dv");
+    let report = report.unwrap();
+    expect_that!(
+        report.files,
+        elements_are![
+            eq(&SceneFile {
+                file_name: "1-00-00-02-17.dv".to_string(),
+                frame_index: 0,
+                frame_count: 2
+            }),
+            eq(&SceneFile {
+                file_name: "2-00-00-02-19.dv".to_string(),
+                frame_index: 2,
+                frame_count: 3
+            }),
+        ]
+    );
+    expect_that!(
+        report.to_string(),
+        eq("frames 0-1 written to 1-00-00-02-17.dv\nframes 2-4 written to 2-00-00-02-19.dv\n")
+    );
+    expect_that!(
+        files,
+        eq(&BTreeMap::from([
+            ("1-00-00-02-17.dv".to_string(), original[..2 * frame_size].to_vec()),
+            ("2-00-00-02-19.dv".to_string(), original[2 * frame_size..].to_vec()),
+        ]))
+    );
+}
+
+#[googletest::test]
+fn test_split_scenes_same_name() {
+    // Nothing is written if the scenes can't all be named.
+    let (report, files) =
+        split(&[scene(0, 2, sony_start()), scene(2, 3, sony_start())], "{timestamp}.dv");
+    expect_that!(
+        report.unwrap_err().to_string(),
+        eq("Scenes 1 and 2 would both be written to 2024.07.08_19-55-58.dv")
+    );
+    expect_that!(files, empty());
+}
+
+#[googletest::test]
+fn test_split_scenes_past_end() {
+    let (report, _) = split(&[scene(3, 3, sony_start())], "{scene}.dv");
+    expect_that!(report.unwrap_err().to_string(), eq("File ended before frame 5"));
+}
@@ -0,0 +1,313 @@
+use std::{fmt, io};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    dif::{self, DissentReason, FrameMetadata},
+    file::{FrameReader, FrameResult, System, ValidInfoMethods},
+    pack::{self, TimeValueWithRequiredFrame},
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Title timecode of a single frame, as found by voting across all copies of the pack in the
+/// frame.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub enum FrameTimecode {
+    /// The frame has a valid title timecode.
+    Valid(TimeValueWithRequiredFrame),
+
+    /// The frame has copies of the title timecode pack, but none of them are valid.  This usually
+    /// indicates a dropout.
+    Invalid,
+
+    /// The frame has no title timecode pack at all.
+    Missing,
+}
+
+impl FrameTimecode {
+    /// Find the title timecode in the consensus metadata of a frame.
+    pub fn from_metadata(metadata: &FrameMetadata) -> Self {
+        match metadata.pack(pack::Type::TitleTimecode) {
+            Some(pack::Pack::TitleTimecode(title_timecode)) => {
+                Self::Valid(title_timecode.timecode.time)
+            }
+            _ if metadata.dissenting_packs.iter().any(|d| {
+                d.reason == DissentReason::Invalid
+                    && d.pack.pack_type() == pack::Type::TitleTimecode
+            }) =>
+            {
+                Self::Invalid
+            }
+            _ => Self::Missing,
+        }
+    }
+
+    /// Find the title timecode of a frame.
+    pub fn from_frame(frame: &dif::Frame) -> Self {
+        Self::from_metadata(&FrameMetadata::from_frame(frame))
+    }
+}
+
+/// How the title timecode changed between two frames, when it did not advance by the expected
+/// number of frames.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub enum DiscontinuityKind {
+    /// The timecode moved forward, but by a different number of frames than expected.
+    Jump,
+
+    /// The timecode stayed the same.
+    Repeat,
+
+    /// The timecode moved backwards.
+    Backwards,
+}
+
+/// A problem with the title timecode found by the [`TimecodeContinuityAnalyzer`].
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub enum TimecodeIssue {
+    /// The timecode did not advance by one frame per frame since the previous frame with a valid
+    /// timecode.
+    Discontinuity {
+        /// Index of the frame where the discontinuity was found.
+        frame_index: u64,
+
+        /// How the timecode changed.
+        kind: DiscontinuityKind,
+
+        /// Index of the previous frame with a valid timecode.  This is the frame right before
+        /// [`TimecodeIssue::Discontinuity::frame_index`], unless the timecode was missing or
+        /// invalid in between.
+        previous_frame_index: u64,
+
+        /// Timecode of the previous frame with a valid timecode.
+        previous: TimeValueWithRequiredFrame,
+
+        /// Timecode of the frame where the discontinuity was found.
+        timecode: TimeValueWithRequiredFrame,
+
+        /// Number of frames that the timecode moved by, minus the number of frames that it was
+        /// expected to move by.
+        offset: i64,
+    },
+
+    /// A run of consecutive frames that have no title timecode.
+    Missing {
+        /// Index of the first frame of the run.
+        frame_index: u64,
+
+        /// Number of frames in the run.
+        frame_count: u64,
+    },
+
+    /// A run of consecutive frames whose title timecode is invalid.
+    Invalid {
+        /// Index of the first frame of the run.
+        frame_index: u64,
+
+        /// Number of frames in the run.
+        frame_count: u64,
+    },
+
+    /// The drop frame flag of the timecode changed since the previous frame with a valid timecode.
+    DropFrameChange {
+        /// Index of the frame where the flag changed.
+        frame_index: u64,
+
+        /// The new value of [`TimeValueWithRequiredFrame::drop_frame`].
+        drop_frame: bool,
+    },
+}
+
+impl fmt::Display for TimecodeIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let frames = |frame_index: u64, frame_count: u64| match frame_count {
+            1 => format!("frame {frame_index}"),
+            _ => format!("frames {frame_index}-{}", frame_index + frame_count - 1),
+        };
+        match self {
+            Self::Discontinuity { frame_index, kind, previous, timecode, offset, .. } => match kind
+            {
+                DiscontinuityKind::Jump => write!(
+                    f,
+                    "frame {frame_index}, timecode jumps {offset:+} frames from {previous} \
+                        to {timecode}"
+                ),
+                DiscontinuityKind::Repeat => {
+                    write!(f, "frame {frame_index}, timecode {timecode} repeats")
+                }
+                DiscontinuityKind::Backwards => write!(
+                    f,
+                    "frame {frame_index}, timecode goes backwards from {previous} to \
+                        {timecode}"
+                ),
+            },
+            Self::Missing { frame_index, frame_count } => {
+                write!(f, "{}, timecode missing", frames(*frame_index, *frame_count))
+            }
+            Self::Invalid { frame_index, frame_count } => {
+                write!(f, "{}, timecode invalid", frames(*frame_index, *frame_count))
+            }
+            Self::DropFrameChange { frame_index, drop_frame } => write!(
+                f,
+                "frame {frame_index}, drop frame turned {}",
+                if *drop_frame { "on" } else { "off" }
+            ),
+        }
+    }
+}
+
+/// Report of the problems with the title timecode across a file.
+///
+/// The [`fmt::Display`] implementation prints one line for each issue, such as
+/// `frame 1234, timecode jumps +90 frames from 00:01:02;03 to 00:01:05;04`.
+#[derive(Debug, PartialEq, Eq, Clone, Default, Serialize, Deserialize)]
+pub struct TimecodeContinuityReport {
+    /// Number of frames that were analyzed.
+    pub frame_count: u64,
+
+    /// Issues that were found, in the order of the frames where they start.
+    pub issues: Vec<TimecodeIssue>,
+}
+
+impl fmt::Display for TimecodeContinuityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for issue in &self.issues {
+            writeln!(f, "{issue}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Walks the title timecode of consecutive frames, and builds a [`TimecodeContinuityReport`].
+///
+/// Each valid timecode is compared to the previous valid timecode: it is expected to have moved
+/// forward by the number of frames between them, wrapping around at 24 hours.  Frames with a
+/// missing or invalid timecode are therefore skipped over without causing a discontinuity if the
+/// timecode picks up where it is expected to afterwards.  When the drop frame flag changes, the
+/// frames between the two timecodes are counted using the new drop frame mode.
+#[derive(Debug, Default)]
+pub struct TimecodeContinuityAnalyzer {
+    report: TimecodeContinuityReport,
+
+    /// Index and timecode of the last frame with a valid timecode.
+    previous: Option<(u64, TimeValueWithRequiredFrame)>,
+
+    /// Run of frames with a missing or invalid timecode that is still being added to.
+    run: Option<TimecodeIssue>,
+}
+
+impl TimecodeContinuityAnalyzer {
+    /// Creates an analyzer that has not seen any frames yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the next frame of the file to the analysis.
+    ///
+    /// Frames must be added in increasing order of their index.
+    pub fn add_frame(&mut self, frame_index: u64, frame: &dif::Frame) {
+        self.add_timecode(frame_index, frame.file_info.system(), FrameTimecode::from_frame(frame));
+    }
+
+    /// Add the title timecode of the next frame of the file to the analysis.
+    ///
+    /// Frames must be added in increasing order of their index.
+    pub fn add_timecode(&mut self, frame_index: u64, system: System, timecode: FrameTimecode) {
+        self.report.frame_count += 1;
+        let timecode = match timecode {
+            FrameTimecode::Valid(timecode) => timecode,
+            FrameTimecode::Invalid | FrameTimecode::Missing => {
+                self.extend_run(frame_index, timecode);
+                return;
+            }
+        };
+        self.report.issues.extend(self.run.take());
+
+        if let Some((previous_frame_index, previous)) = self.previous {
+            if previous.drop_frame != timecode.drop_frame {
+                self.report.issues.push(TimecodeIssue::DropFrameChange {
+                    frame_index,
+                    drop_frame: timecode.drop_frame,
+                });
+            }
+
+            // Measure the distance between the timecodes in the drop frame mode of the current
+            // frame, taking the shortest way around the 24 hour clock.
+            let frames_per_day =
+                i64::from(TimeValueWithRequiredFrame::frames_per_day(system, timecode.drop_frame));
+            let previous_index =
+                TimeValueWithRequiredFrame { drop_frame: timecode.drop_frame, ..previous }
+                    .to_frame_index(system);
+            let mut moved = (i64::from(timecode.to_frame_index(system))
+                - i64::from(previous_index))
+            .rem_euclid(frames_per_day);
+            if moved > frames_per_day / 2 {
+                moved -= frames_per_day;
+            }
+            let expected = i64::try_from(frame_index - previous_frame_index).unwrap();
+            if moved != expected {
+                let kind = match moved {
+                    0 => DiscontinuityKind::Repeat,
+                    _ if moved < 0 => DiscontinuityKind::Backwards,
+                    _ => DiscontinuityKind::Jump,
+                };
+                self.report.issues.push(TimecodeIssue::Discontinuity {
+                    frame_index,
+                    kind,
+                    previous_frame_index,
+                    previous,
+                    timecode,
+                    offset: moved - expected,
+                });
+            }
+        }
+        self.previous = Some((frame_index, timecode));
+    }
+
+    /// Add a frame with a missing or invalid timecode to the current run of such frames, or
+    /// start a new run.
+    fn extend_run(&mut self, frame_index: u64, timecode: FrameTimecode) {
+        match (&mut self.run, timecode) {
+            (
+                Some(TimecodeIssue::Missing { frame_index: start, frame_count }),
+                FrameTimecode::Missing,
+            )
+            | (
+                Some(TimecodeIssue::Invalid { frame_index: start, frame_count }),
+                FrameTimecode::Invalid,
+            ) if *start + *frame_count == frame_index => *frame_count += 1,
+            _ => {
+                self.report.issues.extend(self.run.take());
+                self.run = Some(match timecode {
+                    FrameTimecode::Invalid => {
+                        TimecodeIssue::Invalid { frame_index, frame_count: 1 }
+                    }
+                    _ => TimecodeIssue::Missing { frame_index, frame_count: 1 },
+                });
+            }
+        }
+    }
+
+    /// Finish the analysis and return the report.
+    pub fn finish(mut self) -> TimecodeContinuityReport {
+        self.report.issues.extend(self.run.take());
+        self.report
+    }
+}
+
+/// Analyze the title timecode of every frame in a file; see [`TimecodeContinuityAnalyzer`].
+pub fn check_timecode_continuity<R: io::Read + io::Seek>(
+    reader: &mut FrameReader<R>,
+) -> FrameResult<TimecodeContinuityReport> {
+    reader.seek_frame(0)?;
+    let mut analyzer = TimecodeContinuityAnalyzer::new();
+    loop {
+        let frame_index = reader.next_frame_index();
+        match reader.read_frame()? {
+            Some(frame) => analyzer.add_frame(frame_index, &frame),
+            None => return Ok(analyzer.finish()),
+        }
+    }
+}
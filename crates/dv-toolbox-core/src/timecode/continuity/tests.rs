@@ -0,0 +1,205 @@
+use std::fs::File;
+
+use googletest::prelude::*;
+use rstest::rstest;
+
+use super::*;
+use crate::{
+    file::testutil::{read_test_frames, SONY_GOOD_QUALITY},
+    testutil::test_resource,
+};
+
+fn timecode(second: u8, frame: u8) -> TimeValueWithRequiredFrame {
+    TimeValueWithRequiredFrame { hour: 0, minute: 0, second, drop_frame: true, frame }
+}
+
+fn valid(second: u8, frame: u8) -> FrameTimecode {
+    FrameTimecode::Valid(timecode(second, frame))
+}
+
+#[googletest::test]
+fn test_frame_timecode_from_frame() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    expect_that!(FrameTimecode::from_frame(&frames[0]), eq(valid(2, 17)));
+
+    // Damage the contents of every title timecode pack.
+    let mut invalid = frames[0].clone();
+    for sequence in &mut invalid.channels[0].dif_sequences {
+        for block in &mut sequence.subcode {
+            for sync_block in block.data.chunks_exact_mut(dif::SUBCODE_SYNC_BLOCK_SIZE) {
+                if sync_block[3] == 0x13 {
+                    sync_block[4..].fill(0xFF);
+                }
+            }
+        }
+    }
+    expect_that!(FrameTimecode::from_frame(&invalid), eq(FrameTimecode::Invalid));
+
+    // Blank out the subcode entirely.
+    let mut missing = frames[0].clone();
+    for sequence in &mut missing.channels[0].dif_sequences {
+        for block in &mut sequence.subcode {
+            block.data.fill(0xFF);
+        }
+    }
+    expect_that!(FrameTimecode::from_frame(&missing), eq(FrameTimecode::Missing));
+}
+
+#[googletest::test]
+#[rstest]
+#[case::continuous(vec![valid(0, 0), valid(0, 1), valid(0, 2)], vec![])]
+#[case::jump(
+    vec![valid(0, 0), valid(0, 1), valid(0, 5)],
+    vec![TimecodeIssue::Discontinuity {
+        frame_index: 2,
+        kind: DiscontinuityKind::Jump,
+        previous_frame_index: 1,
+        previous: timecode(0, 1),
+        timecode: timecode(0, 5),
+        offset: 3,
+    }]
+)]
+#[case::repeat(
+    vec![valid(0, 0), valid(0, 1), valid(0, 1), valid(0, 2)],
+    vec![TimecodeIssue::Discontinuity {
+        frame_index: 2,
+        kind: DiscontinuityKind::Repeat,
+        previous_frame_index: 1,
+        previous: timecode(0, 1),
+        timecode: timecode(0, 1),
+        offset: -1,
+    }]
+)]
+#[case::backwards(
+    vec![valid(0, 5), valid(0, 6), valid(0, 0)],
+    vec![TimecodeIssue::Discontinuity {
+        frame_index: 2,
+        kind: DiscontinuityKind::Backwards,
+        previous_frame_index: 1,
+        previous: timecode(0, 6),
+        timecode: timecode(0, 0),
+        offset: -7,
+    }]
+)]
+#[case::gap_continues(
+    vec![valid(0, 0), FrameTimecode::Missing, FrameTimecode::Missing, valid(0, 3)],
+    vec![TimecodeIssue::Missing { frame_index: 1, frame_count: 2 }]
+)]
+#[case::gap_jumps(
+    vec![valid(0, 0), FrameTimecode::Invalid, valid(0, 10)],
+    vec![
+        TimecodeIssue::Invalid { frame_index: 1, frame_count: 1 },
+        TimecodeIssue::Discontinuity {
+            frame_index: 2,
+            kind: DiscontinuityKind::Jump,
+            previous_frame_index: 0,
+            previous: timecode(0, 0),
+            timecode: timecode(0, 10),
+            offset: 8,
+        },
+    ]
+)]
+#[case::runs(
+    vec![
+        FrameTimecode::Missing,
+        FrameTimecode::Invalid,
+        FrameTimecode::Invalid,
+        FrameTimecode::Missing,
+    ],
+    vec![
+        TimecodeIssue::Missing { frame_index: 0, frame_count: 1 },
+        TimecodeIssue::Invalid { frame_index: 1, frame_count: 2 },
+        TimecodeIssue::Missing { frame_index: 3, frame_count: 1 },
+    ]
+)]
+#[case::midnight(
+    vec![
+        FrameTimecode::Valid(TimeValueWithRequiredFrame {
+            hour: 23,
+            minute: 59,
+            second: 59,
+            drop_frame: true,
+            frame: 29,
+        }),
+        valid(0, 0),
+    ],
+    vec![]
+)]
+#[case::drop_frame_change(
+    vec![
+        valid(59, 29),
+        FrameTimecode::Valid(TimeValueWithRequiredFrame {
+            hour: 0,
+            minute: 1,
+            second: 0,
+            drop_frame: false,
+            frame: 0,
+        }),
+    ],
+    vec![TimecodeIssue::DropFrameChange { frame_index: 1, drop_frame: false }]
+)]
+fn test_timecode_continuity_analyzer(
+    #[case] timecodes: Vec<FrameTimecode>,
+    #[case] expected: Vec<TimecodeIssue>,
+) {
+    let mut analyzer = TimecodeContinuityAnalyzer::new();
+    for (frame_index, timecode) in timecodes.iter().enumerate() {
+        analyzer.add_timecode(u64::try_from(frame_index).unwrap(), System::Sys525_60, *timecode);
+    }
+    let report = analyzer.finish();
+    expect_that!(report.frame_count, eq(u64::try_from(timecodes.len()).unwrap()));
+    expect_that!(report.issues, eq(&expected));
+}
+
+#[googletest::test]
+fn test_check_timecode_continuity() {
+    let file = File::open(test_resource("dv_multiframe/sony_good_quality.dv")).unwrap();
+    let mut reader = FrameReader::new(file, *SONY_GOOD_QUALITY).unwrap();
+    let report = check_timecode_continuity(&mut reader).unwrap();
+    expect_that!(report, eq(&TimecodeContinuityReport { frame_count: 5, issues: Vec::new() }));
+}
+
+#[googletest::test]
+fn test_timecode_continuity_report_display() {
+    let report = TimecodeContinuityReport {
+        frame_count: 100,
+        issues: vec![
+            TimecodeIssue::Discontinuity {
+                frame_index: 10,
+                kind: DiscontinuityKind::Jump,
+                previous_frame_index: 9,
+                previous: timecode(1, 2),
+                timecode: timecode(4, 3),
+                offset: 90,
+            },
+            TimecodeIssue::Discontinuity {
+                frame_index: 20,
+                kind: DiscontinuityKind::Repeat,
+                previous_frame_index: 19,
+                previous: timecode(5, 0),
+                timecode: timecode(5, 0),
+                offset: -1,
+            },
+            TimecodeIssue::Discontinuity {
+                frame_index: 30,
+                kind: DiscontinuityKind::Backwards,
+                previous_frame_index: 29,
+                previous: timecode(5, 10),
+                timecode: timecode(0, 0),
+                offset: -161,
+            },
+            TimecodeIssue::Missing { frame_index: 40, frame_count: 5 },
+            TimecodeIssue::Invalid { frame_index: 50, frame_count: 1 },
+            TimecodeIssue::DropFrameChange { frame_index: 60, drop_frame: false },
+        ],
+    };
+    expect_that!(
+        report.to_string(),
+        eq("frame 10, timecode jumps +90 frames from 00:00:01;02 to 00:00:04;03\n\
+            frame 20, timecode 00:00:05;00 repeats\n\
+            frame 30, timecode goes backwards from 00:00:05;10 to 00:00:00;00\n\
+            frames 40-44, timecode missing\n\
+            frame 50, timecode invalid\n\
+            frame 60, drop frame turned off\n")
+    );
+}
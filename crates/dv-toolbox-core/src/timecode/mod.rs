@@ -0,0 +1,37 @@
+//! Analysis and repair of the timecodes recorded in DV frames.
+//!
+//! Camcorders record a title timecode in every frame, which editing software relies on to place
+//! frames on a timeline.  Tapes often end up with broken timecode: it restarts at zero where a
+//! tape was ejected and reinserted, it is missing where the tape was blank or damaged, and
+//! dropouts corrupt individual values.  Such problems commonly cause captured files to fail import
+//! into a non-linear editor.
+//!
+//! The [`TimecodeContinuityAnalyzer`] walks the consensus title timecode of each frame (see
+//! [`crate::dif::FrameMetadata`]), and produces a [`TimecodeContinuityReport`] of every place
+//! where the timecode does not advance by exactly one frame per frame.  The
+//! [`regenerate_timecodes`] and [`regenerate_file_timecodes`] functions repair such problems by
+//! rewriting the title timecode packs, either renumbering every frame from a chosen start, or
+//! only filling in damaged ranges by counting from the good timecodes around them.
+
+pub use continuity::*;
+pub use regenerate::*;
+use snafu::prelude::*;
+
+mod continuity;
+mod regenerate;
+
+/// Result type for calls related to repairing timecodes.
+pub type TimecodeResult<T, E = TimecodeError> = std::result::Result<T, E>;
+
+/// Error type for when timecodes could not be repaired.
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum TimecodeError {
+    #[snafu(whatever, display("{message}"))]
+    Whatever {
+        message: String,
+        #[snafu(source(from(Box<dyn std::error::Error>, Some)))]
+        source: Option<Box<dyn std::error::Error>>,
+        backtrace: snafu::Backtrace,
+    },
+}
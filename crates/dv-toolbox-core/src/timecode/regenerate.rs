@@ -0,0 +1,301 @@
+use std::{collections::HashSet, fmt, io};
+
+use garde::Unvalidated;
+use serde::{Deserialize, Serialize};
+use snafu::prelude::*;
+
+use super::TimecodeResult;
+use crate::{
+    dif::{self, FrameMetadata, PackPosition},
+    file::{FrameReader, FrameWriter, System, ValidInfoMethods},
+    pack::{self, TimeValueWithRequiredFrame},
+};
+
+#[cfg(test)]
+mod tests;
+
+/// How [`regenerate_timecodes`] chooses the frames to rewrite.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub enum RegenerateMode {
+    /// Every frame is renumbered, so that the timecode counts up one frame at a time from the
+    /// given start value at the first frame.
+    Continuous {
+        /// Timecode of the first frame.  Its drop frame flag is used for every frame.
+        start: TimeValueWithRequiredFrame,
+    },
+
+    /// Only frames with a damaged timecode are rewritten, by counting frames from the nearest
+    /// good timecode, called an anchor.
+    ///
+    /// A frame is an anchor if its timecode is valid, and it follows on from the timecode of an
+    /// adjacent frame.  A lone valid timecode that doesn't follow on from any adjacent valid
+    /// timecode is considered damaged.  However, a valid timecode is also an anchor if no adjacent
+    /// frame has a valid timecode to compare it with.
+    ///
+    /// Damaged frames are counted forward from the previous anchor, or backwards from the next
+    /// anchor if they come before the first anchor.
+    FillDamaged,
+}
+
+/// Flags used for new timecodes when the file has no valid timecode to copy them from.  These
+/// are the values of the simplified timecode format used by consumer equipment, which sets every
+/// unused bit.
+const DEFAULT_TITLE_TIMECODE: pack::TitleTimecode = pack::TitleTimecode {
+    timecode: pack::Timecode {
+        time: TimeValueWithRequiredFrame {
+            hour: 0,
+            minute: 0,
+            second: 0,
+            drop_frame: true,
+            frame: 0,
+        },
+        color_frame: pack::ColorFrame::Synchronized,
+        polarity_correction: pack::PolarityCorrection::Odd,
+        binary_group_flag: pack::BinaryGroupFlag::TimeClockGroupPageLine,
+    },
+    blank_flag: pack::BlankFlag::Continuous,
+};
+
+/// Returns a copy of the title timecode with a different time value.  The other fields are kept
+/// as they are, so that they stay consistent with the timecode it was copied from.  The polarity
+/// correction is only brought up to date when the timecode is written to a frame; see
+/// [`write_title_timecode`].
+fn with_time(
+    title_timecode: &pack::TitleTimecode,
+    time: TimeValueWithRequiredFrame,
+) -> pack::TitleTimecode {
+    pack::TitleTimecode {
+        timecode: pack::Timecode { time, ..title_timecode.timecode },
+        ..*title_timecode
+    }
+}
+
+/// Whether two title timecodes are the same, apart from their polarity correction, which depends
+/// on the binary group of the frame that they are written to.
+fn same_apart_from_polarity(a: &pack::TitleTimecode, b: &pack::TitleTimecode) -> bool {
+    let polarity_correction = b.timecode.polarity_correction;
+    pack::TitleTimecode { timecode: pack::Timecode { polarity_correction, ..a.timecode }, ..*a }
+        == *b
+}
+
+/// Choose new title timecodes for consecutive frames of a file.
+///
+/// The `timecodes` hold the consensus title timecode of each frame, or [`None`] if the frame has
+/// no valid title timecode.  A new title timecode is returned for each frame that needs to be
+/// rewritten, or [`None`] if the frame should be left as it is.  The color frame, polarity
+/// correction, binary group, and blank flags of new timecodes are copied from the timecode that
+/// they are counted from, or from the first valid timecode in [`RegenerateMode::Continuous`]
+/// mode.  A frame is left as it is if only its polarity correction differs, since that is
+/// recomputed by [`write_title_timecode`] anyway.
+///
+/// An error is returned in [`RegenerateMode::FillDamaged`] mode if no frame has a valid
+/// timecode to count from.
+pub fn regenerate_timecodes(
+    timecodes: &[Option<pack::TitleTimecode>],
+    system: System,
+    mode: RegenerateMode,
+) -> TimecodeResult<Vec<Option<pack::TitleTimecode>>> {
+    let new_timecodes: Vec<_> = match mode {
+        RegenerateMode::Continuous { start } => {
+            let template = timecodes.iter().flatten().next().unwrap_or(&DEFAULT_TITLE_TIMECODE);
+            (0..timecodes.len())
+                .map(|i| with_time(template, start.add_frames(i64::try_from(i).unwrap(), system)))
+                .collect()
+        }
+        RegenerateMode::FillDamaged => {
+            let anchors: Vec<_> = (0..timecodes.len())
+                .filter(|i| is_anchor(timecodes, *i, system))
+                .map(|i| (i, timecodes[i].unwrap()))
+                .collect();
+            ensure_whatever!(!anchors.is_empty(), "No frame has a valid timecode to count from");
+            let mut next_anchor = 0;
+            (0..timecodes.len())
+                .map(|i| {
+                    while next_anchor < anchors.len() && anchors[next_anchor].0 <= i {
+                        next_anchor += 1;
+                    }
+                    let (anchor_index, anchor) = anchors[next_anchor.saturating_sub(1)];
+                    let frames = i64::try_from(i).unwrap() - i64::try_from(anchor_index).unwrap();
+                    with_time(&anchor, anchor.timecode.time.add_frames(frames, system))
+                })
+                .collect()
+        }
+    };
+    Ok(new_timecodes
+        .into_iter()
+        .zip(timecodes)
+        .map(|(new, old)| {
+            (!old.is_some_and(|old| same_apart_from_polarity(&new, &old))).then_some(new)
+        })
+        .collect())
+}
+
+/// Whether the timecode of a frame can be trusted to count other frames from; see
+/// [`RegenerateMode::FillDamaged`].
+fn is_anchor(timecodes: &[Option<pack::TitleTimecode>], index: usize, system: System) -> bool {
+    let time = |i: usize| timecodes.get(i).copied().flatten().map(|t| t.timecode.time);
+    let Some(current) = time(index) else {
+        return false;
+    };
+    let previous = index.checked_sub(1).and_then(time);
+    let next = time(index + 1);
+    match (previous, next) {
+        (None, None) => true,
+        _ => {
+            previous.is_some_and(|p| p.next_frame(system) == current)
+                || next.is_some_and(|n| current.next_frame(system) == n)
+        }
+    }
+}
+
+/// Returns the positions of the subcode and VAUX packs of the frame that hold a title timecode,
+/// including title timecodes that are invalid.
+pub fn title_timecode_positions(frame: &dif::Frame) -> Vec<PackPosition> {
+    frame
+        .subcode_and_vaux_packs()
+        .into_iter()
+        .filter(|(_, pack)| pack.pack_type() == pack::Type::TitleTimecode)
+        .map(|(position, _)| position)
+        .collect()
+}
+
+/// Replace every title timecode pack in the subcode and VAUX sections of the frame.
+///
+/// Besides the packs that already hold a title timecode, the pack is also written to the given
+/// `positions`, unless they hold a valid pack of another type.  This restores the title timecode
+/// in places where it was lost to a dropout.  The positions are typically gathered from
+/// undamaged frames with [`title_timecode_positions`].
+///
+/// If the frame has a title binary group pack, the timecode uses the IEC 60461 format, and its
+/// polarity correction is recomputed to match the binary group.  Whether a binary group is in use
+/// is decided by the presence of that pack, not by the binary group flag: consumer equipment that
+/// records the simplified timecode format sets the flag to
+/// [`pack::BinaryGroupFlag::TimeClockGroupPageLine`] along with every other unused bit.  Without
+/// a binary group pack, the polarity correction is written as given.
+pub fn write_title_timecode(
+    frame: &mut dif::Frame,
+    title_timecode: &pack::TitleTimecode,
+    positions: &[PackPosition],
+) -> TimecodeResult<()> {
+    let mut title_timecode = *title_timecode;
+    if let Some(pack::Pack::TitleBinaryGroup(binary_group)) =
+        FrameMetadata::from_frame(frame).pack(pack::Type::TitleBinaryGroup)
+    {
+        title_timecode.timecode.polarity_correction =
+            title_timecode.timecode.even_parity_polarity_correction(binary_group);
+    }
+    let valid = Unvalidated::new(title_timecode)
+        .validate_with(&frame.pack_context())
+        .whatever_context("New title timecode is not valid")?;
+    let new_pack = pack::Pack::TitleTimecode(valid.into());
+    for (position, existing) in frame.subcode_and_vaux_packs() {
+        let replace = match existing {
+            pack::Pack::TitleTimecode(_) => true,
+            pack::Pack::Invalid(pack::Type::TitleTimecode, _) => true,
+            pack::Pack::NoInfo(_) | pack::Pack::Invalid(..) => positions.contains(&position),
+            _ => false,
+        };
+        if replace {
+            frame.set_pack(&position, &new_pack);
+        }
+    }
+    Ok(())
+}
+
+/// A range of consecutive frames whose title timecode was rewritten.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub struct RewrittenTimecodes {
+    /// Index of the first frame of the range.
+    pub frame_index: u64,
+
+    /// Number of frames in the range.
+    pub frame_count: u64,
+
+    /// New timecode of the first frame of the range.
+    pub start: TimeValueWithRequiredFrame,
+}
+
+/// Report of the frames whose title timecode was rewritten by [`regenerate_file_timecodes`].
+///
+/// The [`fmt::Display`] implementation prints one line for each range of frames, such as
+/// `frames 100-149, timecode rewritten from 00:00:03;10`.
+#[derive(Debug, PartialEq, Eq, Clone, Default, Serialize, Deserialize)]
+pub struct TimecodeRepairReport {
+    /// Number of frames in the file.
+    pub frame_count: u64,
+
+    /// Ranges of frames that were rewritten, in order.
+    pub rewritten: Vec<RewrittenTimecodes>,
+}
+
+impl fmt::Display for TimecodeRepairReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for range in &self.rewritten {
+            match range.frame_count {
+                1 => write!(f, "frame {}", range.frame_index)?,
+                _ => write!(
+                    f,
+                    "frames {}-{}",
+                    range.frame_index,
+                    range.frame_index + range.frame_count - 1
+                )?,
+            }
+            writeln!(f, ", timecode rewritten from {}", range.start)?;
+        }
+        Ok(())
+    }
+}
+
+/// Rewrite the title timecode of a file; see [`regenerate_timecodes`].
+///
+/// The file is read twice: once to gather the title timecodes, and once to rewrite them.  Every
+/// frame is written to `writer`, whether or not its timecode was changed.  New title timecodes
+/// are written to every subcode and VAUX position that held a title timecode in any frame of the
+/// file; see [`write_title_timecode`].
+pub fn regenerate_file_timecodes<R: io::Read + io::Seek, W: io::Write>(
+    reader: &mut FrameReader<R>,
+    writer: &mut FrameWriter<W>,
+    mode: RegenerateMode,
+) -> TimecodeResult<TimecodeRepairReport> {
+    reader.seek_frame(0).whatever_context("Could not seek to the start of the file")?;
+    let mut timecodes = Vec::new();
+    let mut positions = HashSet::new();
+    while let Some(frame) = reader.read_frame().whatever_context("Could not read the file")? {
+        timecodes.push(match FrameMetadata::from_frame(&frame).pack(pack::Type::TitleTimecode) {
+            Some(pack::Pack::TitleTimecode(title_timecode)) => Some(*title_timecode.0),
+            _ => None,
+        });
+        positions.extend(title_timecode_positions(&frame));
+    }
+    let positions: Vec<_> = positions.into_iter().collect();
+    let new_timecodes = regenerate_timecodes(&timecodes, reader.file_info().system(), mode)?;
+
+    reader.seek_frame(0).whatever_context("Could not seek to the start of the file")?;
+    let mut report = TimecodeRepairReport {
+        frame_count: u64::try_from(timecodes.len()).unwrap(),
+        rewritten: Vec::new(),
+    };
+    for (frame_index, new_timecode) in (0..).zip(&new_timecodes) {
+        let mut frame = reader
+            .read_frame()
+            .with_whatever_context(|_| format!("Could not read frame {frame_index}"))?
+            .with_whatever_context(|| format!("File ended before frame {frame_index}"))?;
+        if let Some(new_timecode) = new_timecode {
+            write_title_timecode(&mut frame, new_timecode, &positions)?;
+            match report.rewritten.last_mut() {
+                Some(range) if range.frame_index + range.frame_count == frame_index => {
+                    range.frame_count += 1;
+                }
+                _ => report.rewritten.push(RewrittenTimecodes {
+                    frame_index,
+                    frame_count: 1,
+                    start: new_timecode.timecode.time,
+                }),
+            }
+        }
+        writer
+            .write_frame(&frame)
+            .with_whatever_context(|_| format!("Could not write frame {frame_index}"))?;
+    }
+    Ok(report)
+}
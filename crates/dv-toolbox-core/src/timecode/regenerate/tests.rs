@@ -0,0 +1,233 @@
+use std::io::Cursor;
+
+use googletest::prelude::*;
+use rstest::rstest;
+
+use super::*;
+use crate::file::testutil::{frame_reader, read_test_frames, SONY_GOOD_QUALITY};
+
+fn time(second: u8, frame: u8) -> TimeValueWithRequiredFrame {
+    TimeValueWithRequiredFrame { hour: 0, minute: 0, second, drop_frame: true, frame }
+}
+
+/// A title timecode with flags that differ from [`DEFAULT_TITLE_TIMECODE`].
+fn title_timecode(second: u8, frame: u8) -> pack::TitleTimecode {
+    pack::TitleTimecode {
+        timecode: pack::Timecode {
+            time: time(second, frame),
+            color_frame: pack::ColorFrame::Unsynchronized,
+            polarity_correction: pack::PolarityCorrection::Even,
+            binary_group_flag: pack::BinaryGroupFlag::TimeUnspecifiedGroupUnspecified,
+        },
+        blank_flag: pack::BlankFlag::Discontinuous,
+    }
+}
+
+fn tc(second: u8, frame: u8) -> Option<pack::TitleTimecode> {
+    Some(title_timecode(second, frame))
+}
+
+#[googletest::test]
+#[rstest]
+#[case::continuous(
+    vec![tc(0, 0), tc(0, 1), None, tc(5, 0)],
+    RegenerateMode::Continuous { start: time(0, 0) },
+    vec![None, None, tc(0, 2), tc(0, 3)]
+)]
+#[case::continuous_default_flags(
+    vec![None, None],
+    RegenerateMode::Continuous { start: time(0, 29) },
+    vec![
+        Some(with_time(&DEFAULT_TITLE_TIMECODE, time(0, 29))),
+        Some(with_time(&DEFAULT_TITLE_TIMECODE, time(1, 0))),
+    ]
+)]
+#[case::fill_gap(
+    vec![tc(0, 0), tc(0, 1), None, None, tc(0, 4)],
+    RegenerateMode::FillDamaged,
+    vec![None, None, tc(0, 2), tc(0, 3), None]
+)]
+#[case::fill_corrupted_value(
+    vec![tc(0, 0), tc(0, 1), tc(7, 7), tc(0, 3), tc(0, 4)],
+    RegenerateMode::FillDamaged,
+    vec![None, None, tc(0, 2), None, None]
+)]
+#[case::fill_before_first_anchor(
+    vec![None, None, tc(1, 0), tc(1, 1)],
+    RegenerateMode::FillDamaged,
+    vec![tc(0, 28), tc(0, 29), None, None]
+)]
+#[case::fill_keeps_scene_breaks(
+    vec![tc(0, 0), tc(0, 1), None, tc(9, 0), tc(9, 1)],
+    RegenerateMode::FillDamaged,
+    vec![None, None, tc(0, 2), None, None]
+)]
+#[case::fill_lone_anchor(
+    vec![None, tc(3, 0), None],
+    RegenerateMode::FillDamaged,
+    vec![tc(2, 29), None, tc(3, 1)]
+)]
+fn test_regenerate_timecodes(
+    #[case] timecodes: Vec<Option<pack::TitleTimecode>>,
+    #[case] mode: RegenerateMode,
+    #[case] expected: Vec<Option<pack::TitleTimecode>>,
+) {
+    expect_that!(regenerate_timecodes(&timecodes, System::Sys525_60, mode), ok(eq(&expected)));
+}
+
+#[googletest::test]
+fn test_regenerate_timecodes_ignores_polarity_correction() {
+    let mut other_polarity = title_timecode(0, 1);
+    other_polarity.timecode.polarity_correction = pack::PolarityCorrection::Odd;
+    expect_that!(
+        regenerate_timecodes(
+            &[tc(0, 0), Some(other_polarity)],
+            System::Sys525_60,
+            RegenerateMode::Continuous { start: time(0, 0) }
+        ),
+        ok(eq(&vec![None, None]))
+    );
+}
+
+#[googletest::test]
+fn test_regenerate_timecodes_without_anchor() {
+    expect_that!(
+        regenerate_timecodes(&[None, None], System::Sys525_60, RegenerateMode::FillDamaged),
+        err(displays_as(eq("No frame has a valid timecode to count from")))
+    );
+}
+
+#[googletest::test]
+fn test_write_title_timecode() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let positions = title_timecode_positions(&frames[0]);
+    expect_that!(positions.len(), eq(80));
+
+    // Lose one copy of the title timecode to a dropout, and corrupt another.
+    let mut frame = frames[0].clone();
+    let ctx = frame.pack_context();
+    frame.set_pack(&positions[0], &pack::Pack::from_raw(&[0xFF; 5], &ctx).0);
+    frame.set_pack(&positions[1], &pack::Pack::from_raw(&[0x13, 0xFF, 0xFF, 0xFF, 0xFF], &ctx).0);
+
+    let new_timecode = title_timecode(10, 5);
+    write_title_timecode(&mut frame, &new_timecode, &positions).unwrap();
+    let metadata = FrameMetadata::from_frame(&frame);
+    expect_that!(metadata.dissenting_packs, empty());
+    expect_that!(
+        metadata.pack(pack::Type::TitleTimecode),
+        some(eq(&pack::Pack::TitleTimecode(
+            Unvalidated::new(new_timecode).validate_with(&ctx).unwrap().into()
+        )))
+    );
+    expect_that!(title_timecode_positions(&frame), eq(&positions));
+
+    // Other packs are not touched.
+    let mut expected = frames[0].clone();
+    for position in &positions {
+        expected.set_pack(position, metadata.pack(pack::Type::TitleTimecode).unwrap());
+    }
+    expect_that!(frame, eq(&expected));
+}
+
+#[googletest::test]
+fn test_write_title_timecode_polarity_correction() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let positions = title_timecode_positions(&frames[0]);
+    let polarity_correction = |frame: &dif::Frame| match FrameMetadata::from_frame(frame)
+        .pack(pack::Type::TitleTimecode)
+    {
+        Some(pack::Pack::TitleTimecode(title_timecode)) => {
+            title_timecode.timecode.polarity_correction
+        }
+        pack => panic!("no title timecode: {pack:?}"),
+    };
+
+    // Without a binary group, the polarity correction is written as given.
+    let mut frame = frames[0].clone();
+    write_title_timecode(&mut frame, &title_timecode(0, 1), &positions).unwrap();
+    expect_that!(polarity_correction(&frame), eq(pack::PolarityCorrection::Even));
+
+    // Record an empty title binary group in an unused pack slot.
+    let mut frame = frames[0].clone();
+    let ctx = frame.pack_context();
+    let (unused, _) = frame
+        .subcode_and_vaux_packs()
+        .into_iter()
+        .find(|(_, pack)| matches!(pack, pack::Pack::NoInfo(_)))
+        .unwrap();
+    frame.set_pack(&unused, &pack::Pack::from_raw(&[0x14, 0x00, 0x00, 0x00, 0x00], &ctx).0);
+
+    // With the binary group, the parity bit follows the number of 0 bits in the time.
+    let polarity_corrections: Vec<_> = (0..4)
+        .map(|i| {
+            write_title_timecode(&mut frame, &title_timecode(0, i), &positions).unwrap();
+            polarity_correction(&frame)
+        })
+        .collect();
+    expect_that!(
+        polarity_corrections,
+        elements_are![
+            eq(&pack::PolarityCorrection::Even),
+            eq(&pack::PolarityCorrection::Odd),
+            eq(&pack::PolarityCorrection::Odd),
+            eq(&pack::PolarityCorrection::Even),
+        ]
+    );
+}
+
+#[googletest::test]
+fn test_regenerate_file_timecodes() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let mut damaged = frames.clone();
+    for frame in &mut damaged[2..4] {
+        for position in title_timecode_positions(&frames[0]) {
+            let no_info = pack::Pack::from_raw(&[0xFF; 5], &frame.pack_context()).0;
+            frame.set_pack(&position, &no_info);
+        }
+    }
+
+    // Filling in the damaged frames restores the original file.
+    let mut writer = FrameWriter::new(Cursor::new(Vec::new()), *SONY_GOOD_QUALITY);
+    let report = regenerate_file_timecodes(
+        &mut frame_reader(&damaged, &SONY_GOOD_QUALITY),
+        &mut writer,
+        RegenerateMode::FillDamaged,
+    )
+    .unwrap();
+    let restored = frame_reader(&frames, &SONY_GOOD_QUALITY).into_inner().into_inner();
+    expect_that!(writer.into_inner().into_inner(), eq(&restored));
+    expect_that!(
+        report,
+        eq(&TimecodeRepairReport {
+            frame_count: 5,
+            rewritten: vec![RewrittenTimecodes {
+                frame_index: 2,
+                frame_count: 2,
+                start: time(2, 19)
+            }],
+        })
+    );
+    expect_that!(report.to_string(), eq("frames 2-3, timecode rewritten from 00:00:02;19\n"));
+
+    // Renumbering the whole file.
+    let mut writer = FrameWriter::new(Cursor::new(Vec::new()), *SONY_GOOD_QUALITY);
+    let start =
+        TimeValueWithRequiredFrame { hour: 1, minute: 0, second: 0, drop_frame: true, frame: 0 };
+    let report = regenerate_file_timecodes(
+        &mut frame_reader(&damaged, &SONY_GOOD_QUALITY),
+        &mut writer,
+        RegenerateMode::Continuous { start },
+    )
+    .unwrap();
+    expect_that!(report.rewritten.len(), eq(1));
+    expect_that!(report.rewritten[0].frame_count, eq(5));
+    let mut output = FrameReader::new(writer.into_inner(), *SONY_GOOD_QUALITY).unwrap();
+    let continuity = crate::timecode::check_timecode_continuity(&mut output).unwrap();
+    expect_that!(continuity.issues, empty());
+    output.seek_frame(4).unwrap();
+    let last = output.read_frame().unwrap().unwrap();
+    expect_that!(
+        crate::timecode::FrameTimecode::from_frame(&last),
+        eq(crate::timecode::FrameTimecode::Valid(start.add_frames(4, System::Sys525_60)))
+    );
+}
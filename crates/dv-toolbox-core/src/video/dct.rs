@@ -0,0 +1,283 @@
+use std::{
+    f64::consts::{PI, SQRT_2},
+    sync::LazyLock,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Number of coefficients in a DCT block.
+pub(super) const COEFFICIENT_COUNT: usize = 64;
+
+/// Width and height of a DCT block, in pixels.
+pub(super) const BLOCK_DIMENSION: usize = 8;
+
+/// Decoded pixels of a single DCT block, in row-major order.
+pub(super) type BlockPixels = [u8; COEFFICIENT_COUNT];
+
+/// Transform that was used to compress a DCT block.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(super) enum DctMode {
+    /// A single 8x8 DCT over the whole block.  Used when there is little motion between the two
+    /// fields of the frame.
+    Dct88,
+
+    /// Two 4x8 DCTs over the sum and the difference of the two fields.  Used when there is a lot
+    /// of motion between the two fields of the frame.
+    Dct248,
+}
+
+/// Zigzag scan order of the 8-8 DCT: maps scan positions to the index of the coefficient, in
+/// row-major order of vertical and horizontal frequency.
+const ZIGZAG_88: [usize; COEFFICIENT_COUNT] = [
+    0, 1, 8, 16, 9, 2, 3, 10, 17, 24, 32, 25, 18, 11, 4, 5, 12, 19, 26, 33, 40, 48, 41, 34, 27, 20,
+    13, 6, 7, 14, 21, 28, 35, 42, 49, 56, 57, 50, 43, 36, 29, 22, 15, 23, 30, 37, 44, 51, 58, 59,
+    52, 45, 38, 31, 39, 46, 53, 60, 61, 54, 47, 55, 62, 63,
+];
+
+/// Zigzag scan order of the 2-4-8 DCT: maps scan positions to the index of the coefficient.  Even
+/// rows hold the coefficients of the sum of the fields and odd rows hold the coefficients of the
+/// difference, so that row `2v + d` has vertical frequency `v`.
+const ZIGZAG_248: [usize; COEFFICIENT_COUNT] = [
+    0, 8, 1, 9, 16, 24, 2, 10, 17, 25, 32, 40, 48, 56, 33, 41, 18, 26, 3, 11, 4, 12, 19, 27, 34,
+    42, 49, 57, 50, 58, 35, 43, 20, 28, 5, 13, 6, 14, 21, 29, 36, 44, 51, 59, 52, 60, 37, 45, 22,
+    30, 7, 15, 23, 31, 38, 46, 53, 61, 54, 62, 39, 47, 55, 63,
+];
+
+/// Quantization step of each area of a DCT block, as a power of two.  The row is selected by
+/// adding the quantization number (QNO) of the macroblock to the offset for the class of the DCT
+/// block from [`CLASS_OFFSETS`].
+///
+/// - IEC 61834-2:1998 - SD format for 525-60 and 625-50 systems
+const QUANTIZATION_SHIFTS: [[u8; 4]; 22] = [
+    [3, 3, 4, 4],
+    [3, 3, 4, 4],
+    [2, 3, 3, 4],
+    [2, 3, 3, 4],
+    [2, 2, 3, 3],
+    [2, 2, 3, 3],
+    [1, 2, 2, 3],
+    [1, 2, 2, 3],
+    [1, 1, 2, 2],
+    [1, 1, 2, 2],
+    [0, 1, 1, 2],
+    [0, 1, 1, 2],
+    [0, 0, 1, 1],
+    [0, 0, 1, 1],
+    [0, 0, 0, 1],
+    [0, 0, 0, 0],
+    [0, 0, 0, 0],
+    [0, 0, 0, 0],
+    [0, 0, 0, 0],
+    [0, 0, 0, 0],
+    [0, 0, 0, 0],
+    [0, 0, 0, 0],
+];
+
+/// Row offset into [`QUANTIZATION_SHIFTS`] for each class number.  Class 3 additionally doubles
+/// the quantization step.
+const CLASS_OFFSETS: [usize; 4] = [6, 3, 0, 1];
+
+/// Returns the area number of the coefficient at the given scan position.
+fn area(position: usize) -> usize {
+    match position {
+        0..=5 => 0,
+        6..=20 => 1,
+        21..=42 => 2,
+        _ => 3,
+    }
+}
+
+/// Weighting factor `w(i)` that the encoder applies along one dimension of frequency `i`.
+fn dimension_weight(i: usize) -> f64 {
+    let cs = |m: f64| (m * PI / 16.0).cos();
+    match i {
+        0 => 1.0,
+        1 => cs(4.0) / (4.0 * cs(7.0) * cs(2.0)),
+        2 => cs(4.0) / (2.0 * cs(6.0)),
+        3 => 1.0 / (2.0 * cs(5.0)),
+        4 => 7.0 / 8.0,
+        5 => cs(4.0) / cs(3.0),
+        6 => cs(4.0) / cs(2.0),
+        7 => cs(4.0) / cs(1.0),
+        _ => unreachable!(),
+    }
+}
+
+/// Coefficient index and weighting factor for each scan position of one DCT mode.
+struct ScanTable {
+    index: [usize; COEFFICIENT_COUNT],
+    weight: [f64; COEFFICIENT_COUNT],
+}
+
+impl ScanTable {
+    fn new(mode: DctMode) -> Self {
+        let index = match mode {
+            DctMode::Dct88 => ZIGZAG_88,
+            DctMode::Dct248 => ZIGZAG_248,
+        };
+        let weight = std::array::from_fn(|position| {
+            let (row, column) =
+                (index[position] / BLOCK_DIMENSION, index[position] % BLOCK_DIMENSION);
+            let vertical = match mode {
+                DctMode::Dct88 => dimension_weight(row),
+                DctMode::Dct248 => dimension_weight(row / 2 * 2),
+            };
+            match index[position] {
+                0 => 0.25,
+                _ => dimension_weight(column) * vertical / 2.0,
+            }
+        });
+        Self { index, weight }
+    }
+}
+
+static SCAN_88: LazyLock<ScanTable> = LazyLock::new(|| ScanTable::new(DctMode::Dct88));
+static SCAN_248: LazyLock<ScanTable> = LazyLock::new(|| ScanTable::new(DctMode::Dct248));
+
+/// Undo the quantization and weighting of the coefficients of a DCT block.
+///
+/// The `levels` are the quantized coefficients in scan order, starting with the DC coefficient.
+/// The returned coefficients are in row-major order, as expected by [`inverse_dct`].
+pub(super) fn dequantize(
+    levels: &[i16; COEFFICIENT_COUNT],
+    mode: DctMode,
+    class: u8,
+    quantization_number: u8,
+) -> [f64; COEFFICIENT_COUNT] {
+    let scan = match mode {
+        DctMode::Dct88 => &*SCAN_88,
+        DctMode::Dct248 => &*SCAN_248,
+    };
+    let class = usize::from(class);
+    let shifts = QUANTIZATION_SHIFTS[usize::from(quantization_number) + CLASS_OFFSETS[class]];
+    let class_factor = if class == 3 { 2.0 } else { 1.0 };
+    let mut coefficients = [0.0; COEFFICIENT_COUNT];
+    for (position, level) in levels.iter().enumerate().filter(|(_, level)| **level != 0) {
+        let step = match position {
+            0 => 1.0,
+            _ => f64::from(1_u16 << shifts[area(position)]) * class_factor,
+        };
+        coefficients[scan.index[position]] = f64::from(*level) * step / scan.weight[position];
+    }
+    coefficients
+}
+
+/// Maximum absolute weighted AC coefficient for each class number, except the last one.  DCT
+/// blocks with more energy are put in coarser classes.
+const CLASS_THRESHOLDS: [f64; 3] = [11.5, 23.5, 35.5];
+
+/// Choose the class number of an 8-8 DCT block from its coefficients, in row-major order.
+pub(super) fn choose_class(coefficients: &[f64; COEFFICIENT_COUNT]) -> u8 {
+    let scan = &*SCAN_88;
+    let max = (1..COEFFICIENT_COUNT)
+        .map(|position| (coefficients[scan.index[position]] * scan.weight[position]).abs())
+        .fold(0.0, f64::max);
+    CLASS_THRESHOLDS.iter().filter(|threshold| max > **threshold).count() as u8
+}
+
+/// Weight and quantize the coefficients of an 8-8 DCT block.  This is the inverse of
+/// [`dequantize`].
+///
+/// The coefficients are in row-major order.  The returned levels are in scan order, starting with
+/// the DC coefficient, and are limited to the range that can be compressed.
+pub(super) fn quantize(
+    coefficients: &[f64; COEFFICIENT_COUNT],
+    class: u8,
+    quantization_number: u8,
+) -> [i16; COEFFICIENT_COUNT] {
+    let scan = &*SCAN_88;
+    let class = usize::from(class);
+    let shifts = QUANTIZATION_SHIFTS[usize::from(quantization_number) + CLASS_OFFSETS[class]];
+    let class_factor = if class == 3 { 2.0 } else { 1.0 };
+    std::array::from_fn(|position| {
+        let weighted = coefficients[scan.index[position]] * scan.weight[position];
+        match position {
+            0 => weighted.round().clamp(-256.0, 255.0) as i16,
+            _ => {
+                let step = f64::from(1_u16 << shifts[area(position)]) * class_factor;
+                (weighted / step).round().clamp(-255.0, 255.0) as i16
+            }
+        }
+    })
+}
+
+/// Basis functions of the one-dimensional inverse DCT of the given size, indexed by frequency
+/// and then by sample.  They are normalized so that the transform is orthonormal.
+fn idct_basis<const N: usize>() -> [[f64; N]; N] {
+    let n = N as f64;
+    std::array::from_fn(|k| {
+        std::array::from_fn(|x| {
+            let scale = if k == 0 { (1.0 / n).sqrt() } else { (2.0 / n).sqrt() };
+            let angle = (2 * x + 1) as f64 * k as f64 * PI / (2.0 * n);
+            scale * angle.cos()
+        })
+    })
+}
+
+static BASIS_8: LazyLock<[[f64; 8]; 8]> = LazyLock::new(idct_basis::<8>);
+static BASIS_4: LazyLock<[[f64; 4]; 4]> = LazyLock::new(idct_basis::<4>);
+
+/// Transform pixels into coefficients with an 8-8 DCT, after removing the offset of 128.  The
+/// coefficients are returned in row-major order.
+pub(super) fn forward_dct(pixels: &BlockPixels) -> [f64; COEFFICIENT_COUNT] {
+    let mut coefficients = [0.0; COEFFICIENT_COUNT];
+    for (v, basis_v) in BASIS_8.iter().enumerate() {
+        for (h, basis_h) in BASIS_8.iter().enumerate() {
+            let mut sum = 0.0;
+            for (index, pixel) in pixels.iter().enumerate() {
+                let (y, x) = (index / BLOCK_DIMENSION, index % BLOCK_DIMENSION);
+                sum += (f64::from(*pixel) - 128.0) * basis_v[y] * basis_h[x];
+            }
+            coefficients[v * BLOCK_DIMENSION + h] = sum;
+        }
+    }
+    coefficients
+}
+
+/// Transform dequantized coefficients back into pixels, adding the offset of 128 that the
+/// encoder removed before the DCT.
+pub(super) fn inverse_dct(coefficients: &[f64; COEFFICIENT_COUNT], mode: DctMode) -> BlockPixels {
+    let mut samples = [0.0; COEFFICIENT_COUNT];
+    match mode {
+        DctMode::Dct88 => {
+            for (v, basis_v) in BASIS_8.iter().enumerate() {
+                for (h, basis_h) in BASIS_8.iter().enumerate() {
+                    let coefficient = coefficients[v * 8 + h];
+                    if coefficient == 0.0 {
+                        continue;
+                    }
+                    for y in 0..8 {
+                        for x in 0..8 {
+                            samples[y * 8 + x] += coefficient * basis_v[y] * basis_h[x];
+                        }
+                    }
+                }
+            }
+        }
+        DctMode::Dct248 => {
+            // The 4x8 DCTs are scaled by an extra factor of 1/sqrt(2) compared to an orthonormal
+            // transform.  Even rows of coefficients hold the sum of the two fields, and odd rows
+            // hold the difference.  Half of the sum is added to both fields, while half of the
+            // difference is added to the first field and subtracted from the second field.  The
+            // fields are interleaved line by line.
+            for (row, basis_v) in BASIS_4.iter().flat_map(|b| [b, b]).enumerate() {
+                let sign = if row % 2 == 0 { 1.0 } else { -1.0 };
+                for (h, basis_h) in BASIS_8.iter().enumerate() {
+                    let coefficient = coefficients[row * 8 + h] * SQRT_2 / 2.0;
+                    if coefficient == 0.0 {
+                        continue;
+                    }
+                    for z in 0..4 {
+                        for x in 0..8 {
+                            let value = coefficient * basis_v[z] * basis_h[x];
+                            samples[2 * z * 8 + x] += value;
+                            samples[(2 * z + 1) * 8 + x] += sign * value;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    samples.map(|sample| (sample + 128.0).round().clamp(0.0, 255.0) as u8)
+}
@@ -0,0 +1,55 @@
+use googletest::prelude::*;
+use rstest::rstest;
+
+use super::*;
+
+fn levels(values: &[(usize, i16)]) -> [i16; COEFFICIENT_COUNT] {
+    let mut levels = [0; COEFFICIENT_COUNT];
+    for (position, value) in values {
+        levels[*position] = *value;
+    }
+    levels
+}
+
+#[googletest::test]
+#[rstest]
+#[case::dct88_zero(DctMode::Dct88, 0, 128)]
+#[case::dct88_positive(DctMode::Dct88, 100, 178)]
+#[case::dct88_negative(DctMode::Dct88, -256, 0)]
+#[case::dct248_zero(DctMode::Dct248, 0, 128)]
+#[case::dct248_positive(DctMode::Dct248, 100, 178)]
+#[case::dct248_negative(DctMode::Dct248, -200, 28)]
+fn test_dc_only(#[case] mode: DctMode, #[case] dc: i16, #[case] expected: u8) {
+    // The DC coefficient holds twice the average pixel value, offset by 128.
+    let coefficients = dequantize(&levels(&[(0, dc)]), mode, 0, 0);
+    expect_that!(inverse_dct(&coefficients, mode), each(eq(expected)));
+}
+
+#[googletest::test]
+fn test_dct248_field_difference() {
+    // The first AC coefficient of a 2-4-8 DCT block is the DC coefficient of the difference
+    // between the two fields.  Its weight is 1/2, so a level of 8 is a coefficient of 16, or a
+    // difference of 4 between the fields after the 4x8 DCT.
+    let coefficients = dequantize(&levels(&[(1, 8)]), DctMode::Dct248, 2, 15);
+    let pixels = inverse_dct(&coefficients, DctMode::Dct248);
+    for (row, line) in pixels.chunks_exact(BLOCK_DIMENSION).enumerate() {
+        let expected = if row % 2 == 0 { 130 } else { 126 };
+        expect_that!(line, each(eq(&expected)), "row {row}");
+    }
+}
+
+#[googletest::test]
+#[rstest]
+#[case::class_2_fine(2, 15, 1)]
+#[case::class_2_coarse(2, 0, 8)]
+#[case::class_0(0, 0, 2)]
+#[case::class_3(3, 15, 2)]
+#[case::class_3_coarse(3, 0, 16)]
+fn test_quantization_step(#[case] class: u8, #[case] qno: u8, #[case] step: i32) {
+    // Scan position 3 of an 8-8 DCT block is in area 0, and is the coefficient with vertical
+    // frequency 2, whose weight is w(2) / 2.
+    let coefficients = dequantize(&levels(&[(3, 1)]), DctMode::Dct88, class, qno);
+    let expected = f64::from(step) * 2.0 / dimension_weight(2);
+    expect_that!(coefficients[16], near(expected, 1e-9));
+    expect_that!(coefficients.iter().filter(|c| **c != 0.0).count(), eq(1));
+}
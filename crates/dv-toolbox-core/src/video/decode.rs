@@ -0,0 +1,281 @@
+use serde::{Deserialize, Serialize};
+use snafu::prelude::*;
+
+use super::{
+    dct::{self, BlockPixels, DctMode, COEFFICIENT_COUNT},
+    placement::{self, PlaneKind, MACROBLOCK_DCT_BLOCK_COUNT},
+    vlc::{self, BitReader, Codeword},
+    ChromaSubsampling, MacroblockArea, Plane, VideoResult,
+};
+use crate::{
+    dif::{self, VideoBlockPosition, VIDEO_BLOCK_COUNT, VIDEO_SEGMENT_BLOCK_COUNT},
+    file::{System, ValidInfoMethods},
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Space in bits allocated to each DCT block of a compressed macroblock, following the header
+/// byte of the video DIF block.
+pub(super) const DCT_BLOCK_BITS: [usize; MACROBLOCK_DCT_BLOCK_COUNT] = [112, 112, 112, 112, 80, 80];
+
+/// Number of bits at the start of each DCT block for the DC coefficient, the DCT mode, and the
+/// class number.
+pub(super) const DCT_BLOCK_HEADER_BITS: usize = 12;
+
+/// A DV frame that was decoded into planar YUV pixels, along with what is known about damage to
+/// the compressed video.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct DecodedPicture {
+    /// The system of the frame, which determines the layout of the macroblocks.
+    pub system: System,
+
+    /// Chroma subsampling of the [`DecodedPicture::cb`] and [`DecodedPicture::cr`] planes.
+    pub chroma_subsampling: ChromaSubsampling,
+
+    /// Luminance plane.
+    pub y: Plane,
+
+    /// Blue-difference chrominance plane.
+    pub cb: Plane,
+
+    /// Red-difference chrominance plane.
+    pub cr: Plane,
+
+    /// Condition of each macroblock, as recorded by the tape deck or inferred from the DIF
+    /// blocks.  Use [`DecodedPicture::macroblock_area`] to find the pixels of each macroblock.
+    pub damage: dif::DamageMap,
+
+    /// Macroblocks whose compressed data could not be fully decoded, in the order that they are
+    /// stored in the file.  A DCT block either did not end with an end of block codeword, or it
+    /// held more than 64 coefficients.  This is a sign of damage that the tape deck did not
+    /// report.  The affected pixels are still decoded as well as possible.
+    pub bitstream_errors: Vec<VideoBlockPosition>,
+}
+
+impl DecodedPicture {
+    /// Returns the area of the picture covered by the macroblock stored at the given position.
+    pub fn macroblock_area(&self, position: &VideoBlockPosition) -> MacroblockArea {
+        placement::macroblock_area(self.system, position)
+    }
+}
+
+/// Progress of decoding the AC coefficients of a single DCT block.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Progress {
+    /// More coefficients remain to be read from the overflow space of other DCT blocks.
+    Incomplete,
+
+    /// The end of block codeword was read.
+    Complete,
+
+    /// The run of a codeword went past the last coefficient.  Decoding stopped there.
+    Overrun,
+}
+
+/// Decoding state of a single DCT block.
+#[derive(Debug)]
+struct DctBlock {
+    mode: DctMode,
+    class: u8,
+
+    /// Quantized coefficients in scan order, starting with the DC coefficient.
+    levels: [i16; COEFFICIENT_COUNT],
+
+    /// Scan position of the next coefficient to decode.
+    next: usize,
+
+    progress: Progress,
+
+    /// Bits at the end of the last space that was read, which did not form a complete codeword.
+    /// The codeword continues at the start of the next space.
+    partial: Vec<bool>,
+}
+
+impl DctBlock {
+    /// Read the header of the DCT block from the start of its space.
+    fn from_header(bits: &[bool]) -> Self {
+        let value = |range: std::ops::Range<usize>| {
+            bits[range].iter().fold(0_u16, |value, bit| value << 1 | u16::from(*bit))
+        };
+        // Sign extend the 9-bit DC coefficient.
+        let dc = i16::try_from(value(0..9)).unwrap() << 7 >> 7;
+        let mut levels = [0; COEFFICIENT_COUNT];
+        levels[0] = dc;
+        Self {
+            mode: if bits[9] { DctMode::Dct248 } else { DctMode::Dct88 },
+            class: u8::try_from(value(10..12)).unwrap(),
+            levels,
+            next: 1,
+            progress: Progress::Incomplete,
+            partial: Vec::new(),
+        }
+    }
+
+    /// Read AC coefficients from the bits, until the end of block codeword is found or the bits
+    /// run out.
+    fn read_ac(&mut self, reader: &mut BitReader) {
+        let mut partial = std::mem::take(&mut self.partial);
+        while self.progress == Progress::Incomplete {
+            let mut window = partial.clone();
+            let needed = vlc::MAX_CODEWORD_LENGTH.saturating_sub(window.len());
+            window.extend(reader.remaining().iter().take(needed));
+            let Some((codeword, length)) = vlc::decode_codeword(&window) else {
+                // The rest of the bits form an incomplete codeword, to be continued later.
+                reader.advance(window.len() - partial.len());
+                self.partial = window;
+                return;
+            };
+            let from_partial = length.min(partial.len());
+            partial.drain(..from_partial);
+            reader.advance(length - from_partial);
+
+            match codeword {
+                Codeword::EndOfBlock => self.progress = Progress::Complete,
+                Codeword::Coefficient { run, amplitude } => {
+                    let position = self.next + usize::from(run);
+                    if position >= COEFFICIENT_COUNT {
+                        self.progress = Progress::Overrun;
+                    } else {
+                        self.levels[position] = amplitude;
+                        self.next = position + 1;
+                    }
+                }
+            }
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.progress != Progress::Incomplete
+    }
+
+    /// Dequantize and transform the DCT block into pixels.
+    fn to_pixels(&self, quantization_number: u8) -> BlockPixels {
+        let coefficients =
+            dct::dequantize(&self.levels, self.mode, self.class, quantization_number);
+        dct::inverse_dct(&coefficients, self.mode)
+    }
+}
+
+/// A compressed macroblock that was decoded into pixels.
+struct DecodedMacroblock {
+    /// The four luminance DCT blocks, followed by the Cr and Cb DCT blocks.
+    blocks: [BlockPixels; MACROBLOCK_DCT_BLOCK_COUNT],
+
+    /// Whether any DCT block of the macroblock could not be fully decoded.
+    bitstream_error: bool,
+}
+
+/// Decode the five compressed macroblocks of a video segment.
+///
+/// Each DCT block has a fixed amount of space in the video DIF block.  DCT blocks that need less
+/// space leave room for the overflow of those that need more, which is read in three passes:
+/// 1. Each DCT block is read from its own space.
+/// 2. Unfinished DCT blocks continue with the space left over by other DCT blocks of the same
+///    macroblock.
+/// 3. Unfinished DCT blocks continue with the space left over anywhere in the video segment.
+fn decode_video_segment(
+    segment: &[dif::DIFBlock; VIDEO_SEGMENT_BLOCK_COUNT],
+) -> [DecodedMacroblock; VIDEO_SEGMENT_BLOCK_COUNT] {
+    let mut segment_overflow = Vec::new();
+    let mut macroblocks = Vec::with_capacity(VIDEO_SEGMENT_BLOCK_COUNT);
+    for block in segment {
+        let bits = vlc::to_bits(&block.data[1..]);
+        let mut macroblock_overflow = Vec::new();
+        let mut start = 0;
+        let mut dct_blocks = Vec::with_capacity(MACROBLOCK_DCT_BLOCK_COUNT);
+        for size in DCT_BLOCK_BITS {
+            let space = &bits[start..start + size];
+            start += size;
+            let mut dct_block = DctBlock::from_header(space);
+            let mut reader = BitReader::new(&space[DCT_BLOCK_HEADER_BITS..]);
+            dct_block.read_ac(&mut reader);
+            if dct_block.is_finished() {
+                macroblock_overflow.extend_from_slice(reader.remaining());
+            }
+            dct_blocks.push(dct_block);
+        }
+
+        let mut reader = BitReader::new(&macroblock_overflow);
+        for dct_block in dct_blocks.iter_mut().filter(|b| !b.is_finished()) {
+            dct_block.read_ac(&mut reader);
+        }
+        if dct_blocks.iter().all(DctBlock::is_finished) {
+            segment_overflow.extend_from_slice(reader.remaining());
+        }
+        macroblocks.push(dct_blocks);
+    }
+
+    let mut reader = BitReader::new(&segment_overflow);
+    for dct_block in macroblocks.iter_mut().flatten().filter(|b| !b.is_finished()) {
+        dct_block.read_ac(&mut reader);
+    }
+
+    std::array::from_fn(|m| {
+        let quantization_number =
+            dif::MacroblockHeader::from_raw(&segment[m].data).quantization_number.value();
+        DecodedMacroblock {
+            blocks: std::array::from_fn(|b| macroblocks[m][b].to_pixels(quantization_number)),
+            bitstream_error: macroblocks[m].iter().any(|b| b.progress != Progress::Complete),
+        }
+    })
+}
+
+/// Copy the pixels of a decoded macroblock into the planes of the picture.
+fn put_macroblock(picture: &mut DecodedPicture, area: &MacroblockArea, mb: &DecodedMacroblock) {
+    let parts = placement::dct_block_parts(area, picture.chroma_subsampling);
+    for ((kind, parts), pixels) in parts.iter().zip(&mb.blocks) {
+        let plane = match kind {
+            PlaneKind::Y => &mut picture.y,
+            PlaneKind::Cr => &mut picture.cr,
+            PlaneKind::Cb => &mut picture.cb,
+        };
+        for part in parts {
+            plane.put_part(pixels, part);
+        }
+    }
+}
+
+/// Decode the compressed video of a DV frame into planar YUV pixels.
+///
+/// Every macroblock is decoded, including those that are damaged.  The damage is reported
+/// alongside the pixels, so that restoration steps can decide what to do with them.
+///
+/// Only 25 mbps frames are currently supported.
+pub fn decode_frame(frame: &dif::Frame) -> VideoResult<DecodedPicture> {
+    ensure_whatever!(
+        frame.channels.len() == 1,
+        "Decoding video with {} channels is not supported",
+        frame.channels.len()
+    );
+    let system = frame.file_info.system();
+    let chroma_subsampling = placement::chroma_subsampling(system);
+    let ((width, height), (chroma_width, chroma_height)) = placement::plane_sizes(system);
+    let mut picture = DecodedPicture {
+        system,
+        chroma_subsampling,
+        y: Plane::new(width, height, 0),
+        cb: Plane::new(chroma_width, chroma_height, 0),
+        cr: Plane::new(chroma_width, chroma_height, 0),
+        damage: dif::DamageMap::from_frame(frame),
+        bitstream_errors: Vec::new(),
+    };
+
+    for (dif_sequence, sequence) in frame.channels[0].dif_sequences.iter().enumerate() {
+        for start in (0..VIDEO_BLOCK_COUNT).step_by(VIDEO_SEGMENT_BLOCK_COUNT) {
+            let segment = <&[_; VIDEO_SEGMENT_BLOCK_COUNT]>::try_from(
+                &sequence.video[start..start + VIDEO_SEGMENT_BLOCK_COUNT],
+            )
+            .unwrap();
+            for (m, macroblock) in decode_video_segment(segment).iter().enumerate() {
+                let position = VideoBlockPosition { channel: 0, dif_sequence, block: start + m };
+                let area = picture.macroblock_area(&position);
+                put_macroblock(&mut picture, &area, macroblock);
+                if macroblock.bitstream_error {
+                    picture.bitstream_errors.push(position);
+                }
+            }
+        }
+    }
+    Ok(picture)
+}
@@ -0,0 +1,71 @@
+use googletest::prelude::*;
+
+use super::*;
+use crate::{
+    dif::MacroblockDamage,
+    file::testutil::{read_test_frames, SONY_GOOD_QUALITY},
+    video::dct::BLOCK_DIMENSION,
+};
+
+/// Average absolute difference between horizontally adjacent luminance pixels, split into pairs
+/// that straddle the edge of a DCT block and pairs that are inside a DCT block.
+fn edge_and_inner_differences(plane: &Plane) -> (f64, f64) {
+    let (mut edge, mut inner) = ((0_u32, 0_u32), (0_u32, 0_u32));
+    for y in 0..plane.height {
+        for x in 1..plane.width {
+            let difference = u32::from(plane.get(x, y).abs_diff(plane.get(x - 1, y)));
+            let sum = if x % BLOCK_DIMENSION == 0 { &mut edge } else { &mut inner };
+            *sum = (sum.0 + difference, sum.1 + 1);
+        }
+    }
+    (f64::from(edge.0) / f64::from(edge.1), f64::from(inner.0) / f64::from(inner.1))
+}
+
+#[googletest::test]
+fn test_decode_frame_undamaged() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let picture = decode_frame(&frames[0]).unwrap();
+
+    expect_that!(picture.system, eq(System::Sys525_60));
+    expect_that!(picture.chroma_subsampling, eq(ChromaSubsampling::Yuv411));
+    expect_that!((picture.y.width, picture.y.height), eq((720, 480)));
+    expect_that!((picture.cb.width, picture.cb.height), eq((180, 480)));
+    expect_that!((picture.cr.width, picture.cr.height), eq((180, 480)));
+    expect_that!(picture.bitstream_errors, empty());
+    expect_that!(picture.damage, eq(&dif::DamageMap::from_frame(&frames[0])));
+
+    // The test file is a dim indoor scene.
+    let mean = picture.y.data.iter().map(|p| u64::from(*p)).sum::<u64>() / (720 * 480);
+    expect_that!(mean, ge(40));
+    expect_that!(mean, le(140));
+
+    // If the macroblocks were put in the wrong places, the picture would be discontinuous at the
+    // edges of the DCT blocks.
+    let (edge, inner) = edge_and_inner_differences(&picture.y);
+    expect_that!(edge, lt(inner * 1.5));
+}
+
+#[googletest::test]
+fn test_decode_frame_damaged() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let mut frame = frames[0].clone();
+    frame.channels[0].dif_sequences[4].video[17].data.fill(0xFF);
+    let picture = decode_frame(&frame).unwrap();
+
+    let position = VideoBlockPosition { channel: 0, dif_sequence: 4, block: 17 };
+    expect_that!(picture.bitstream_errors, eq(&vec![position]));
+    expect_that!(picture.damage.get(&position), eq(MacroblockDamage::Missing));
+    expect_that!(
+        picture.macroblock_area(&position),
+        eq(MacroblockArea { x: 448, y: 136, width: 32, height: 8 })
+    );
+
+    // Other macroblocks of the same video segment are not affected.
+    let undamaged = decode_frame(&frames[0]).unwrap();
+    let neighbor = picture.macroblock_area(&VideoBlockPosition { block: 16, ..position });
+    for y in neighbor.y..neighbor.y + neighbor.height {
+        for x in neighbor.x..neighbor.x + neighbor.width {
+            expect_that!(picture.y.get(x, y), eq(undamaged.y.get(x, y)));
+        }
+    }
+}
@@ -0,0 +1,242 @@
+use arbitrary_int::u4;
+use snafu::prelude::*;
+
+use super::{
+    dct::{self, BlockPixels, COEFFICIENT_COUNT},
+    decode::{DCT_BLOCK_BITS, DCT_BLOCK_HEADER_BITS},
+    placement::{self, PlaneKind, MACROBLOCK_DCT_BLOCK_COUNT},
+    vlc, Plane, VideoResult,
+};
+use crate::{
+    dif::{
+        self, DIFBlock, MacroblockHeader, MacroblockStatus, VideoBlockPosition, VIDEO_BLOCK_COUNT,
+        VIDEO_SEGMENT_BLOCK_COUNT,
+    },
+    file::ValidInfoMethods,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Space in bits for the DCT blocks of a compressed macroblock.
+const MACROBLOCK_BITS: usize = 608;
+
+/// Highest quantization number, which gives the finest quantization.
+const MAX_QUANTIZATION_NUMBER: u8 = 15;
+
+/// A DCT block that is being compressed.
+#[derive(Debug)]
+struct EncodedBlock {
+    coefficients: [f64; COEFFICIENT_COUNT],
+    class: u8,
+
+    /// Quantized coefficients in scan order, starting with the DC coefficient.
+    levels: [i16; COEFFICIENT_COUNT],
+
+    /// Compressed AC coefficients, including the end of block codeword.
+    ac: Vec<bool>,
+}
+
+impl EncodedBlock {
+    fn new(pixels: &BlockPixels) -> Self {
+        let coefficients = dct::forward_dct(pixels);
+        Self {
+            class: dct::choose_class(&coefficients),
+            coefficients,
+            levels: [0; COEFFICIENT_COUNT],
+            ac: Vec::new(),
+        }
+    }
+
+    fn quantize(&mut self, quantization_number: u8) {
+        self.levels = dct::quantize(&self.coefficients, self.class, quantization_number);
+        self.ac = vlc::encode_ac(&self.levels);
+    }
+
+    /// Drop the highest frequency non-zero AC coefficient, to save space.  Returns false if
+    /// there were no AC coefficients left to drop.
+    fn drop_last_coefficient(&mut self) -> bool {
+        let Some(last) = self.levels[1..].iter().rposition(|level| *level != 0) else {
+            return false;
+        };
+        self.levels[last + 1] = 0;
+        self.ac = vlc::encode_ac(&self.levels);
+        true
+    }
+
+    /// The DC coefficient, DCT mode, and class number at the start of the space of the block.
+    fn header(&self) -> Vec<bool> {
+        let value = (self.levels[0] as u16 & 0x1FF) << 7 | u16::from(self.class) << 4;
+        vlc::to_bits(&value.to_be_bytes())[..DCT_BLOCK_HEADER_BITS].to_vec()
+    }
+
+    fn total_bits(&self) -> usize {
+        DCT_BLOCK_HEADER_BITS + self.ac.len()
+    }
+}
+
+/// Sequential writer into a list of free ranges of a buffer of bits.
+#[derive(Debug, Default)]
+struct SpaceWriter {
+    /// Free ranges that were not written to yet, as (start, length) pairs.
+    ranges: Vec<(usize, usize)>,
+}
+
+impl SpaceWriter {
+    /// Write as many bits as fit into the free ranges, in order.  Returns the number of bits
+    /// that were written.
+    fn write(&mut self, buffer: &mut [bool], bits: &[bool]) -> usize {
+        let mut written = 0;
+        while written < bits.len() && !self.ranges.is_empty() {
+            let (start, length) = &mut self.ranges[0];
+            let count = (*length).min(bits.len() - written);
+            buffer[*start..*start + count].copy_from_slice(&bits[written..written + count]);
+            written += count;
+            *start += count;
+            *length -= count;
+            if *length == 0 {
+                self.ranges.remove(0);
+            }
+        }
+        written
+    }
+}
+
+/// Choose a quantization number for the video segment, such that the compressed DCT blocks fit
+/// in the space of the segment.
+///
+/// The finest quantization that fits is used.  If the DCT blocks don't fit even with the
+/// coarsest quantization, then high frequency coefficients are dropped from the largest DCT
+/// blocks until they fit.
+fn quantize_video_segment(blocks: &mut [EncodedBlock]) -> u8 {
+    let budget = VIDEO_SEGMENT_BLOCK_COUNT * MACROBLOCK_BITS;
+    let total =
+        |blocks: &[EncodedBlock]| blocks.iter().map(EncodedBlock::total_bits).sum::<usize>();
+    for quantization_number in (0..=MAX_QUANTIZATION_NUMBER).rev() {
+        blocks.iter_mut().for_each(|block| block.quantize(quantization_number));
+        if total(blocks) <= budget {
+            return quantization_number;
+        }
+    }
+    while total(blocks) > budget {
+        let largest = blocks.iter_mut().max_by_key(|block| block.ac.len()).unwrap();
+        if !largest.drop_last_coefficient() {
+            break;
+        }
+    }
+    0
+}
+
+/// Compress the pixels of the five macroblocks of a video segment into the payloads of its video
+/// DIF blocks.
+///
+/// The compressed DCT blocks are distributed so that [`super::decode_frame`] reads them back
+/// in its three passes: DCT blocks that don't fit in their own space overflow first into the
+/// space left over in the same macroblock, and then into the space left over anywhere in the
+/// video segment.
+fn encode_video_segment(
+    pixels: &[[BlockPixels; MACROBLOCK_DCT_BLOCK_COUNT]; VIDEO_SEGMENT_BLOCK_COUNT],
+    segment: &mut [DIFBlock],
+) {
+    let mut blocks: Vec<_> = pixels.iter().flatten().map(EncodedBlock::new).collect();
+    let quantization_number = quantize_video_segment(&mut blocks);
+
+    let mut buffer = vec![false; VIDEO_SEGMENT_BLOCK_COUNT * MACROBLOCK_BITS];
+    let mut segment_space = SpaceWriter::default();
+    let mut segment_overflow = Vec::new();
+    for (m, macroblock) in blocks.chunks(MACROBLOCK_DCT_BLOCK_COUNT).enumerate() {
+        let mut macroblock_space = SpaceWriter::default();
+        let mut overflow = Vec::new();
+        let mut start = m * MACROBLOCK_BITS;
+        for (block, size) in macroblock.iter().zip(DCT_BLOCK_BITS) {
+            let header = block.header();
+            buffer[start..start + header.len()].copy_from_slice(&header);
+            let space = size - header.len();
+            let own = block.ac.len().min(space);
+            buffer[start + header.len()..start + header.len() + own]
+                .copy_from_slice(&block.ac[..own]);
+            if own < space {
+                macroblock_space.ranges.push((start + header.len() + own, space - own));
+            } else {
+                overflow.push(&block.ac[own..]);
+            }
+            start += size;
+        }
+        for bits in overflow {
+            let written = macroblock_space.write(&mut buffer, bits);
+            segment_overflow.push(&bits[written..]);
+        }
+        segment_space.ranges.append(&mut macroblock_space.ranges);
+    }
+    for bits in segment_overflow {
+        let written = segment_space.write(&mut buffer, bits);
+        assert_eq!(written, bits.len(), "compressed video segment does not fit");
+    }
+
+    let header = MacroblockHeader {
+        status: MacroblockStatus::NoError,
+        quantization_number: u4::new(quantization_number),
+    };
+    for (block, bits) in segment.iter_mut().zip(buffer.chunks_exact(MACROBLOCK_BITS)) {
+        header.write_raw(&mut block.data);
+        block.data[1..].copy_from_slice(&vlc::from_bits(bits));
+    }
+}
+
+/// Compress a picture into the video DIF blocks of a DV frame, replacing their payloads.
+///
+/// The planes must have the sizes that the system of the frame expects: 720x480 luminance with
+/// 4:1:1 chroma subsampling for 525-60 systems, and 720x576 luminance with 4:2:0 chroma
+/// subsampling for 625-50 systems.  The IDs of the DIF blocks, and all other sections of the
+/// frame, are left untouched.  The macroblocks are marked as having no errors.
+///
+/// A quantization number is chosen for each video segment so that it fits in the fixed space
+/// available.  Only the 8-8 DCT is used.  Only 25 mbps frames are currently supported.
+pub fn encode_frame(frame: &mut dif::Frame, y: &Plane, cb: &Plane, cr: &Plane) -> VideoResult<()> {
+    ensure_whatever!(
+        frame.channels.len() == 1,
+        "Encoding video with {} channels is not supported",
+        frame.channels.len()
+    );
+    let system = frame.file_info.system();
+    let (luma_size, chroma_size) = placement::plane_sizes(system);
+    for (name, plane, size) in
+        [("Y", y, luma_size), ("Cb", cb, chroma_size), ("Cr", cr, chroma_size)]
+    {
+        ensure_whatever!(
+            (plane.width, plane.height) == size && plane.data.len() == size.0 * size.1,
+            "{name} plane is {}x{}, but {system} pictures need {}x{}",
+            plane.width,
+            plane.height,
+            size.0,
+            size.1
+        );
+    }
+
+    let chroma_subsampling = placement::chroma_subsampling(system);
+    for (dif_sequence, sequence) in frame.channels[0].dif_sequences.iter_mut().enumerate() {
+        for start in (0..VIDEO_BLOCK_COUNT).step_by(VIDEO_SEGMENT_BLOCK_COUNT) {
+            let pixels = std::array::from_fn(|m| {
+                let position = VideoBlockPosition { channel: 0, dif_sequence, block: start + m };
+                let area = placement::macroblock_area(system, &position);
+                placement::dct_block_parts(&area, chroma_subsampling).map(|(kind, parts)| {
+                    let plane = match kind {
+                        PlaneKind::Y => y,
+                        PlaneKind::Cr => cr,
+                        PlaneKind::Cb => cb,
+                    };
+                    let mut pixels = [0; COEFFICIENT_COUNT];
+                    for part in &parts {
+                        plane.take_part(&mut pixels, part);
+                    }
+                    pixels
+                })
+            });
+            encode_video_segment(
+                &pixels,
+                &mut sequence.video[start..start + VIDEO_SEGMENT_BLOCK_COUNT],
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use googletest::prelude::*;
+use rstest::rstest;
+
+use super::*;
+use crate::{
+    file::{
+        testutil::{read_test_frames, synthetic_plane, PAL_SINGLE_FRAME, SONY_GOOD_QUALITY},
+        System, ValidInfo,
+    },
+    video::{decode_frame, ChromaSubsampling},
+};
+
+/// Average absolute difference between the pixels of two planes.
+fn mean_difference(a: &Plane, b: &Plane) -> f64 {
+    let sum: u64 = a.data.iter().zip(&b.data).map(|(a, b)| u64::from(a.abs_diff(*b))).sum();
+    sum as f64 / a.data.len() as f64
+}
+
+#[googletest::test]
+fn test_encode_frame_round_trip() {
+    let frames = read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY);
+    let original = decode_frame(&frames[0]).unwrap();
+
+    let mut frame = frames[0].clone();
+    encode_frame(&mut frame, &original.y, &original.cb, &original.cr).unwrap();
+    let reencoded = decode_frame(&frame).unwrap();
+
+    expect_that!(reencoded.bitstream_errors, empty());
+    expect_that!(reencoded.damage.summary().damaged_percentage(), eq(0.0));
+    expect_that!(mean_difference(&original.y, &reencoded.y), lt(2.0));
+    expect_that!(mean_difference(&original.cb, &reencoded.cb), lt(1.0));
+    expect_that!(mean_difference(&original.cr, &reencoded.cr), lt(1.0));
+
+    // Only the video DIF blocks are changed, and their IDs are kept.
+    let (before, after) =
+        (&frames[0].channels[0].dif_sequences[0], &frame.channels[0].dif_sequences[0]);
+    expect_that!(after.audio, eq(before.audio));
+    expect_that!(after.vaux, eq(before.vaux));
+    expect_that!(after.video[0].id, eq(before.video[0].id));
+    expect_that!(after.video[0].data, not(eq(before.video[0].data)));
+}
+
+#[googletest::test]
+#[rstest]
+#[case::ntsc(&*SONY_GOOD_QUALITY, System::Sys525_60, (720, 480), (180, 480))]
+#[case::pal(&*PAL_SINGLE_FRAME, System::Sys625_50, (720, 576), (360, 288))]
+fn test_encode_frame_synthetic(
+    #[case] file_info: &ValidInfo,
+    #[case] system: System,
+    #[case] luma: (usize, usize),
+    #[case] chroma: (usize, usize),
+) {
+    let size = usize::try_from(file_info.video_frame_size()).unwrap();
+    let mut frame = dif::Frame::from_raw(&vec![0; size], file_info);
+    let y = synthetic_plane(luma.0, luma.1, 0);
+    let cb = synthetic_plane(chroma.0, chroma.1, 50);
+    let cr = synthetic_plane(chroma.0, chroma.1, 100);
+    encode_frame(&mut frame, &y, &cb, &cr).unwrap();
+
+    let picture = decode_frame(&frame).unwrap();
+    expect_that!(picture.system, eq(system));
+    expect_that!(picture.bitstream_errors, empty());
+    expect_that!(picture.y.width, eq(luma.0));
+    expect_that!(picture.cb.height, eq(chroma.1));
+    expect_that!(mean_difference(&y, &picture.y), lt(1.5));
+    expect_that!(mean_difference(&cb, &picture.cb), lt(1.5));
+    expect_that!(mean_difference(&cr, &picture.cr), lt(1.5));
+    if system == System::Sys625_50 {
+        expect_that!(picture.chroma_subsampling, eq(ChromaSubsampling::Yuv420));
+    }
+}
+
+#[googletest::test]
+fn test_encode_frame_flat() {
+    // A flat picture has no AC coefficients, so it is reproduced exactly.
+    let mut frame =
+        read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY).remove(0);
+    let (y, chroma) = (Plane::new(720, 480, 90), Plane::new(180, 480, 128));
+    encode_frame(&mut frame, &y, &chroma, &chroma).unwrap();
+
+    let picture = decode_frame(&frame).unwrap();
+    expect_that!(picture.y, eq(&y));
+    expect_that!(picture.cb, eq(&chroma));
+    expect_that!(picture.cr, eq(&chroma));
+    let headers = frame.macroblock_headers();
+    expect_that!(headers[0].1.quantization_number, eq(u4::new(MAX_QUANTIZATION_NUMBER)));
+}
+
+#[googletest::test]
+fn test_encode_frame_noise() {
+    // Random noise can't be compressed into the available space without dropping
+    // coefficients, but the result must still be decodable.
+    let mut frame =
+        read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY).remove(0);
+    let mut state = 0x1234_5678_u32;
+    let mut noise = |width, height| {
+        let mut plane = Plane::new(width, height, 0);
+        for pixel in &mut plane.data {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            *pixel = state.to_le_bytes()[0];
+        }
+        plane
+    };
+    let (y, cb, cr) = (noise(720, 480), noise(180, 480), noise(180, 480));
+    encode_frame(&mut frame, &y, &cb, &cr).unwrap();
+
+    let picture = decode_frame(&frame).unwrap();
+    expect_that!(picture.bitstream_errors, empty());
+    expect_that!(
+        frame.macroblock_headers().iter().map(|(_, h)| h.quantization_number).collect::<Vec<_>>(),
+        each(eq(&u4::new(0)))
+    );
+}
+
+#[googletest::test]
+fn test_encode_frame_wrong_size() {
+    let mut frame =
+        read_test_frames("dv_multiframe/sony_good_quality.dv", &SONY_GOOD_QUALITY).remove(0);
+    let (y, chroma) = (Plane::new(720, 576, 0), Plane::new(360, 288, 0));
+    expect_that!(
+        encode_frame(&mut frame, &y, &chroma, &chroma).unwrap_err().to_string(),
+        eq("Y plane is 720x576, but 525-60 pictures need 720x480")
+    );
+}
@@ -0,0 +1,55 @@
+//! Native decoding of the compressed video stored in DV frames, as defined in
+//! [IEC 61834-2](https://webstore.iec.ch/en/publication/5984) and other related standards.
+//!
+//! Each frame is compressed on its own, without reference to other frames.  The picture is
+//! divided into macroblocks, each made of four luminance DCT blocks and two chrominance DCT
+//! blocks.  Groups of five macroblocks taken from all over the picture are compressed together
+//! into video segments of five video DIF blocks.  The [`decode_frame`] function reverses all of
+//! this and returns planar YUV pixels: 4:1:1 for 525-60 systems and 4:2:0 for 625-50 systems.
+//!
+//! Unlike FFmpeg, the decoder reports exactly which macroblocks were damaged and where they are
+//! in the picture, which restoration steps need in order to work on the pixels.  Once the pixels
+//! were repaired, [`encode_frame`] compresses them back into the video DIF blocks of the frame.
+
+pub use decode::*;
+pub use encode::*;
+pub use placement::MacroblockArea;
+pub use plane::*;
+use serde::{Deserialize, Serialize};
+use snafu::prelude::*;
+
+mod dct;
+mod decode;
+mod encode;
+mod placement;
+mod plane;
+mod vlc;
+
+/// Chroma subsampling of a decoded picture.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub enum ChromaSubsampling {
+    /// Chrominance planes have a quarter of the horizontal resolution and the full vertical
+    /// resolution.  Used by 525-60 systems.
+    Yuv411,
+
+    /// Chrominance planes have half of the horizontal and vertical resolution.  Used by 625-50
+    /// systems.
+    Yuv420,
+}
+
+/// Result type for calls related to decoding video.
+pub type VideoResult<T, E = VideoError> = std::result::Result<T, E>;
+
+/// Error type for when the video in a DV frame could not be decoded.
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum VideoError {
+    #[snafu(whatever, display("{message}"))]
+    Whatever {
+        message: String,
+        #[snafu(source(from(Box<dyn std::error::Error>, Some)))]
+        source: Option<Box<dyn std::error::Error>>,
+        // There is intentionally not a backtrace here, since they are slow and we could encounter
+        // a lot of these errors when reading bad videotapes.
+    },
+}
@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+
+use super::{dct::BLOCK_DIMENSION, ChromaSubsampling};
+use crate::{dif::VideoBlockPosition, file::System};
+
+#[cfg(test)]
+mod tests;
+
+/// Rectangular area of the picture covered by a macroblock, in luminance pixels.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub struct MacroblockArea {
+    /// Horizontal position of the left edge of the macroblock.
+    pub x: usize,
+
+    /// Vertical position of the top edge of the macroblock.
+    pub y: usize,
+
+    /// Width of the macroblock.  This is 32 pixels for most 525-60 macroblocks, and 16 pixels
+    /// otherwise.
+    pub width: usize,
+
+    /// Height of the macroblock.  This is 8 pixels for most 525-60 macroblocks, and 16 pixels
+    /// otherwise.
+    pub height: usize,
+}
+
+/// Offset of the super block row, relative to the DIF sequence, for each macroblock of a video
+/// segment.
+const SUPER_BLOCK_ROW_OFFSETS: [usize; 5] = [2, 6, 8, 0, 4];
+
+/// Super block column for each macroblock of a video segment.
+const SUPER_BLOCK_COLUMNS: [usize; 5] = [2, 1, 3, 0, 4];
+
+/// Number of DCT blocks in a macroblock: four luminance blocks, followed by one Cr block and one
+/// Cb block.
+pub(super) const MACROBLOCK_DCT_BLOCK_COUNT: usize = 6;
+
+/// Width of the pictures of all supported systems, in luminance pixels.
+const PICTURE_WIDTH: usize = 720;
+
+/// Chroma subsampling of the pictures of the system.
+pub(super) fn chroma_subsampling(system: System) -> ChromaSubsampling {
+    match system {
+        System::Sys525_60 => ChromaSubsampling::Yuv411,
+        System::Sys625_50 => ChromaSubsampling::Yuv420,
+    }
+}
+
+/// Width and height of the luminance plane and of the chrominance planes of the system.
+pub(super) fn plane_sizes(system: System) -> ((usize, usize), (usize, usize)) {
+    match system {
+        System::Sys525_60 => ((PICTURE_WIDTH, 480), (PICTURE_WIDTH / 4, 480)),
+        System::Sys625_50 => ((PICTURE_WIDTH, 576), (PICTURE_WIDTH / 2, 288)),
+    }
+}
+
+/// Plane of the picture that a DCT block belongs to.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(super) enum PlaneKind {
+    Y,
+    Cr,
+    Cb,
+}
+
+/// A rectangular part of a DCT block, along with its location in the plane.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(super) struct BlockPart {
+    /// Position of the top left corner of the part within the DCT block.
+    pub(super) block: (usize, usize),
+
+    /// Width and height of the part.
+    pub(super) size: (usize, usize),
+
+    /// Position of the top left corner of the part within the plane.
+    pub(super) plane: (usize, usize),
+}
+
+/// Returns the location of the pixels of each DCT block of the macroblock covering the area.
+///
+/// Most DCT blocks are a single 8x8 part.  The chrominance of the 16x16 macroblocks of 4:1:1
+/// pictures is 4x16 pixels, so the left half of the DCT block holds the upper half of it, and the
+/// right half of the DCT block holds the lower half.
+pub(super) fn dct_block_parts(
+    area: &MacroblockArea,
+    chroma_subsampling: ChromaSubsampling,
+) -> [(PlaneKind, Vec<BlockPart>); MACROBLOCK_DCT_BLOCK_COUNT] {
+    let whole = |x, y| {
+        vec![BlockPart { block: (0, 0), size: (BLOCK_DIMENSION, BLOCK_DIMENSION), plane: (x, y) }]
+    };
+    let luma = |i: usize| {
+        if area.height == BLOCK_DIMENSION {
+            // The luminance blocks of a 32x8 macroblock are side by side.
+            whole(area.x + i * BLOCK_DIMENSION, area.y)
+        } else {
+            // The luminance blocks of a 16x16 macroblock are in a 2x2 grid.
+            whole(area.x + i % 2 * BLOCK_DIMENSION, area.y + i / 2 * BLOCK_DIMENSION)
+        }
+    };
+    let chroma = || match chroma_subsampling {
+        ChromaSubsampling::Yuv411 if area.height == BLOCK_DIMENSION => whole(area.x / 4, area.y),
+        ChromaSubsampling::Yuv411 => {
+            let size = (BLOCK_DIMENSION / 2, BLOCK_DIMENSION);
+            vec![
+                BlockPart { block: (0, 0), size, plane: (area.x / 4, area.y) },
+                BlockPart {
+                    block: (BLOCK_DIMENSION / 2, 0),
+                    size,
+                    plane: (area.x / 4, area.y + BLOCK_DIMENSION),
+                },
+            ]
+        }
+        ChromaSubsampling::Yuv420 => whole(area.x / 2, area.y / 2),
+    };
+    [
+        (PlaneKind::Y, luma(0)),
+        (PlaneKind::Y, luma(1)),
+        (PlaneKind::Y, luma(2)),
+        (PlaneKind::Y, luma(3)),
+        (PlaneKind::Cr, chroma()),
+        (PlaneKind::Cb, chroma()),
+    ]
+}
+
+/// Returns the area of the picture covered by the macroblock stored at the given position.
+///
+/// The macroblocks of a video segment are taken from five different super blocks spread across
+/// the picture, so that a dropout on the tape damages small parts of the picture in many places
+/// instead of a large contiguous area.  Within a super block, the macroblocks are ordered in a
+/// serpentine pattern going down and up the columns.
+///
+/// - IEC 61834-2:1998 - SD format for 525-60 and 625-50 systems
+pub(super) fn macroblock_area(system: System, position: &VideoBlockPosition) -> MacroblockArea {
+    let macroblock = position.macroblock();
+    let segment = position.video_segment();
+    let column = SUPER_BLOCK_COLUMNS[macroblock];
+    match system {
+        System::Sys525_60 => {
+            let row = (position.dif_sequence + SUPER_BLOCK_ROW_OFFSETS[macroblock]) % 10;
+
+            // Super blocks are 4.5 macroblocks wide and 6 macroblocks tall.  The half column is
+            // made of three macroblocks at the top or the bottom of the column.
+            let index = segment + if column % 2 == 1 { 3 } else { 0 };
+            let x = column * 9 / 2 + index / 6;
+            let serpentine = if (index / 6) % 2 == 0 { index % 6 } else { 5 - index % 6 };
+            if x < 22 {
+                MacroblockArea { x: x * 32, y: (row * 6 + serpentine) * 8, width: 32, height: 8 }
+            } else {
+                // The right edge of the picture is made of square macroblocks.
+                MacroblockArea { x: 704, y: (row * 3 + serpentine) * 16, width: 16, height: 16 }
+            }
+        }
+        System::Sys625_50 => {
+            let row = (position.dif_sequence + SUPER_BLOCK_ROW_OFFSETS[macroblock]) % 12;
+
+            // Super blocks are 9 macroblocks wide and 3 macroblocks tall.
+            let x = column * 9 + segment / 3;
+            let serpentine = if (segment / 3) % 2 == 0 { segment % 3 } else { 2 - segment % 3 };
+            MacroblockArea { x: x * 16, y: (row * 3 + serpentine) * 16, width: 16, height: 16 }
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use googletest::prelude::*;
+use rstest::rstest;
+
+use super::*;
+use crate::dif::VIDEO_BLOCK_COUNT;
+
+#[googletest::test]
+#[rstest]
+#[case::ntsc(System::Sys525_60, 10, 480)]
+#[case::pal(System::Sys625_50, 12, 576)]
+fn test_macroblocks_cover_picture(
+    #[case] system: System,
+    #[case] dif_sequence_count: usize,
+    #[case] height: usize,
+) {
+    let mut coverage = vec![0_u8; 720 * height];
+    for dif_sequence in 0..dif_sequence_count {
+        for block in 0..VIDEO_BLOCK_COUNT {
+            let area =
+                macroblock_area(system, &VideoBlockPosition { channel: 0, dif_sequence, block });
+            for y in area.y..area.y + area.height {
+                for x in area.x..area.x + area.width {
+                    coverage[y * 720 + x] += 1;
+                }
+            }
+        }
+    }
+    expect_that!(coverage, each(eq(&1)));
+}
+
+#[googletest::test]
+#[rstest]
+#[case::ntsc_first(System::Sys525_60, 0, 0, MacroblockArea { x: 288, y: 96, width: 32, height: 8 })]
+#[case::ntsc_serpentine(
+    System::Sys525_60,
+    0,
+    31,
+    MacroblockArea { x: 160, y: 304, width: 32, height: 8 }
+)]
+#[case::ntsc_right_edge(
+    System::Sys525_60,
+    3,
+    134,
+    MacroblockArea { x: 704, y: 368, width: 16, height: 16 }
+)]
+#[case::pal_first(System::Sys625_50, 0, 0, MacroblockArea { x: 288, y: 96, width: 16, height: 16 })]
+#[case::pal_last(
+    System::Sys625_50,
+    11,
+    134,
+    MacroblockArea { x: 704, y: 176, width: 16, height: 16 }
+)]
+fn test_macroblock_area(
+    #[case] system: System,
+    #[case] dif_sequence: usize,
+    #[case] block: usize,
+    #[case] expected: MacroblockArea,
+) {
+    expect_that!(
+        macroblock_area(system, &VideoBlockPosition { channel: 0, dif_sequence, block }),
+        eq(expected)
+    );
+}
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+    dct::{BlockPixels, BLOCK_DIMENSION},
+    placement::BlockPart,
+};
+
+/// A single plane of pixels of a picture.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+pub struct Plane {
+    /// Width of the plane, in pixels.
+    pub width: usize,
+
+    /// Height of the plane, in pixels.
+    pub height: usize,
+
+    /// Pixels of the plane in row-major order, without any padding between rows.
+    pub data: Vec<u8>,
+}
+
+impl Plane {
+    /// Create a plane of the given size, with every pixel set to the given value.
+    pub fn new(width: usize, height: usize, value: u8) -> Self {
+        Self { width, height, data: vec![value; width * height] }
+    }
+
+    /// Returns the pixel at the given position.
+    ///
+    /// The function will panic if the position is outside the plane.
+    pub fn get(&self, x: usize, y: usize) -> u8 {
+        assert!(x < self.width && y < self.height);
+        self.data[y * self.width + x]
+    }
+
+    /// Copy part of a DCT block into the plane.
+    pub(super) fn put_part(&mut self, pixels: &BlockPixels, part: &BlockPart) {
+        for row in 0..part.size.1 {
+            let source = (part.block.1 + row) * BLOCK_DIMENSION + part.block.0;
+            let destination = (part.plane.1 + row) * self.width + part.plane.0;
+            self.data[destination..destination + part.size.0]
+                .copy_from_slice(&pixels[source..source + part.size.0]);
+        }
+    }
+
+    /// Copy part of the plane into a DCT block.
+    pub(super) fn take_part(&self, pixels: &mut BlockPixels, part: &BlockPart) {
+        for row in 0..part.size.1 {
+            let source = (part.plane.1 + row) * self.width + part.plane.0;
+            let destination = (part.block.1 + row) * BLOCK_DIMENSION + part.block.0;
+            pixels[destination..destination + part.size.0]
+                .copy_from_slice(&self.data[source..source + part.size.0]);
+        }
+    }
+}
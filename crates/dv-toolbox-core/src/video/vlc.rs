@@ -0,0 +1,265 @@
+#[cfg(test)]
+mod tests;
+
+/// A single variable-length codeword of the AC coefficients of a DCT block.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(super) enum Codeword {
+    /// A coefficient preceded by a run of zero coefficients.  The amplitude can also be zero, in
+    /// which case the codeword only advances the scan position.
+    Coefficient { run: u8, amplitude: i16 },
+
+    /// There are no more non-zero coefficients in the DCT block.
+    EndOfBlock,
+}
+
+/// Length in bits of the longest codeword, including its sign bit.
+pub(super) const MAX_CODEWORD_LENGTH: usize = 16;
+
+/// End of block codeword: `0110`.
+const END_OF_BLOCK: (usize, u16) = (4, 0b0110);
+
+/// Codewords for a range of consecutive codes of the same length.
+struct CodeGroup {
+    /// Length of the codes in bits, not including the sign bit.
+    length: usize,
+
+    /// Code of the first (run, amplitude) pair.
+    first_code: u16,
+
+    /// (run, amplitude) pairs for each code of the range.
+    pairs: &'static [(u8, u8)],
+}
+
+/// Codewords for (run, amplitude) pairs, grouped by length.  A sign bit follows the codeword if
+/// the amplitude is not zero.
+///
+/// - IEC 61834-2:1998 - SD format for 525-60 and 625-50 systems
+const CODE_GROUPS: [CodeGroup; 11] = [
+    CodeGroup { length: 2, first_code: 0b00, pairs: &[(0, 1)] },
+    CodeGroup { length: 3, first_code: 0b010, pairs: &[(0, 2)] },
+    CodeGroup { length: 4, first_code: 0b0111, pairs: &[(1, 1), (0, 3), (0, 4)] },
+    CodeGroup { length: 5, first_code: 0b10100, pairs: &[(2, 1), (1, 2), (0, 5), (0, 6)] },
+    CodeGroup { length: 6, first_code: 0b110000, pairs: &[(3, 1), (4, 1), (0, 7), (0, 8)] },
+    CodeGroup {
+        length: 7,
+        first_code: 0b1101000,
+        pairs: &[(5, 1), (6, 1), (2, 2), (1, 3), (1, 4), (0, 9), (0, 10), (0, 11)],
+    },
+    CodeGroup {
+        length: 8,
+        first_code: 0b1110_0000,
+        pairs: &[
+            (7, 1),
+            (8, 1),
+            (9, 1),
+            (10, 1),
+            (3, 2),
+            (4, 2),
+            (2, 3),
+            (1, 5),
+            (1, 6),
+            (1, 7),
+            (0, 12),
+            (0, 13),
+            (0, 14),
+            (0, 15),
+            (0, 16),
+            (0, 17),
+        ],
+    },
+    CodeGroup {
+        length: 9,
+        first_code: 0b1_1110_0000,
+        pairs: &[
+            (11, 1),
+            (12, 1),
+            (13, 1),
+            (14, 1),
+            (5, 2),
+            (6, 2),
+            (3, 3),
+            (4, 3),
+            (2, 4),
+            (2, 5),
+            (1, 8),
+            (0, 18),
+            (0, 19),
+            (0, 20),
+            (0, 21),
+            (0, 22),
+        ],
+    },
+    CodeGroup {
+        length: 10,
+        first_code: 0b11_1110_0000,
+        pairs: &[(5, 3), (3, 4), (3, 5), (2, 6), (1, 9), (1, 10), (1, 11)],
+    },
+    CodeGroup {
+        length: 11,
+        first_code: 0b111_1100_1110,
+        pairs: &[(0, 0), (1, 0), (6, 3), (4, 4), (3, 6), (1, 12), (1, 13), (1, 14)],
+    },
+    CodeGroup {
+        length: 12,
+        first_code: 0b1111_1010_1100,
+        pairs: &[
+            (2, 0),
+            (3, 0),
+            (4, 0),
+            (5, 0),
+            (7, 2),
+            (8, 2),
+            (9, 2),
+            (10, 2),
+            (7, 3),
+            (8, 3),
+            (4, 5),
+            (3, 7),
+            (2, 7),
+            (2, 8),
+            (2, 9),
+            (2, 10),
+            (2, 11),
+            (1, 15),
+            (1, 16),
+            (1, 17),
+        ],
+    },
+];
+
+/// Prefix of the 13-bit codewords that hold a 6-bit run with an amplitude of zero.
+const LONG_RUN_PREFIX: u16 = 0b111_1110;
+
+/// Prefix of the 15-bit codewords that hold an 8-bit amplitude with a run of zero.
+const LONG_AMPLITUDE_PREFIX: u16 = 0b111_1111;
+
+/// Look up a codeword of the given length, not including the sign bit.
+fn lookup(length: usize, code: u16) -> Option<(u8, u8)> {
+    match length {
+        13 if code >> 6 == LONG_RUN_PREFIX => Some((u8::try_from(code & 0x3F).unwrap(), 0)),
+        15 if code >> 8 == LONG_AMPLITUDE_PREFIX => Some((0, u8::try_from(code & 0xFF).unwrap())),
+        _ => CODE_GROUPS.iter().filter(|group| group.length == length).find_map(|group| {
+            let index = code.checked_sub(group.first_code)?;
+            group.pairs.get(usize::from(index)).copied()
+        }),
+    }
+}
+
+/// Decode the codeword at the start of the bits.
+///
+/// Returns the codeword along with its length in bits, including the sign bit.  [`None`] is
+/// returned if the bits end before a complete codeword was read.  Since the code is complete,
+/// this can't happen if at least [`MAX_CODEWORD_LENGTH`] bits are given.
+pub(super) fn decode_codeword(bits: &[bool]) -> Option<(Codeword, usize)> {
+    let mut code = 0_u16;
+    for (index, bit) in bits.iter().take(MAX_CODEWORD_LENGTH - 1).enumerate() {
+        code = code << 1 | u16::from(*bit);
+        let length = index + 1;
+        if (length, code) == END_OF_BLOCK {
+            return Some((Codeword::EndOfBlock, length));
+        }
+        if let Some((run, amplitude)) = lookup(length, code) {
+            if amplitude == 0 {
+                return Some((Codeword::Coefficient { run, amplitude: 0 }, length));
+            }
+            let negative = *bits.get(length)?;
+            let amplitude = if negative { -i16::from(amplitude) } else { i16::from(amplitude) };
+            return Some((Codeword::Coefficient { run, amplitude }, length + 1));
+        }
+    }
+    None
+}
+
+/// Append the bits of a code of the given length, most significant bit first.
+fn push_code(bits: &mut Vec<bool>, length: usize, code: u16) {
+    bits.extend((0..length).rev().map(|i| code >> i & 1 == 1));
+}
+
+/// Look up the code and its length for a (run, amplitude) pair, not including the sign bit.
+fn find_code(run: u8, amplitude: u8) -> Option<(usize, u16)> {
+    CODE_GROUPS.iter().find_map(|group| {
+        let index = group.pairs.iter().position(|pair| *pair == (run, amplitude))?;
+        Some((group.length, group.first_code + u16::try_from(index).unwrap()))
+    })
+}
+
+/// Append the codeword for a coefficient preceded by a run of zero coefficients.
+fn push_coefficient(bits: &mut Vec<bool>, run: u8, amplitude: i16) {
+    let magnitude = u8::try_from(amplitude.unsigned_abs()).expect("amplitude is out of range");
+    match find_code(run, magnitude) {
+        Some((length, code)) => push_code(bits, length, code),
+        None if run == 0 => push_code(bits, 15, LONG_AMPLITUDE_PREFIX << 8 | u16::from(magnitude)),
+        None => {
+            // A codeword with an amplitude of zero skips its run, plus its own coefficient.  Use
+            // one to skip the run, followed by a codeword without a run.
+            let skip = run - 1;
+            match find_code(skip, 0) {
+                Some((length, code)) => push_code(bits, length, code),
+                None => push_code(bits, 13, LONG_RUN_PREFIX << 6 | u16::from(skip)),
+            }
+            push_coefficient(bits, 0, amplitude);
+            return;
+        }
+    }
+    if magnitude != 0 {
+        bits.push(amplitude < 0);
+    }
+}
+
+/// Encode the AC coefficients of a DCT block, followed by the end of block codeword.
+///
+/// The `levels` are the quantized coefficients in scan order, starting with the DC coefficient,
+/// which is not encoded.
+pub(super) fn encode_ac(levels: &[i16]) -> Vec<bool> {
+    let mut bits = Vec::new();
+    let mut run = 0;
+    for level in &levels[1..] {
+        if *level == 0 {
+            run += 1;
+        } else {
+            push_coefficient(&mut bits, run, *level);
+            run = 0;
+        }
+    }
+    push_code(&mut bits, END_OF_BLOCK.0, END_OF_BLOCK.1);
+    bits
+}
+
+/// Pack bits into bytes, most significant bit first.  The last byte is padded with zero bits.
+pub(super) fn from_bits(bits: &[bool]) -> Vec<u8> {
+    bits.chunks(8)
+        .map(|chunk| {
+            chunk.iter().enumerate().fold(0, |byte, (i, bit)| byte | u8::from(*bit) << (7 - i))
+        })
+        .collect()
+}
+
+/// Expand bytes into individual bits, most significant bit first.
+pub(super) fn to_bits(bytes: &[u8]) -> Vec<bool> {
+    bytes.iter().flat_map(|byte| (0..8).rev().map(move |i| byte >> i & 1 == 1)).collect()
+}
+
+/// Sequential reader over a buffer of bits.
+#[derive(Debug)]
+pub(super) struct BitReader<'a> {
+    bits: &'a [bool],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    /// Start reading at the beginning of the bits.
+    pub(super) fn new(bits: &'a [bool]) -> Self {
+        Self { bits, position: 0 }
+    }
+
+    /// Bits that have not been consumed yet.
+    pub(super) fn remaining(&self) -> &'a [bool] {
+        &self.bits[self.position..]
+    }
+
+    /// Consume the given number of bits.
+    pub(super) fn advance(&mut self, count: usize) {
+        assert!(count <= self.bits.len() - self.position);
+        self.position += count;
+    }
+}
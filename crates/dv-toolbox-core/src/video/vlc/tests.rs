@@ -0,0 +1,104 @@
+use googletest::prelude::*;
+use rstest::rstest;
+
+use super::*;
+
+fn bits(value: &str) -> Vec<bool> {
+    value.chars().filter(|c| *c != ' ').map(|c| c == '1').collect()
+}
+
+#[googletest::test]
+#[rstest]
+#[case::shortest_positive("00 0", Codeword::Coefficient { run: 0, amplitude: 1 }, 3)]
+#[case::shortest_negative("00 1", Codeword::Coefficient { run: 0, amplitude: -1 }, 3)]
+#[case::end_of_block("0110", Codeword::EndOfBlock, 4)]
+#[case::trailing_bits("0111 1 0101", Codeword::Coefficient { run: 1, amplitude: -1 }, 5)]
+#[case::nine_bits("111101010 0", Codeword::Coefficient { run: 1, amplitude: 8 }, 10)]
+#[case::zero_amplitude("11111001111", Codeword::Coefficient { run: 1, amplitude: 0 }, 11)]
+#[case::twelve_bits("111110111111 1", Codeword::Coefficient { run: 1, amplitude: -17 }, 13)]
+#[case::long_run("1111110 111101", Codeword::Coefficient { run: 61, amplitude: 0 }, 13)]
+#[case::long_amplitude(
+    "1111111 11111111 1",
+    Codeword::Coefficient { run: 0, amplitude: -255 },
+    16
+)]
+fn test_decode_codeword(#[case] value: &str, #[case] codeword: Codeword, #[case] length: usize) {
+    expect_that!(decode_codeword(&bits(value)), some(eq((codeword, length))));
+}
+
+#[googletest::test]
+#[rstest]
+#[case::empty("")]
+#[case::missing_sign("00")]
+#[case::truncated("1111111 1111")]
+fn test_decode_codeword_incomplete(#[case] value: &str) {
+    expect_that!(decode_codeword(&bits(value)), none());
+}
+
+#[googletest::test]
+fn test_decode_codeword_complete() {
+    // The code is complete, so that any sequence of bits that is long enough starts with a
+    // codeword.
+    for value in 0..=u16::MAX {
+        let bits = to_bits(&value.to_be_bytes());
+        expect_that!(decode_codeword(&bits), some(anything()), "bits {value:016b}");
+    }
+}
+
+#[googletest::test]
+fn test_bit_reader() {
+    let bits = to_bits(&[0b1010_0000, 0xFF]);
+    expect_that!(bits.len(), eq(16));
+    let mut reader = BitReader::new(&bits);
+    expect_that!(reader.remaining()[..4], eq([true, false, true, false]));
+    reader.advance(7);
+    expect_that!(reader.remaining(), eq(&[false, true, true, true, true, true, true, true, true]));
+    reader.advance(9);
+    expect_that!(reader.remaining(), empty());
+}
+
+#[googletest::test]
+#[rstest]
+#[case::empty(&[], "0110")]
+#[case::short(&[(0, 1), (1, -2)], "00 0 10101 1 0110")]
+#[case::long_amplitude(&[(0, -100)], "1111111 01100100 1 0110")]
+#[case::long_run_no_code(&[(20, 1)], "1111110 010011 00 0 0110")]
+#[case::short_run_no_code(&[(1, 30)], "11111001110 1111111 00011110 0 0110")]
+fn test_encode_ac(#[case] coefficients: &[(usize, i16)], #[case] expected: &str) {
+    let mut levels = [0; 64];
+    let mut next = 1;
+    for (run, level) in coefficients {
+        levels[next + run] = *level;
+        next += run + 1;
+    }
+    expect_that!(encode_ac(&levels), eq(&bits(expected)));
+}
+
+#[googletest::test]
+fn test_encode_ac_round_trip() {
+    let mut levels = [0; 64];
+    for (i, level) in levels.iter_mut().enumerate().skip(1).step_by(3) {
+        *level = i16::try_from(i * i).unwrap() * if i % 2 == 0 { 1 } else { -1 } % 256;
+    }
+    levels[63] = 1;
+
+    let bits = encode_ac(&levels);
+    let mut reader = BitReader::new(&bits);
+    let mut decoded = [0; 64];
+    let mut next = 1;
+    while let Some((codeword, length)) = decode_codeword(reader.remaining()) {
+        reader.advance(length);
+        let Codeword::Coefficient { run, amplitude } = codeword else {
+            break;
+        };
+        decoded[next + usize::from(run)] = amplitude;
+        next += usize::from(run) + 1;
+    }
+    expect_that!(decoded, eq(levels));
+    expect_that!(reader.remaining(), empty());
+}
+
+#[googletest::test]
+fn test_from_bits() {
+    expect_that!(from_bits(&bits("1010 0000 1111 1")), eq(&[0b1010_0000, 0b1111_1000]));
+}